@@ -2,17 +2,22 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use std::sync::{mpsc, Arc};
 
 use bip32::DerivationPath;
+use rand::{rngs::StdRng, SeedableRng};
 use vanity_miner::core::executor::Executor;
 use vanity_miner::{
-    chains::EthereumChain,
+    chains::{ConfluxChain, EthereumChain},
     core::{
+        cancel::CancellationToken,
         chain::Chain,
-        config::{AddressConfig, ChainConfig, Encoding, GenerationMode, MiningConfig},
+        config::{
+            AddressConfig, ChainConfig, Encoding, GenerationMode, MiningConfig, MiningTarget,
+        },
         matcher::Matcher,
     },
     executors::CpuExecutor,
     matchers::RegexMatcher,
     stats::MiningStats,
+    utils::keccak,
 };
 
 const PRIVATE_ATTEMPTS_PER_ITER: u64 = 500;
@@ -35,16 +40,23 @@ fn cpu_executor_private_key_benchmark(c: &mut Criterion) {
             };
 
             let config = MiningConfig {
-                chain,
-                matcher,
+                targets: vec![MiningTarget {
+                    name: None,
+                    chain,
+                    matcher,
+                    address_config,
+                    limit: 0,
+                }],
                 generation_mode: GenerationMode::PrivateKey,
-                address_config,
+                seed: None,
+                checkpoint: None,
                 max_attempts: PRIVATE_ATTEMPTS_PER_ITER,
-                limit: 0,
+                deadline: None,
                 result_sender: tx,
+                cancel: CancellationToken::new(),
             };
 
-            let result = executor.execute(config);
+            let result = executor.execute(config).expect("mining run");
             criterion::black_box(result);
         })
     });
@@ -66,30 +78,116 @@ fn cpu_executor_mnemonic_benchmark(c: &mut Criterion) {
             };
 
             let config = MiningConfig {
-                chain,
-                matcher,
+                targets: vec![MiningTarget {
+                    name: None,
+                    chain,
+                    matcher,
+                    address_config,
+                    limit: 0,
+                }],
                 generation_mode: GenerationMode::Mnemonic {
                     word_count: 12,
                     derivation_path: "m/44'/60'/0'/0/0"
                         .parse::<DerivationPath>()
                         .expect("valid derivation path"),
                 },
-                address_config,
+                seed: None,
+                checkpoint: None,
                 max_attempts: MNEMONIC_ATTEMPTS_PER_ITER,
-                limit: 0,
+                deadline: None,
                 result_sender: tx,
+                cancel: CancellationToken::new(),
             };
 
-            let result = executor.execute(config);
+            let result = executor.execute(config).expect("mining run");
             criterion::black_box(result);
         })
     });
 }
 
+/// Per-attempt work after key generation: address, display form, match.
+fn address_pipeline_benchmark(c: &mut Criterion) {
+    let chains: [(&str, Arc<dyn Chain>, AddressConfig); 2] = [
+        (
+            "eth_checksum",
+            Arc::new(EthereumChain::new()),
+            AddressConfig::new(
+                Encoding::HexChecksum,
+                ChainConfig::Ethereum { checksum: true },
+            ),
+        ),
+        (
+            "cfx_base32",
+            Arc::new(ConfluxChain::new()),
+            AddressConfig::new(Encoding::Base32, ChainConfig::Conflux { network_id: 1029 }),
+        ),
+    ];
+    let matcher = RegexMatcher::new("^dead").expect("valid regex");
+
+    for (name, chain, address_config) in chains {
+        let keypair = chain
+            .generate_keypair(&mut StdRng::seed_from_u64(1))
+            .expect("keypair");
+        let mut formatted = String::with_capacity(64);
+
+        c.bench_function(&format!("address_pipeline_{name}"), |b| {
+            b.iter(|| {
+                let address = chain
+                    .compute_address(keypair.public_key())
+                    .expect("address");
+                formatted.clear();
+                chain
+                    .format_address_into(&address, &address_config, &mut formatted)
+                    .expect("format");
+                criterion::black_box(matcher.matches(&formatted))
+            })
+        });
+    }
+}
+
+fn keypair_generation_benchmark(c: &mut Criterion) {
+    let chain = EthereumChain::new();
+    let mut rng = StdRng::seed_from_u64(1);
+    c.bench_function("generate_keypair_eth", |b| {
+        b.iter(|| criterion::black_box(chain.generate_keypair(&mut rng).expect("keypair")))
+    });
+}
+
+fn keccak_batch_benchmark(c: &mut Criterion) {
+    let inputs = [[0x5au8; 64]; keccak::MAX_LANES];
+    let inputs: Vec<&[u8]> = inputs.iter().map(|i| &i[..]).collect();
+    let mut out = [[0u8; 32]; keccak::MAX_LANES];
+
+    c.bench_function("keccak256_single_x4", |b| {
+        b.iter(|| {
+            for (input, hash) in inputs.iter().zip(out.iter_mut()) {
+                *hash = keccak::keccak256(input);
+            }
+            criterion::black_box(&out);
+        })
+    });
+    c.bench_function(
+        &format!("keccak256_batch_x4_{:?}", keccak::Backend::detect()),
+        |b| {
+            b.iter(|| {
+                keccak::keccak256_batch(&inputs, &mut out);
+                criterion::black_box(&out);
+            })
+        },
+    );
+}
+
+criterion_group!(
+    hot_path_group,
+    address_pipeline_benchmark,
+    keypair_generation_benchmark,
+    keccak_batch_benchmark
+);
+
 criterion_group!(
     cpu_executor_group,
     cpu_executor_private_key_benchmark,
     cpu_executor_mnemonic_benchmark
 );
 
-criterion_main!(cpu_executor_group);
+criterion_main!(hot_path_group, cpu_executor_group);
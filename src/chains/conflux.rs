@@ -3,17 +3,26 @@ use bip39::Mnemonic;
 use rand::RngCore;
 use secp256k1::{PublicKey as SecpPubkey, Secp256k1, SecretKey};
 use sha3::{Digest, Keccak256};
-
-use crate::core::{
-    chain::Chain,
-    config::{AddressConfig, ChainConfig},
-    types::{Address, KeyPair, PublicKey, Result, VanityError},
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    core::{
+        chain::Chain,
+        config::{AddressConfig, ChainConfig},
+        secret::Secret,
+        types::{Address, KeyPair, PublicKey, Result, VanityError, SECP256K1_PUBLIC_LEN},
+    },
+    utils::keccak,
 };
 
 // Conflux address encoding constants.
 const CFX_VERSION_BYTE: u8 = 0x00;
 const CFX_BIT_MASK_5: u8 = 0x1F;
 const CFX_BASE32_CHECKSUM_LEN: usize = 8;
+/// Version byte plus the 20-byte address.
+const CFX_PAYLOAD_LEN: usize = 21;
+/// 5-bit groups needed for the payload.
+const CFX_PAYLOAD_GROUPS: usize = (CFX_PAYLOAD_LEN * 8).div_ceil(5);
 
 const CFX_POLY_COEFFS: [u64; 5] = [
     0x98f2bc8e61,
@@ -35,19 +44,26 @@ impl ConfluxChain {
         }
     }
 
-    fn public_key_bytes(public_key: &PublicKey) -> Result<Vec<u8>> {
-        match public_key {
-            PublicKey::Secp256k1(bytes) => Ok(bytes.clone()),
-            _ => Err(VanityError::CryptoError(
-                "Conflux only supports secp256k1 keys".into(),
-            )),
+    /// The 64 bytes of an uncompressed secp256k1 key that get hashed.
+    fn hashed_key_bytes(public_key: PublicKey<'_>) -> Result<&[u8]> {
+        let bytes = match public_key {
+            PublicKey::Secp256k1(bytes) => bytes,
+            _ => {
+                return Err(VanityError::CryptoError(
+                    "Conflux only supports secp256k1 keys".into(),
+                ))
+            }
+        };
+        if bytes.len() != SECP256K1_PUBLIC_LEN || bytes[0] != 0x04 {
+            return Err(VanityError::CryptoError(
+                "expected a 65-byte uncompressed secp256k1 public key".into(),
+            ));
         }
+        Ok(&bytes[1..])
     }
 
-    fn derive_pubkey_bytes(&self, secret_key: &SecretKey) -> Vec<u8> {
-        SecpPubkey::from_secret_key(&self.secp, secret_key)
-            .serialize_uncompressed()
-            .to_vec()
+    fn derive_pubkey_bytes(&self, secret_key: &SecretKey) -> [u8; SECP256K1_PUBLIC_LEN] {
+        SecpPubkey::from_secret_key(&self.secp, secret_key).serialize_uncompressed()
     }
 
     fn keccak256(data: &[u8]) -> [u8; 32] {
@@ -56,12 +72,8 @@ impl ConfluxChain {
         hasher.finalize().into()
     }
 
-    fn raw20_from_public(&self, public_key: &PublicKey) -> Result<[u8; 20]> {
-        let bytes = Self::public_key_bytes(public_key)?;
-        debug_assert_eq!(bytes.len(), 65);
-        debug_assert_eq!(bytes[0], 0x04);
-
-        let hash = Self::keccak256(&bytes[1..]);
+    fn raw20_from_public(&self, public_key: PublicKey<'_>) -> Result<[u8; 20]> {
+        let hash = Self::keccak256(Self::hashed_key_bytes(public_key)?);
         Ok(hash[12..].try_into().expect("slice to 20 bytes"))
     }
 
@@ -69,62 +81,54 @@ impl ConfluxChain {
         b"abcdefghjkmnprstuvwxyz0123456789"
     }
 
-    /// Derives network prefix string from network id.
+    /// Appends the network prefix for a network id.
     /// 1029 -> "cfx", 1 -> "cfxtest", others -> "net{n}".
-    fn network_prefix(network_id: u32) -> String {
+    fn push_network_prefix(network_id: u32, out: &mut String) {
         match network_id {
-            1029 => "cfx".to_string(),
-            1 => "cfxtest".to_string(),
-            n => format!("net{}", n),
+            1029 => out.push_str("cfx"),
+            1 => out.push_str("cfxtest"),
+            n => {
+                out.push_str("net");
+                out.push_str(itoa_u32(n, &mut [0; 10]));
+            }
         }
     }
 
-    fn encode_base32(raw20: &[u8; 20], network_id: u32) -> String {
-        // normalize high nibble of the first byte to 0x1 (it means user address)
-        let modified = *raw20;
+    fn encode_base32_into(raw20: &[u8; 20], network_id: u32, out: &mut String) {
+        let prefix_start = out.len();
+        Self::push_network_prefix(network_id, out);
 
-        // build payload = [version byte || modified_address]
-        let mut payload = Vec::with_capacity(1 + modified.len());
-        payload.push(CFX_VERSION_BYTE);
-        payload.extend_from_slice(&modified);
+        // build payload = [version byte || address]
+        let mut payload = [0u8; CFX_PAYLOAD_LEN];
+        payload[0] = CFX_VERSION_BYTE;
+        payload[1..].copy_from_slice(raw20);
 
         // convert payload from 8-bit bytes to 5-bit bytes
         let payload_5_bits = Self::convert_bits_8_to_5(&payload);
 
-        // expand prefix to 5-bite values and build checksum input
-        let prefix = Self::network_prefix(network_id);
-        let expanded_prefix: Vec<u8> = prefix.chars().map(|c| (c as u8) & CFX_BIT_MASK_5).collect();
-
-        let checksum_input_len =
-            expanded_prefix.len() + 1 + payload_5_bits.len() + CFX_BASE32_CHECKSUM_LEN;
-        let mut checksum_input = Vec::with_capacity(checksum_input_len);
-        checksum_input.extend_from_slice(&expanded_prefix);
-        checksum_input.push(0);
-        checksum_input.extend_from_slice(&payload_5_bits);
-        checksum_input.extend_from_slice(&[0u8; CFX_BASE32_CHECKSUM_LEN]);
-
-        let checksum = Self::calculate_checksum(&checksum_input);
+        // checksum input = 5-bit prefix || 0 || payload || 8 zero groups
+        let checksum = Self::calculate_checksum(
+            out.as_bytes()[prefix_start..]
+                .iter()
+                .map(|&c| c & CFX_BIT_MASK_5)
+                .chain([0])
+                .chain(payload_5_bits)
+                .chain([0; CFX_BASE32_CHECKSUM_LEN]),
+        );
 
         let charset = Self::base32_charset();
 
-        let payload_str: String = payload_5_bits
-            .iter()
-            .map(|&b| charset[b as usize] as char)
-            .collect();
-
-        let checksum_str: String = (0..CFX_BASE32_CHECKSUM_LEN)
-            .rev()
-            .map(|i| {
-                let index = ((checksum >> (i * 5)) & CFX_BIT_MASK_5 as u64) as usize;
-                charset[index] as char
-            })
-            .collect();
-
-        format!("{}:{}{}", prefix, payload_str, checksum_str)
+        out.push(':');
+        out.extend(payload_5_bits.iter().map(|&b| charset[b as usize] as char));
+        out.extend((0..CFX_BASE32_CHECKSUM_LEN).rev().map(|i| {
+            let index = ((checksum >> (i * 5)) & CFX_BIT_MASK_5 as u64) as usize;
+            charset[index] as char
+        }));
     }
 
-    fn convert_bits_8_to_5(data: &[u8]) -> Vec<u8> {
-        let mut result = Vec::with_capacity((data.len() * 8 + 4) / 5);
+    fn convert_bits_8_to_5(data: &[u8; CFX_PAYLOAD_LEN]) -> [u8; CFX_PAYLOAD_GROUPS] {
+        let mut result = [0u8; CFX_PAYLOAD_GROUPS];
+        let mut groups = result.iter_mut();
         let mut acc: u32 = 0;
         let mut bits: u8 = 0;
 
@@ -135,21 +139,21 @@ impl ConfluxChain {
             while bits >= 5 {
                 bits -= 5;
                 let value = ((acc >> bits) & 0x1F) as u8;
-                result.push(value);
+                *groups.next().expect("group count fits the payload") = value;
             }
         }
 
         if bits > 0 {
             let value = ((acc << (5 - bits)) & 0x1F) as u8;
-            result.push(value);
+            *groups.next().expect("group count fits the payload") = value;
         }
 
         result
     }
 
-    fn calculate_checksum(data: &[u8]) -> u64 {
+    fn calculate_checksum(data: impl IntoIterator<Item = u8>) -> u64 {
         let mut c: u64 = 1;
-        for &d in data {
+        for d in data {
             let c0 = (c >> 35) as u8;
             c = ((c & 0x07ffffffff) << 5) ^ u64::from(d);
 
@@ -163,6 +167,12 @@ impl ConfluxChain {
     }
 }
 
+impl Default for ConfluxChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Chain for ConfluxChain {
     fn id(&self) -> &str {
         "conflux"
@@ -175,17 +185,21 @@ impl Chain for ConfluxChain {
     fn generate_keypair(&self, rng: &mut dyn RngCore) -> Result<KeyPair> {
         let mut secret_bytes = [0u8; 32];
 
-        let secret = loop {
+        let mut secret = loop {
             rng.fill_bytes(&mut secret_bytes);
             if let Ok(sec) = SecretKey::from_byte_array(&secret_bytes) {
                 break sec;
             }
         };
+        secret_bytes.zeroize();
 
         let public = self.derive_pubkey_bytes(&secret);
+        let key = Secret::new(secret.secret_bytes());
+
+        secret.non_secure_erase();
 
         Ok(KeyPair::Secp256k1 {
-            secret: secret.secret_bytes(),
+            secret: key,
             public,
             mnemonic: None,
             derivation_path: None,
@@ -193,23 +207,26 @@ impl Chain for ConfluxChain {
     }
 
     fn derive_from_mnemonic(&self, mnemonic: &Mnemonic, path: &DerivationPath) -> Result<KeyPair> {
-        let seed = mnemonic.to_seed("");
+        let seed = Zeroizing::new(mnemonic.to_seed(""));
 
-        let xprv = XPrv::derive_from_path(seed, path)
+        let xprv = XPrv::derive_from_path(seed.as_slice(), path)
             .map_err(|e| VanityError::CryptoError(e.to_string()))?;
 
         let child_key = xprv.private_key();
-        let secret_bytes = child_key.to_bytes();
+        let secret_bytes = Zeroizing::new(<[u8; 32]>::from(child_key.to_bytes()));
 
-        let secret = SecretKey::from_slice(&secret_bytes)
+        let mut secret = SecretKey::from_slice(&*secret_bytes)
             .map_err(|e| VanityError::CryptoError(e.to_string()))?;
 
         let public = self.derive_pubkey_bytes(&secret);
+        let key = Secret::new(secret.secret_bytes());
+
+        secret.non_secure_erase();
 
         Ok(KeyPair::Secp256k1 {
-            secret: secret.secret_bytes(),
+            secret: key,
             public,
-            mnemonic: Some(mnemonic.clone()),
+            mnemonic: Some(Secret::new(mnemonic.clone())),
             derivation_path: Some(path.clone()),
         })
     }
@@ -219,44 +236,75 @@ impl Chain for ConfluxChain {
             return Err(VanityError::CryptoError("invalid secret length".into()));
         }
 
-        let secret_key = SecretKey::from_slice(secret)
+        let mut secret_key = SecretKey::from_slice(secret)
             .map_err(|e| VanityError::CryptoError(format!("invalid secret: {}", e)))?;
 
         let public = self.derive_pubkey_bytes(&secret_key);
+        let key = Secret::new(secret_key.secret_bytes());
+
+        secret_key.non_secure_erase();
 
         Ok(KeyPair::Secp256k1 {
-            secret: secret_key.secret_bytes(),
+            secret: key,
             public,
             mnemonic: None,
             derivation_path: None,
         })
     }
 
-    fn compute_address(&self, public_key: &PublicKey) -> Address {
-        let raw20 = self
-            .raw20_from_public(public_key)
-            .expect("Conflux only supports secp256k1 public keys");
-        Address {
-            raw: raw20.to_vec(),
-            chain_id: "conflux".to_string(),
+    fn compute_address(&self, public_key: PublicKey<'_>) -> Result<Address> {
+        let raw20 = self.raw20_from_public(public_key)?;
+        Ok(Address::new("conflux", &raw20))
+    }
+
+    fn compute_addresses(
+        &self,
+        public_keys: &[PublicKey<'_>],
+        out: &mut Vec<Address>,
+    ) -> Result<()> {
+        out.clear();
+        for keys in public_keys.chunks(keccak::MAX_LANES) {
+            let mut inputs: [&[u8]; keccak::MAX_LANES] = [&[]; keccak::MAX_LANES];
+            for (input, &key) in inputs.iter_mut().zip(keys) {
+                *input = Self::hashed_key_bytes(key)?;
+            }
+
+            let mut hashes = [[0u8; 32]; keccak::MAX_LANES];
+            keccak::keccak256_batch(&inputs[..keys.len()], &mut hashes);
+            out.extend(
+                hashes[..keys.len()]
+                    .iter()
+                    .map(|hash| Address::new("conflux", &hash[12..])),
+            );
         }
+        Ok(())
     }
 
-    fn format_address(&self, address: &Address, config: &AddressConfig) -> String {
-        let raw20: [u8; 20] = address
-            .raw
-            .as_slice()
-            .try_into()
-            .expect("Conflux address must be 20 bytes");
+    fn format_address_into(
+        &self,
+        address: &Address,
+        config: &AddressConfig,
+        out: &mut String,
+    ) -> Result<()> {
+        let raw20: [u8; 20] = address.raw().try_into().map_err(|_| {
+            VanityError::AddressFormat(format!(
+                "Conflux address must be 20 bytes, got {}",
+                address.raw().len()
+            ))
+        })?;
 
         match &config.chain_config {
-            ChainConfig::Conflux { network_id } => Self::encode_base32(&raw20, *network_id),
-
-            _ => panic!("conflux only supports conflux chain config"),
+            ChainConfig::Conflux { network_id } => {
+                Self::encode_base32_into(&raw20, *network_id, out);
+                Ok(())
+            }
+            _ => Err(VanityError::AddressFormat(
+                "Conflux addresses need a Conflux chain config".into(),
+            )),
         }
     }
 
-    fn format_secret(&self, keypair: &KeyPair) -> String {
+    fn format_secret(&self, keypair: &KeyPair) -> Secret<String> {
         match keypair {
             KeyPair::Secp256k1 {
                 secret,
@@ -265,12 +313,12 @@ impl Chain for ConfluxChain {
                 ..
             } => {
                 if let (Some(m), Some(path)) = (mnemonic, derivation_path) {
-                    format!("mnemonic:{}|path:{}", m.to_string(), path.to_string())
+                    format!("mnemonic:{}|path:{}", m.expose(), path).into()
                 } else {
-                    hex::encode(secret)
+                    hex::encode(secret.expose()).into()
                 }
             }
-            KeyPair::Ed25519 { .. } => "<unsupported>".to_string(),
+            KeyPair::Ed25519 { .. } => "<unsupported>".into(),
         }
     }
 
@@ -303,18 +351,40 @@ impl Chain for ConfluxChain {
         true
     }
 }
+
+/// Formats `n` in decimal into `buf` without allocating.
+fn itoa_u32(mut n: u32, buf: &mut [u8; 10]) -> &str {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    std::str::from_utf8(&buf[i..]).expect("decimal digits are ASCII")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::config::Encoding;
     use hex;
 
+    fn encode(raw: &[u8; 20], network_id: u32) -> String {
+        let mut out = String::new();
+        ConfluxChain::encode_base32_into(raw, network_id, &mut out);
+        out
+    }
+
     #[test]
     fn base32_encoding_matches_existing_vector() {
         let mut raw: [u8; 20] = [0u8; 20];
         hex::decode_to_slice("8357E767bc8CC8f1a1ed113444661C03A89293F5", &mut raw)
             .expect("valid hex");
 
-        let encoded = ConfluxChain::encode_base32(&raw, 1029);
+        let encoded = encode(&raw, 1029);
         assert_eq!(encoded, "cfx:acbzt35h1wgpv6rb7yjxjvdgdub4veyx8y9hcukbdw");
     }
 
@@ -324,9 +394,9 @@ mod tests {
         hex::decode_to_slice("8357E767bc8CC8f1a1ed113444661C03A89293F5", &mut raw)
             .expect("valid hex");
 
-        let mainnet = ConfluxChain::encode_base32(&raw, 1029);
-        let testnet = ConfluxChain::encode_base32(&raw, 1);
-        let custom = ConfluxChain::encode_base32(&raw, 123);
+        let mainnet = encode(&raw, 1029);
+        let testnet = encode(&raw, 1);
+        let custom = encode(&raw, 123);
 
         assert_eq!(mainnet, "cfx:acbzt35h1wgpv6rb7yjxjvdgdub4veyx8y9hcukbdw");
         assert_eq!(
@@ -335,4 +405,44 @@ mod tests {
         );
         assert_eq!(custom, "net123:acbzt35h1wgpv6rb7yjxjvdgdub4veyx8yyzbw1kj9");
     }
+
+    #[test]
+    fn rejects_foreign_keys_and_configs() {
+        let chain = ConfluxChain::new();
+        assert!(chain.compute_address(PublicKey::Ed25519(&[0; 32])).is_err());
+        assert!(chain
+            .compute_address(PublicKey::Secp256k1(&[4; 33]))
+            .is_err());
+
+        let address = Address::new("conflux", &[0; 20]);
+        let eth_cfg = AddressConfig::new(Encoding::Hex, ChainConfig::Ethereum { checksum: false });
+        assert!(chain.format_address(&address, &eth_cfg).is_err());
+
+        let short = Address::new("conflux", &[0; 3]);
+        let cfx_cfg =
+            AddressConfig::new(Encoding::Base32, ChainConfig::Conflux { network_id: 1029 });
+        assert!(chain.format_address(&short, &cfx_cfg).is_err());
+    }
+
+    #[test]
+    fn batched_addresses_match_single_addresses() {
+        let chain = ConfluxChain::new();
+        let keypairs: Vec<KeyPair> = (1u8..=5)
+            .map(|i| chain.keypair_from_secret(&[i; 32]).expect("keypair"))
+            .collect();
+        let publics: Vec<PublicKey<'_>> = keypairs.iter().map(KeyPair::public_key).collect();
+
+        let mut batched = Vec::new();
+        chain
+            .compute_addresses(&publics, &mut batched)
+            .expect("batched addresses");
+
+        assert_eq!(batched.len(), publics.len());
+        for (public, address) in publics.iter().zip(&batched) {
+            assert_eq!(
+                chain.compute_address(*public).expect("address").raw(),
+                address.raw()
+            );
+        }
+    }
 }
@@ -3,11 +3,16 @@ use bip39::Mnemonic;
 use rand::RngCore;
 use secp256k1::{PublicKey as SecpPubkey, Secp256k1, SecretKey};
 use sha3::{Digest, Keccak256};
-
-use crate::core::{
-    chain::Chain,
-    config::{AddressConfig, ChainConfig, Encoding},
-    types::{Address, KeyPair, PublicKey, Result, VanityError},
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    core::{
+        chain::Chain,
+        config::{AddressConfig, ChainConfig, Encoding},
+        secret::Secret,
+        types::{Address, KeyPair, PublicKey, Result, VanityError, SECP256K1_PUBLIC_LEN},
+    },
+    utils::keccak,
 };
 
 pub struct EthereumChain {
@@ -21,13 +26,22 @@ impl EthereumChain {
         }
     }
 
-    fn public_key_bytes(public_key: &PublicKey) -> Result<Vec<u8>> {
-        match public_key {
-            PublicKey::Secp256k1(bytes) => Ok(bytes.clone()),
-            PublicKey::Ed25519(_) => Err(VanityError::CryptoError(
-                "Ethereum only supports secp256k1 keys".into(),
-            )),
+    /// The 64 bytes of an uncompressed secp256k1 key that get hashed.
+    fn hashed_key_bytes(public_key: PublicKey<'_>) -> Result<&[u8]> {
+        let bytes = match public_key {
+            PublicKey::Secp256k1(bytes) => bytes,
+            PublicKey::Ed25519(_) => {
+                return Err(VanityError::CryptoError(
+                    "Ethereum only supports secp256k1 keys".into(),
+                ))
+            }
+        };
+        if bytes.len() != SECP256K1_PUBLIC_LEN || bytes[0] != 0x04 {
+            return Err(VanityError::CryptoError(
+                "expected a 65-byte uncompressed secp256k1 public key".into(),
+            ));
         }
+        Ok(&bytes[1..])
     }
 
     fn keccak256(data: &[u8]) -> [u8; 32] {
@@ -36,37 +50,37 @@ impl EthereumChain {
         hasher.finalize().into()
     }
 
-    fn derive_pubkey_bytes(&self, secret_key: &SecretKey) -> Vec<u8> {
-        SecpPubkey::from_secret_key(&self.secp, secret_key)
-            .serialize_uncompressed()
-            .to_vec()
+    fn derive_pubkey_bytes(&self, secret_key: &SecretKey) -> [u8; SECP256K1_PUBLIC_LEN] {
+        SecpPubkey::from_secret_key(&self.secp, secret_key).serialize_uncompressed()
     }
 
-    fn hex_lower(address: &Address) -> String {
-        hex::encode(&address.raw)
+    fn raw20(address: &Address) -> Result<[u8; 20]> {
+        address.raw().try_into().map_err(|_| {
+            VanityError::AddressFormat(format!(
+                "Ethereum address must be 20 bytes, got {}",
+                address.raw().len()
+            ))
+        })
     }
 
-    fn eip55_checksum(address: &Address) -> String {
-        let lower = Self::hex_lower(address);
-        let hash = Self::keccak256(lower.as_bytes());
-
-        let mut checksum = String::with_capacity(lower.len());
+    fn hex_lower(raw: &[u8; 20]) -> [u8; 40] {
+        let mut hex = [0u8; 40];
+        hex::encode_to_slice(raw, &mut hex).expect("40 hex digits for 20 bytes");
+        hex
+    }
 
-        for (i, c) in lower.chars().enumerate() {
-            if c.is_ascii_digit() {
-                checksum.push(c);
-                continue;
-            }
+    fn eip55_checksum(raw: &[u8; 20]) -> [u8; 40] {
+        let mut hex = Self::hex_lower(raw);
+        let hash = Self::keccak256(&hex);
 
+        for (i, c) in hex.iter_mut().enumerate() {
             let byte = hash[i / 2];
             let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0x0F };
             if nibble >= 8 {
-                checksum.push(c.to_ascii_uppercase());
-            } else {
-                checksum.push(c);
+                c.make_ascii_uppercase();
             }
         }
-        checksum
+        hex
     }
 
     fn strip_0x(address: &str) -> &str {
@@ -77,6 +91,12 @@ impl EthereumChain {
     }
 }
 
+impl Default for EthereumChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Chain for EthereumChain {
     fn id(&self) -> &str {
         "ethereum"
@@ -86,27 +106,50 @@ impl Chain for EthereumChain {
         "Ethereum"
     }
 
-    fn compute_address(&self, public_key: &crate::core::types::PublicKey) -> Address {
-        let bytes = Self::public_key_bytes(public_key).expect("secp256k1 only");
-        debug_assert_eq!(bytes.len(), 65);
-        debug_assert_eq!(bytes[0], 0x04);
+    fn compute_address(&self, public_key: PublicKey<'_>) -> Result<Address> {
+        let hash = Self::keccak256(Self::hashed_key_bytes(public_key)?);
+        Ok(Address::new("ethereum", &hash[12..]))
+    }
 
-        let hash = Self::keccak256(&bytes[1..]);
-        let raw = hash[12..].to_vec();
+    fn compute_addresses(
+        &self,
+        public_keys: &[PublicKey<'_>],
+        out: &mut Vec<Address>,
+    ) -> Result<()> {
+        out.clear();
+        for keys in public_keys.chunks(keccak::MAX_LANES) {
+            let mut inputs: [&[u8]; keccak::MAX_LANES] = [&[]; keccak::MAX_LANES];
+            for (input, &key) in inputs.iter_mut().zip(keys) {
+                *input = Self::hashed_key_bytes(key)?;
+            }
 
-        Address {
-            raw,
-            chain_id: "ethereum".into(),
+            let mut hashes = [[0u8; 32]; keccak::MAX_LANES];
+            keccak::keccak256_batch(&inputs[..keys.len()], &mut hashes);
+            out.extend(
+                hashes[..keys.len()]
+                    .iter()
+                    .map(|hash| Address::new("ethereum", &hash[12..])),
+            );
         }
+        Ok(())
     }
 
-    fn format_address(&self, address: &Address, config: &AddressConfig) -> String {
-        match (&config.encoding, &config.chain_config) {
+    fn format_address_into(
+        &self,
+        address: &Address,
+        config: &AddressConfig,
+        out: &mut String,
+    ) -> Result<()> {
+        let raw = Self::raw20(address)?;
+        let hex = match (&config.encoding, &config.chain_config) {
             (Encoding::HexChecksum, ChainConfig::Ethereum { checksum: true }) => {
-                format!("0x{}", Self::eip55_checksum(address))
+                Self::eip55_checksum(&raw)
             }
-            _ => format!("0x{}", Self::hex_lower(address)),
-        }
+            _ => Self::hex_lower(&raw),
+        };
+        out.push_str("0x");
+        out.push_str(std::str::from_utf8(&hex).expect("hex digits are ASCII"));
+        Ok(())
     }
 
     fn validate_address(&self, address: &str) -> bool {
@@ -128,46 +171,40 @@ impl Chain for EthereumChain {
             return true;
         }
 
-        let raw = match hex::decode(normalized.to_ascii_lowercase()) {
-            Ok(r) => r,
-            Err(_) => return false,
-        };
-
-        if raw.len() != 20 {
+        let mut raw = [0u8; 20];
+        if hex::decode_to_slice(normalized, &mut raw).is_err() {
             return false;
         }
 
-        let addr = Address {
-            raw,
-            chain_id: "ethereum".into(),
-        };
-
-        Self::eip55_checksum(&addr) == normalized
+        Self::eip55_checksum(&raw) == normalized.as_bytes()
     }
 
     fn derive_from_mnemonic(&self, mnemonic: &Mnemonic, path: &DerivationPath) -> Result<KeyPair> {
-        let seed = mnemonic.to_seed("");
+        let seed = Zeroizing::new(mnemonic.to_seed(""));
 
-        let xprv = XPrv::derive_from_path(seed, path)
+        let xprv = XPrv::derive_from_path(seed.as_slice(), path)
             .map_err(|e| VanityError::CryptoError(e.to_string()))?;
 
         let child_key = xprv.private_key();
-        let secret_bytes = child_key.to_bytes();
+        let secret_bytes = Zeroizing::new(<[u8; 32]>::from(child_key.to_bytes()));
 
-        let secret = SecretKey::from_slice(&secret_bytes)
+        let mut secret = SecretKey::from_slice(&*secret_bytes)
             .map_err(|e| VanityError::CryptoError(e.to_string()))?;
 
         let public = self.derive_pubkey_bytes(&secret);
+        let key = Secret::new(secret.secret_bytes());
+
+        secret.non_secure_erase();
 
         Ok(KeyPair::Secp256k1 {
-            secret: secret.secret_bytes(),
+            secret: key,
             public,
-            mnemonic: Some(mnemonic.clone()),
+            mnemonic: Some(Secret::new(mnemonic.clone())),
             derivation_path: Some(path.clone()),
         })
     }
 
-    fn format_secret(&self, keypair: &KeyPair) -> String {
+    fn format_secret(&self, keypair: &KeyPair) -> Secret<String> {
         match keypair {
             KeyPair::Secp256k1 {
                 secret,
@@ -176,28 +213,32 @@ impl Chain for EthereumChain {
                 ..
             } => {
                 if let (Some(m), Some(path)) = (mnemonic, derivation_path) {
-                    return format!("mnemonic:{}|path:{}", m.to_string(), path.to_string());
+                    format!("mnemonic:{}|path:{}", m.expose(), path).into()
                 } else {
-                    hex::encode(secret)
+                    hex::encode(secret.expose()).into()
                 }
             }
-            KeyPair::Ed25519 { .. } => String::from("<unsupported>"),
+            KeyPair::Ed25519 { .. } => "<unsupported>".into(),
         }
     }
     fn generate_keypair(&self, rng: &mut dyn RngCore) -> Result<KeyPair> {
         let mut secret_bytes = [0u8; 32];
 
-        let secret = loop {
+        let mut secret = loop {
             rng.fill_bytes(&mut secret_bytes);
             if let Ok(sec) = SecretKey::from_byte_array(&secret_bytes) {
                 break sec;
             }
         };
+        secret_bytes.zeroize();
 
         let pubkey = self.derive_pubkey_bytes(&secret);
+        let key = Secret::new(secret.secret_bytes());
+
+        secret.non_secure_erase();
 
         Ok(KeyPair::Secp256k1 {
-            secret: secret.secret_bytes(),
+            secret: key,
             public: pubkey,
             derivation_path: None,
             mnemonic: None,
@@ -209,13 +250,16 @@ impl Chain for EthereumChain {
             return Err(VanityError::CryptoError("invalid secret length".into()));
         }
 
-        let secret_key = SecretKey::from_slice(secret)
+        let mut secret_key = SecretKey::from_slice(secret)
             .map_err(|e| VanityError::CryptoError(format!("invalid secret: {}", e)))?;
 
         let public = self.derive_pubkey_bytes(&secret_key);
+        let key = Secret::new(secret_key.secret_bytes());
+
+        secret_key.non_secure_erase();
 
         Ok(KeyPair::Secp256k1 {
-            secret: secret_key.secret_bytes(),
+            secret: key,
             public,
             mnemonic: None,
             derivation_path: None,
@@ -245,12 +289,12 @@ mod tests {
 
         let public = chain.derive_pubkey_bytes(&secret);
 
-        let key = PublicKey::Secp256k1(public);
-
-        let address = chain.compute_address(&key);
+        let address = chain
+            .compute_address(PublicKey::Secp256k1(&public))
+            .expect("address");
 
         assert_eq!(
-            hex::encode(&address.raw),
+            hex::encode(address.raw()),
             "61b5ccbbee987149006b93a9cf5be30706b610e0"
         )
     }
@@ -261,10 +305,7 @@ mod tests {
 
         let raw = decode("61b5ccbbee987149006b93a9cf5be30706b610e0").expect("hex");
 
-        let address = Address {
-            raw,
-            chain_id: "ethereum".into(),
-        };
+        let address = Address::new("ethereum", &raw);
 
         let plain_cfg = AddressConfig {
             encoding: Encoding::Hex,
@@ -272,7 +313,7 @@ mod tests {
         };
 
         assert_eq!(
-            chain.format_address(&address, &plain_cfg),
+            chain.format_address(&address, &plain_cfg).unwrap(),
             "0x61b5ccbbee987149006b93a9cf5be30706b610e0"
         );
 
@@ -282,7 +323,7 @@ mod tests {
         };
 
         assert_eq!(
-            chain.format_address(&address, &checksum_cfg),
+            chain.format_address(&address, &checksum_cfg).unwrap(),
             "0x61B5ccbBee987149006B93a9Cf5bE30706B610e0"
         )
     }
@@ -292,13 +333,13 @@ mod tests {
         let chain = test_chain();
 
         let key_private = KeyPair::Secp256k1 {
-            secret: [0u8; 32],
-            public: vec![],
+            secret: [0u8; 32].into(),
+            public: [0; SECP256K1_PUBLIC_LEN],
             mnemonic: None,
             derivation_path: None,
         };
         assert_eq!(
-            chain.format_secret(&key_private),
+            chain.format_secret(&key_private).expose(),
             "0000000000000000000000000000000000000000000000000000000000000000"
         );
 
@@ -310,15 +351,15 @@ mod tests {
         let path: DerivationPath = "m/44'/60'/0'/0/0".parse().expect("valid path");
 
         let key_mnemonic = KeyPair::Secp256k1 {
-            secret: [0u8; 32],
-            public: vec![],
-            mnemonic: Some(mnemonic.clone()),
+            secret: [0u8; 32].into(),
+            public: [0; SECP256K1_PUBLIC_LEN],
+            mnemonic: Some(Secret::new(mnemonic.clone())),
             derivation_path: Some(path.clone()),
         };
 
         let formatted = chain.format_secret(&key_mnemonic);
-        assert!(formatted.contains(mnemonic.to_string().as_str()));
-        assert!(formatted.contains(&path.to_string()));
+        assert!(formatted.expose().contains(mnemonic.to_string().as_str()));
+        assert!(formatted.expose().contains(&path.to_string()));
     }
 
     #[test]
@@ -343,7 +384,7 @@ mod tests {
                     ..
                 },
             ) => {
-                assert_eq!(s1, s2);
+                assert_eq!(s1.expose(), s2.expose());
                 assert_eq!(p1, p2);
                 assert_eq!(p1.len(), 65);
                 assert_eq!(p1[0], 0x04);
@@ -373,16 +414,18 @@ mod tests {
         } = keypair
         {
             assert_eq!(
-                hex::encode(secret),
+                hex::encode(secret.expose()),
                 "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
             );
             assert!(public.len() == 65 && public[0] == 0x04);
-            assert_eq!(m.unwrap().to_string(), mnemonic_str);
+            assert_eq!(m.unwrap().expose().to_string(), mnemonic_str);
             assert_eq!(derivation_path.unwrap().to_string(), path.to_string());
 
-            let address = chain.compute_address(&PublicKey::Secp256k1(public));
+            let address = chain
+                .compute_address(PublicKey::Secp256k1(&public))
+                .expect("address");
             assert_eq!(
-                hex::encode(address.raw),
+                hex::encode(address.raw()),
                 "f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
             );
         } else {
@@ -425,7 +468,7 @@ mod tests {
         } = keypair
         {
             assert_eq!(
-                hex::encode(secret),
+                hex::encode(secret.expose()),
                 "c3e92bc305f6197098645e9fabee4cdad96235b0ccb8fb1e9089b620433af6cf"
             );
             assert_eq!(public.len(), 65);
@@ -436,4 +479,30 @@ mod tests {
             panic!("unexpected key type");
         }
     }
+
+    #[test]
+    fn batched_addresses_match_single_addresses() {
+        let chain = test_chain();
+        let mut rng = StdRng::seed_from_u64(7);
+        let keypairs: Vec<KeyPair> = (0..7)
+            .map(|_| chain.generate_keypair(&mut rng).expect("keypair"))
+            .collect();
+        let publics: Vec<PublicKey<'_>> = keypairs.iter().map(KeyPair::public_key).collect();
+
+        let mut batched = Vec::new();
+        chain
+            .compute_addresses(&publics, &mut batched)
+            .expect("batched addresses");
+
+        assert_eq!(batched.len(), publics.len());
+        for (public, address) in publics.iter().zip(&batched) {
+            assert_eq!(
+                chain.compute_address(*public).expect("address").raw(),
+                address.raw()
+            );
+        }
+        assert!(chain
+            .compute_addresses(&[PublicKey::Ed25519(&[0; 32])], &mut batched)
+            .is_err());
+    }
 }
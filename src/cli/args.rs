@@ -1,17 +1,48 @@
-use clap::{ArgGroup, Parser};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
-use super::validators::parse_mnemonic_word_count;
+use age::x25519::Recipient;
+use clap::{ArgGroup, Parser, Subcommand};
+
+use super::validators::{
+    parse_case_mode, parse_cpu_list, parse_cpu_percent, parse_csv_columns, parse_deadline,
+    parse_hashrate, parse_kdf, parse_key_range, parse_key_template, parse_mnemonic_template,
+    parse_mnemonic_word_count, parse_nice, parse_output_format, parse_paper_format,
+    parse_pattern_length, parse_recipient, parse_seed, parse_share_policy, parse_time_budget,
+};
+use crate::{
+    core::{
+        range::KeyRange,
+        recovery::{KeyTemplate, MnemonicTemplate},
+        shamir::SharePolicy,
+    },
+    matchers::CaseMode,
+    output::{CsvColumn, Kdf, OutputFormat, PaperFormat},
+};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 #[command(
       group(
           ArgGroup::new("key_source")
               .required(true)
-              .args(&["mnemonic", "private_key"]),
+              .args(&[
+                  "mnemonic",
+                  "private_key",
+                  "range",
+                  "recover_key",
+                  "recover_mnemonic",
+                  "job",
+              ]),
       )
   )]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Target blockchain id. Default: ethereum
     #[arg(long, default_value = "ethereum")]
     pub chain: String,
@@ -27,34 +58,104 @@ pub struct Args {
     #[arg(long = "mnemonic", short = 'm')]
     pub mnemonic: bool,
 
+    /// Scan private keys START:END (inclusive, hex) in order, e.g. 0x1000:0x1fff.
+    #[arg(long, value_parser = parse_key_range, value_name = "START:END")]
+    pub range: Option<KeyRange>,
+
+    /// Recover a private key with unknown nibbles, e.g. 0x4c0883a6...?d1e?...
+    /// (64 hex characters, `?` for each unreadable one). Needs --address.
+    #[arg(long, value_parser = parse_key_template, value_name = "HEX", requires = "address")]
+    pub recover_key: Option<KeyTemplate>,
+
+    /// Recover a mnemonic with unknown words, e.g. "abandon ? zoo ...". Derived
+    /// at --derivation-path. Needs --address.
+    #[arg(
+        long,
+        value_parser = parse_mnemonic_template,
+        value_name = "PHRASE",
+        requires = "address"
+    )]
+    pub recover_mnemonic: Option<MnemonicTemplate>,
+
+    /// Known address of the secret being recovered.
+    #[arg(long, value_name = "ADDRESS")]
+    pub address: Option<String>,
+
+    /// Job file (TOML or JSON) listing named targets to mine in one run.
+    /// Each target sets its own pattern, chain and limit.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["PatternArgs", "chain", "cfx_network", "limit"]
+    )]
+    pub job: Option<PathBuf>,
+
     /// Max attempts to generate addresses (default: unlimited).
     #[arg(long, short = 'a')]
     pub max_attempts: Option<u64>,
 
+    /// Stop after this much time, e.g. 90s, 15m, 2h or 1h30m.
+    #[arg(long, value_parser = parse_time_budget, conflicts_with = "until")]
+    pub duration: Option<Duration>,
+
+    /// Stop at this time, e.g. 2026-10-18T06:00 (local) or an RFC 3339 timestamp.
+    #[arg(long, value_parser = parse_deadline)]
+    pub until: Option<SystemTime>,
+
     /// Max matching addresses to return (default: unlimited).
     #[arg(long, short = 'l')]
     pub limit: Option<u64>,
 
-    /// Number of threads to use (default: number of CPU cores).
+    /// Derive keys deterministically from this seed (for audits and tests).
+    /// Keys are only as secret as the seed: never fund them unless the seed is
+    /// itself secret and high-entropy.
+    #[arg(long, value_parser = parse_seed, value_name = "SEED")]
+    pub seed: Option<[u8; 32]>,
+
+    /// Periodically save progress of a seeded or range run to this file.
+    #[arg(long, value_name = "FILE", conflicts_with = "resume")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Time between checkpoints, e.g. 30s or 5m.
+    #[arg(long, value_parser = parse_time_budget, default_value = "60s")]
+    pub checkpoint_interval: Duration,
+
+    /// Continue a seeded or range run from a checkpoint file (and keep updating it).
+    #[arg(long, value_name = "FILE")]
+    pub resume: Option<PathBuf>,
+
+    /// Number of threads to use (default: number of CPU cores, or of --cpus).
     #[arg(long, short = 't')]
     pub threads: Option<usize>,
 
-    /// Required substring(s) in the address (case-insensitive).
-    #[arg(long, short = 'c')]
-    pub contains: Option<Vec<String>>,
+    /// Pin worker threads to these cores, e.g. 0,2,4-7 (Linux only).
+    // Spelled out so clap parses the whole list as one value.
+    #[arg(long, value_parser = parse_cpu_list, value_name = "LIST")]
+    pub cpus: Option<std::vec::Vec<usize>>,
 
-    /// Required prefix for the address.
-    #[arg(long, short = 'p')]
-    pub prefix: Option<String>,
+    /// Run workers at this nice level, 1-19 (Linux only).
+    #[arg(long, value_parser = parse_nice, value_name = "N", conflicts_with = "idle")]
+    pub nice: Option<u8>,
 
-    /// Required suffix for the address.
-    #[arg(long, short = 's')]
-    pub suffix: Option<String>,
+    /// Run workers only when the CPU is otherwise idle (Linux only).
+    #[arg(long)]
+    pub idle: bool,
 
-    /// Regex for the address (Rust regex syntax).
-    /// Example: "^[a-zA-Z0-9]{4}.*\\d{2}$"
-    #[arg(long, short = 'r')]
-    pub regex: Option<String>,
+    /// Keep each worker thread busy at most this percentage of the time.
+    #[arg(long, value_parser = parse_cpu_percent, value_name = "PERCENT")]
+    pub max_cpu: Option<u8>,
+
+    /// Cap the total hashrate in addresses per second.
+    #[arg(
+        long,
+        value_parser = parse_hashrate,
+        value_name = "RATE",
+        conflicts_with = "max_cpu"
+    )]
+    pub max_hashrate: Option<u64>,
+
+    #[command(flatten)]
+    pub pattern: PatternArgs,
 
     /// Derivation path for mnemonic-based address generation.
     #[arg(long, short = 'd', default_value = "m/44'/60'/0'/0/0")]
@@ -68,11 +169,313 @@ pub struct Args {
     #[arg(long = "output", short = 'o', default_value = "vanity-addresses.csv")]
     pub output: String,
 
+    /// Result file format: csv, or jsonl for one JSON object per result with
+    /// full metadata (default: jsonl for .jsonl/.ndjson files, else csv).
+    #[arg(long, value_parser = parse_output_format)]
+    pub format: Option<OutputFormat>,
+
+    /// Also store results in this SQLite database, skipping addresses it
+    /// already holds; see the `results` subcommand. The database is not
+    /// encrypted, so it cannot be combined with --recipient or --passphrase.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["recipient", "passphrase"])]
+    pub db: Option<PathBuf>,
+
+    /// CSV columns to write, e.g. chain,address,private_key,mnemonic,derivation_path
+    /// or `all`. The file then starts with a schema version line.
+    // Spelled out so clap parses the whole list as one value.
+    #[arg(long, value_parser = parse_csv_columns, value_name = "LIST")]
+    pub csv_columns: Option<std::vec::Vec<CsvColumn>>,
+
     /// Also print each found address to the console.
     #[arg(long)]
     pub console: bool,
 
+    /// Print a QR code of each found address to the console (implies --console).
+    #[arg(long)]
+    pub console_qr: bool,
+
+    /// Also write each result as a printable page with QR codes of the
+    /// address and secret to DIR, one file per address. The pages are not
+    /// encrypted, so this cannot be combined with --recipient or --passphrase.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["recipient", "passphrase"])]
+    pub paper_wallet: Option<PathBuf>,
+
+    /// Paper wallet pages as html or svg.
+    #[arg(long, value_parser = parse_paper_format, default_value = "html", requires = "paper_wallet")]
+    pub paper_format: PaperFormat,
+
     /// Do not write results to a CSV file.
     #[arg(long = "no-file")]
     pub no_file: bool,
+
+    /// Save each found key as an encrypted keystore V3 file in DIR; the CSV
+    /// then holds only the address and keystore path.
+    #[arg(long, value_name = "DIR")]
+    pub keystore: Option<PathBuf>,
+
+    /// Key derivation for keystore files: scrypt or pbkdf2.
+    #[arg(long, value_parser = parse_kdf, default_value = "scrypt")]
+    pub keystore_kdf: Kdf,
+
+    /// Encrypt the result file to this age public key (age1...); may be
+    /// repeated. The file gets a .age extension.
+    #[arg(long, value_parser = parse_recipient, value_name = "AGE_KEY")]
+    pub recipient: Vec<Recipient>,
+
+    /// Encrypt the result file with a passphrase. The file gets a .age extension.
+    #[arg(long, conflicts_with = "recipient")]
+    pub passphrase: bool,
+
+    /// Also POST each result as JSON to URL, retrying with backoff; results
+    /// that cannot be delivered are spooled and sent on a later run.
+    #[arg(long, value_name = "URL")]
+    pub webhook: Option<String>,
+
+    /// Send only the address and metadata to the webhook, never the secret.
+    #[arg(long, requires = "webhook")]
+    pub webhook_redact: bool,
+
+    /// Spool file for results the webhook could not take.
+    #[arg(
+        long,
+        value_name = "FILE",
+        default_value = "webhook-spool.jsonl",
+        requires = "webhook"
+    )]
+    pub webhook_spool: PathBuf,
+
+    /// Split each found key into N shares, any M of which recover it, and
+    /// write share i to <output>.share<i>.csv instead of the result file.
+    /// Mnemonics become SLIP-39 shares that only the `combine` subcommand
+    /// turns back into the mnemonic.
+    #[arg(
+        long,
+        value_name = "M-of-N",
+        value_parser = parse_share_policy,
+        conflicts_with_all = [
+            "format", "csv_columns", "db", "console", "console_qr", "paper_wallet", "no_file",
+            "keystore", "recipient", "passphrase",
+        ]
+    )]
+    pub shares: Option<SharePolicy>,
+
+    /// Read the password from the first line of FILE instead of prompting.
+    #[arg(long, value_name = "FILE", conflicts_with = "password_env")]
+    pub password_file: Option<PathBuf>,
+
+    /// Read the password from environment variable VAR instead of prompting.
+    #[arg(long, value_name = "VAR")]
+    pub password_env: Option<String>,
+}
+
+// Address pattern options shared by mining, estimation and verification. A
+// plain comment: clap would use a doc comment as the binary's about text.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct PatternArgs {
+    /// Required substring(s) in the address (any of them).
+    #[arg(long, short = 'c')]
+    pub contains: Option<Vec<String>>,
+
+    /// Required prefix for the address.
+    #[arg(long, short = 'p')]
+    pub prefix: Option<String>,
+
+    /// Required suffix for the address.
+    #[arg(long, short = 's')]
+    pub suffix: Option<String>,
+
+    /// Regex for the address (Rust regex syntax).
+    /// Example: "^[a-zA-Z0-9]{4}.*\\d{2}$"
+    #[arg(long, short = 'r')]
+    pub regex: Option<String>,
+
+    /// Full-length address mask; `?` is any character, `[0-9]` a class and
+    /// `{n}` repeats the previous token.
+    /// Example: "0xdead?{32}beef"
+    #[arg(long)]
+    pub mask: Option<String>,
+
+    /// Case handling for --prefix, --suffix, --contains and --regex:
+    /// insensitive, exact (match the displayed address), or checksum
+    /// (enforce EIP-55 case where the chain has it).
+    #[arg(long, default_value = "insensitive", value_parser = parse_case_mode)]
+    pub case: CaseMode,
+
+    /// Also accept leetspeak spellings of --prefix, --suffix and --contains
+    /// (e.g. "coffee" matches "c0ff3e").
+    #[arg(long)]
+    pub leet: bool,
+
+    /// Require N identical leading characters (any character).
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub repeat_prefix: Option<usize>,
+
+    /// Require N identical trailing characters (any character).
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub repeat_suffix: Option<usize>,
+
+    /// Require the last N characters to mirror the first N (e.g. abc…cba).
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub mirror: Option<usize>,
+
+    /// Require a palindrome of at least N characters anywhere.
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub palindrome: Option<usize>,
+
+    /// Require a run of at least N identical characters anywhere.
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub run: Option<usize>,
+
+    /// Require at least N consecutive digits.
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub digits: Option<usize>,
+
+    /// Require at least N consecutive letters.
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub letters: Option<usize>,
+
+    /// Require an ascending sequence of at least N characters (e.g. 0123456).
+    #[arg(long, value_name = "N", value_parser = parse_pattern_length)]
+    pub ascending: Option<usize>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Estimate how hard a pattern is to find without mining.
+    Estimate(EstimateArgs),
+    /// Decrypt a result file written with --recipient or --passphrase.
+    Decrypt(DecryptArgs),
+    /// List, filter, export or import results stored in a --db database.
+    Results(ResultsArgs),
+    /// Re-derive every address of a CSV or JSONL result file from its secret.
+    Verify(VerifyArgs),
+    /// Recover keys from --shares files and re-derive their addresses.
+    Combine(CombineArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct EstimateArgs {
+    /// Target blockchain id.
+    #[arg(long, default_value = "ethereum")]
+    pub chain: String,
+
+    #[arg(long = "cfx-network", default_value_t = 1029)]
+    pub cfx_network: u32,
+
+    #[command(flatten)]
+    pub pattern: PatternArgs,
+
+    /// Hashrate in addr/s used to turn attempts into time estimates
+    /// (e.g. the "Avg" speed reported by a previous run).
+    #[arg(long)]
+    pub rate: Option<f64>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DecryptArgs {
+    /// Encrypted result file (.age).
+    pub file: PathBuf,
+
+    /// age identity file holding the private key; may be repeated.
+    #[arg(long, short = 'i', value_name = "FILE")]
+    pub identity: Vec<PathBuf>,
+
+    /// Read the passphrase from the first line of FILE instead of prompting.
+    #[arg(long, value_name = "FILE", conflicts_with = "password_env")]
+    pub password_file: Option<PathBuf>,
+
+    /// Read the passphrase from environment variable VAR instead of prompting.
+    #[arg(long, value_name = "VAR")]
+    pub password_env: Option<String>,
+
+    /// Write the plaintext to FILE (readable by the owner only) instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ResultsArgs {
+    /// Result database written with --db.
+    #[arg(long, value_name = "FILE")]
+    pub db: PathBuf,
+
+    /// Only results on this chain, e.g. ethereum.
+    #[arg(long)]
+    pub chain: Option<String>,
+
+    /// Only results whose pattern contains TEXT, e.g. prefix:dead.
+    #[arg(long, value_name = "TEXT")]
+    pub pattern: Option<String>,
+
+    /// Only results found at or after TIME, e.g. 2026-10-01 or 2026-10-18T06:00.
+    #[arg(long, value_name = "TIME", value_parser = parse_deadline)]
+    pub since: Option<SystemTime>,
+
+    /// Only results found before TIME.
+    #[arg(long, value_name = "TIME", value_parser = parse_deadline)]
+    pub until: Option<SystemTime>,
+
+    /// Only results of run ID (see --runs).
+    #[arg(long, value_name = "ID")]
+    pub run: Option<i64>,
+
+    /// List the runs instead of their results.
+    #[arg(long, conflicts_with = "export")]
+    pub runs: bool,
+
+    /// Export the matching results with their secrets as csv (every column) or jsonl.
+    #[arg(long, value_name = "FORMAT", value_parser = parse_output_format)]
+    pub export: Option<OutputFormat>,
+
+    /// Write the export to FILE (readable by the owner only) instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE", requires = "export")]
+    pub output: Option<PathBuf>,
+
+    /// Import a CSV or JSONL result file into the database; may be repeated.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["runs", "export"])]
+    pub import: Vec<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyArgs {
+    /// Result file to check (CSV or JSONL).
+    pub file: PathBuf,
+
+    /// Read the file as csv or jsonl (default: from the extension).
+    #[arg(long, value_parser = parse_output_format)]
+    pub format: Option<OutputFormat>,
+
+    /// Chain of the results, for files that do not record it (default:
+    /// guessed from each address).
+    #[arg(long)]
+    pub chain: Option<String>,
+
+    /// Also require every address to match these pattern options.
+    #[command(flatten)]
+    pub pattern: PatternArgs,
+
+    /// Read the keystore password from the first line of FILE instead of prompting.
+    #[arg(long, value_name = "FILE", conflicts_with = "password_env")]
+    pub password_file: Option<PathBuf>,
+
+    /// Read the keystore password from environment variable VAR instead of prompting.
+    #[arg(long, value_name = "VAR")]
+    pub password_env: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CombineArgs {
+    /// Share files written with --shares; at least the threshold of them.
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Chain of the shares, for files that do not record it (default:
+    /// guessed from each address).
+    #[arg(long)]
+    pub chain: Option<String>,
+
+    /// Write the recovered results to FILE (readable by the owner only)
+    /// instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
 }
@@ -0,0 +1,92 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+};
+
+use crate::{
+    core::types::{FoundAddress, Result, VanityError},
+    output::{
+        combine_shares, permissions::owner_only, read_share_file, CsvColumn, CsvResultSink,
+        CsvSchema, ResultSink, ShareRecord,
+    },
+};
+
+use super::{args::CombineArgs, verify::check_secret};
+
+/// Recover the keys of `--shares` files, check each one still derives its
+/// address and write them out as a result CSV.
+pub fn run_combine(args: &CombineArgs) -> Result<()> {
+    // Shares grouped by address, in file order.
+    let mut addresses: Vec<(String, Vec<ShareRecord>)> = Vec::new();
+    for path in &args.files {
+        for record in read_share_file(File::open(path)?)? {
+            let key = record.address.to_ascii_lowercase();
+            let index = match addresses.iter().position(|(a, _)| *a == key) {
+                Some(index) => index,
+                None => {
+                    addresses.push((key, Vec::new()));
+                    addresses.len() - 1
+                }
+            };
+            let shares = &mut addresses[index].1;
+            // The same file given twice adds nothing.
+            if !shares
+                .iter()
+                .any(|s| s.share.expose() == record.share.expose())
+            {
+                shares.push(record);
+            }
+        }
+    }
+
+    let writer: Box<dyn Write + Send> = match &args.output {
+        Some(path) => {
+            Box::new(owner_only(OpenOptions::new().write(true).create_new(true)).open(path)?)
+        }
+        None => Box::new(io::stdout()),
+    };
+    let schema = CsvSchema::new(vec![
+        CsvColumn::Chain,
+        CsvColumn::Network,
+        CsvColumn::Address,
+        CsvColumn::Secret,
+    ]);
+    let mut sink = CsvResultSink::from_writer(writer, schema, true)?;
+
+    let mut failed = 0;
+    for (_, shares) in &addresses {
+        let first = &shares[0];
+        let records: Vec<&ShareRecord> = shares.iter().collect();
+        let checked = combine_shares(&records)
+            .map_err(|e| e.to_string())
+            .and_then(|secret| {
+                let found = FoundAddress {
+                    chain: first.chain.clone(),
+                    network: first.network,
+                    ..FoundAddress::new(&first.address, secret)
+                };
+                check_secret(&found, args.chain.as_deref(), &args.files[0], None)?;
+                Ok(found)
+            });
+        match checked {
+            Ok(found) => sink.handle(&found)?,
+            Err(reason) => {
+                failed += 1;
+                eprintln!("FAIL {}: {reason}", first.address);
+            }
+        }
+    }
+    eprintln!(
+        "Recovered {} of {} address(es)",
+        addresses.len() - failed,
+        addresses.len()
+    );
+
+    if failed > 0 {
+        return Err(VanityError::Verification(format!(
+            "{failed} of {} address(es) could not be recovered",
+            addresses.len()
+        )));
+    }
+    Ok(())
+}
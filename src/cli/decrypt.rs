@@ -0,0 +1,31 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+};
+
+use crate::{
+    core::types::Result,
+    output::{decrypt_file, is_passphrase_encrypted, permissions::owner_only, DecryptionKey},
+};
+
+use super::{args::DecryptArgs, PasswordSource};
+
+/// Decrypt an encrypted result file to stdout or `--output`.
+pub fn run_decrypt(args: &DecryptArgs) -> Result<()> {
+    let key = if is_passphrase_encrypted(&args.file)? {
+        let source =
+            PasswordSource::from_flags(args.password_file.as_deref(), args.password_env.as_deref());
+        DecryptionKey::Passphrase(source.read("Passphrase: ", false)?.expose().clone().into())
+    } else {
+        DecryptionKey::Identities(args.identity.clone())
+    };
+    let plaintext = decrypt_file(&args.file, &key)?;
+
+    match &args.output {
+        Some(path) => owner_only(OpenOptions::new().write(true).create_new(true))
+            .open(path)?
+            .write_all(plaintext.expose())?,
+        None => io::stdout().write_all(plaintext.expose())?,
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::{
+    core::{
+        difficulty::{AddressSpace, Difficulty},
+        types::{Result, VanityError},
+        ChainProvider,
+    },
+    utils::format_duration,
+};
+
+use super::{
+    args::EstimateArgs,
+    runtime::{address_config_for, build_pattern_matcher},
+};
+
+/// Probability targets reported alongside the expected attempts.
+const PROBABILITY_TARGETS: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Print the difficulty of the configured pattern without mining.
+pub fn run_estimate(args: &EstimateArgs) -> Result<()> {
+    let provider = ChainProvider::new();
+    if provider.get(&args.chain).is_none() {
+        return Err(VanityError::UnsupportedChain(args.chain.clone()));
+    }
+
+    let space = AddressSpace::for_config(&address_config_for(&args.chain, args.cfx_network));
+    let matcher = build_pattern_matcher(&args.pattern, &space)?;
+
+    println!("Chain: {}", args.chain);
+    println!("Pattern: {}", matcher.description());
+
+    let Some(probability) = matcher.probability(&space) else {
+        println!("Difficulty: unknown (pattern cannot be estimated)");
+        return Ok(());
+    };
+
+    let Some(difficulty) = Difficulty::from_probability(probability) else {
+        println!("Difficulty: impossible (pattern can never match this chain's addresses)");
+        return Ok(());
+    };
+
+    println!(
+        "Expected attempts: {:.0}{}",
+        difficulty.expected_attempts(),
+        format_time(difficulty.expected_attempts(), args.rate)
+    );
+
+    for target in PROBABILITY_TARGETS {
+        let attempts = difficulty.attempts_for_probability(target);
+        println!(
+            "{:.0}% chance after: {:.0} attempts{}",
+            target * 100.0,
+            attempts,
+            format_time(attempts, args.rate)
+        );
+    }
+
+    if args.rate.is_none() {
+        println!("Pass --rate <addr/s> to convert attempts into time.");
+    }
+
+    Ok(())
+}
+
+/// ` (~2h 5m)` for `attempts` at `rate`, or nothing without a usable rate.
+fn format_time(attempts: f64, rate: Option<f64>) -> String {
+    let Some(rate) = rate.filter(|r| *r > 0.0 && r.is_finite()) else {
+        return String::new();
+    };
+    match Difficulty::time_for_attempts(attempts, rate) {
+        Some(time) => format!(" (~{})", format_duration(time)),
+        // Beyond a `Duration` (about 5.8e11 years).
+        None => " (longer than the age of the universe)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_too_long_for_a_duration_are_still_shown() {
+        assert_eq!(format_time(1e30, None), "");
+        assert_eq!(format_time(1e30, Some(0.0)), "");
+        assert!(format_time(7200.0, Some(1.0)).starts_with(" (~2h"));
+        assert_eq!(
+            format_time(1e40, Some(1.0)),
+            " (longer than the age of the universe)"
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use super::{
+    runtime::{
+        address_config_for, build_config_matcher, checkpoint_config, default_threads,
+        generation_mode_for, keystore_config, output_encryption, paper_wallet_config,
+        password_source, resolve_chain, scheduling, time_budget, webhook_config, RuntimeConfig,
+        RuntimeTarget,
+    },
+    validators::{parse_mnemonic_word_count, parse_pattern_length},
+    Args,
+};
+use crate::{
+    core::{
+        config::{GenerationMode, MiningTarget},
+        difficulty::AddressSpace,
+        range::KeyRange,
+        types::{Result, VanityError},
+    },
+    matchers::MatcherConfig,
+};
+
+/// A job file describing many independent targets mined in one run.
+///
+/// ```toml
+/// mode = "mnemonic"
+/// output = "found.csv"
+///
+/// [[target]]
+/// name = "dead"
+/// prefix = "dead"
+/// limit = 2
+///
+/// [[target]]
+/// name = "cfx-beef"
+/// chain = "conflux"
+/// suffix = "beef"
+/// output = "cfx.csv"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct JobFile {
+    /// Key source: `private-key`, `mnemonic` or `range`.
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// Private key range `START:END` (hex) for `mode = "range"`.
+    pub range: Option<String>,
+    pub mnemonic_words: Option<usize>,
+    /// Defaults to `m/44'/60'/0'/0/0`.
+    pub derivation_path: Option<String>,
+    pub max_attempts: Option<u64>,
+    pub threads: Option<usize>,
+    /// Default output file for targets without their own.
+    pub output: Option<String>,
+    #[serde(alias = "target")]
+    pub targets: Vec<JobTarget>,
+}
+
+/// One named goal inside a job file.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct JobTarget {
+    pub name: String,
+    #[serde(default = "default_chain")]
+    pub chain: String,
+    #[serde(default = "default_cfx_network")]
+    pub cfx_network: u32,
+    /// Matches to find before the target retires (0 = unlimited).
+    #[serde(default)]
+    pub limit: u64,
+    pub output: Option<String>,
+    #[serde(flatten)]
+    pub pattern: MatcherConfig,
+    /// Keys neither the target nor its pattern knows, e.g. a misspelled
+    /// `prefx`; collected so `validate` can reject them.
+    #[serde(flatten)]
+    extra: HashMap<String, toml::Value>,
+}
+
+impl JobTarget {
+    fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| VanityError::InvalidJob(format!("target {}: {msg}", self.name));
+
+        if !self.extra.is_empty() {
+            let mut keys: Vec<_> = self.extra.keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            return Err(invalid(format!("unknown field(s): {}", keys.join(", "))));
+        }
+        if !self.pattern.has_constraints() {
+            return Err(invalid("no pattern defined".into()));
+        }
+        for (name, len) in self.pattern.lengths() {
+            parse_pattern_length(&len.to_string()).map_err(|e| invalid(format!("{name}: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+fn default_mode() -> String {
+    "private-key".to_string()
+}
+
+fn default_derivation_path() -> String {
+    "m/44'/60'/0'/0/0".to_string()
+}
+
+fn default_chain() -> String {
+    "ethereum".to_string()
+}
+
+fn default_cfx_network() -> u32 {
+    1029
+}
+
+impl JobFile {
+    /// Parse a job from TOML or JSON, chosen by the file extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        let job = if is_json {
+            Self::from_json(&text)?
+        } else {
+            Self::from_toml(&text)?
+        };
+        job.validate()?;
+        Ok(job)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| VanityError::InvalidJob(e.to_string()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        serde_json::from_str(text).map_err(|e| VanityError::InvalidJob(e.to_string()))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.targets.is_empty() {
+            return Err(VanityError::InvalidJob("no targets defined".into()));
+        }
+
+        let mut names = HashSet::new();
+        for target in &self.targets {
+            if target.name.is_empty() {
+                return Err(VanityError::InvalidJob("target name is empty".into()));
+            }
+            if !names.insert(target.name.as_str()) {
+                return Err(VanityError::InvalidJob(format!(
+                    "duplicate target name: {}",
+                    target.name
+                )));
+            }
+            target.validate()?;
+        }
+
+        if let Some(words) = self.mnemonic_words {
+            parse_mnemonic_word_count(&words.to_string()).map_err(VanityError::InvalidJob)?;
+        }
+
+        // Fields the mode does not use would be silently ignored.
+        let unused: &[(&str, bool)] = match self.mode.as_str() {
+            "private-key" => &[
+                ("range", self.range.is_some()),
+                ("mnemonic-words", self.mnemonic_words.is_some()),
+                ("derivation-path", self.derivation_path.is_some()),
+            ],
+            "mnemonic" => &[("range", self.range.is_some())],
+            "range" if self.range.is_none() => {
+                return Err(VanityError::InvalidJob("range mode needs a range".into()))
+            }
+            "range" => &[
+                ("mnemonic-words", self.mnemonic_words.is_some()),
+                ("derivation-path", self.derivation_path.is_some()),
+            ],
+            other => return Err(VanityError::InvalidJob(format!("unknown mode: {other}"))),
+        };
+        match unused.iter().find(|(_, set)| *set) {
+            Some((field, _)) => Err(VanityError::InvalidJob(format!(
+                "{field} is not used in {} mode",
+                self.mode
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolve the job into a runtime configuration.
+    ///
+    /// `--threads` and `--max-attempts` from `args` override the job values.
+    pub fn into_runtime(self, args: &Args) -> Result<RuntimeConfig> {
+        let default_output = self.output.unwrap_or_else(|| args.output.clone());
+
+        let targets = self
+            .targets
+            .into_iter()
+            .map(|t| {
+                let address_config = address_config_for(&t.chain, t.cfx_network);
+                let space = AddressSpace::for_config(&address_config);
+                let matcher = build_config_matcher(t.pattern, &space)
+                    .map_err(|e| VanityError::InvalidJob(format!("target {}: {e}", t.name)))?;
+
+                Ok(RuntimeTarget {
+                    target: MiningTarget {
+                        name: Some(t.name),
+                        chain: resolve_chain(&t.chain)?,
+                        matcher,
+                        address_config,
+                        limit: t.limit,
+                    },
+                    output: t.output.unwrap_or_else(|| default_output.clone()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let generation_mode = match &self.range {
+            Some(range) if self.mode == "range" => {
+                let range = KeyRange::parse(range)?;
+                GenerationMode::Range {
+                    start: range.start,
+                    end: range.end,
+                }
+            }
+            _ => generation_mode_for(
+                self.mode == "private-key",
+                self.mnemonic_words,
+                &self.derivation_path.unwrap_or_else(default_derivation_path),
+            )?,
+        };
+
+        Ok(RuntimeConfig {
+            targets,
+            generation_mode,
+            seed: args.seed,
+            checkpoint: checkpoint_config(args)?,
+            max_attempts: args.max_attempts.or(self.max_attempts).unwrap_or(0),
+            time_budget: time_budget(args)?,
+            threads: args
+                .threads
+                .or(self.threads)
+                .unwrap_or_else(|| default_threads(args)),
+            scheduling: scheduling(args),
+            console: args.console,
+            console_qr: args.console_qr,
+            no_file: args.no_file,
+            format: args.format,
+            csv_columns: args.csv_columns.clone(),
+            db: args.db.clone(),
+            shares: args.shares,
+            keystore: keystore_config(args),
+            paper_wallet: paper_wallet_config(args),
+            webhook: webhook_config(args),
+            encryption: output_encryption(args),
+            password: password_source(args),
+        })
+    }
+}
+
+/// Load the job file at `path` and resolve it against the CLI `args`.
+pub(crate) fn load_job(path: &Path, args: &Args) -> Result<RuntimeConfig> {
+    JobFile::from_path(path)?.into_runtime(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    const JOB: &str = r#"
+        mode = "private-key"
+        max-attempts = 500
+        output = "all.csv"
+
+        [[target]]
+        name = "dead"
+        prefix = "dead"
+        limit = 2
+
+        [[target]]
+        name = "cfx"
+        chain = "conflux"
+        suffix = "beef"
+        case = "exact"
+        output = "cfx.csv"
+    "#;
+
+    fn args(extra: &[&str]) -> Args {
+        let mut argv = vec!["vanity-miner", "--job", "job.toml"];
+        argv.extend_from_slice(extra);
+        Args::try_parse_from(argv).unwrap()
+    }
+
+    #[test]
+    fn parses_toml_targets() {
+        let job = JobFile::from_toml(JOB).unwrap();
+        job.validate().unwrap();
+        assert_eq!(job.targets.len(), 2);
+        assert_eq!(job.targets[0].pattern.prefix.as_deref(), Some("dead"));
+        assert_eq!(job.targets[1].chain, "conflux");
+
+        let runtime = job.into_runtime(&args(&["--threads", "3"])).unwrap();
+        assert_eq!(runtime.threads, 3);
+        assert_eq!(runtime.max_attempts, 500);
+        assert_eq!(runtime.targets[0].target.name.as_deref(), Some("dead"));
+        assert_eq!(runtime.targets[0].target.limit, 2);
+        assert_eq!(runtime.targets[0].output, "all.csv");
+        assert_eq!(runtime.targets[1].output, "cfx.csv");
+        assert_eq!(runtime.targets[1].target.chain.id(), "conflux");
+    }
+
+    #[test]
+    fn parses_json_targets() {
+        let job = JobFile::from_json(
+            r#"{"mode": "mnemonic", "targets": [{"name": "a", "contains": ["cafe"]}]}"#,
+        )
+        .unwrap();
+        job.validate().unwrap();
+        assert_eq!(job.targets[0].pattern.contains, vec!["cafe".to_string()]);
+        assert_eq!(job.targets[0].limit, 0);
+    }
+
+    #[test]
+    fn parses_range_mode() {
+        let job = JobFile::from_toml(
+            "mode = \"range\"\nrange = \"0x10:0x1f\"\n[[target]]\nname = \"a\"\nprefix = \"0\"\n",
+        )
+        .unwrap();
+        let runtime = job.into_runtime(&args(&[])).unwrap();
+        assert!(matches!(
+            runtime.generation_mode,
+            GenerationMode::Range { start, end } if start == 0x10.into() && end == 0x1f.into()
+        ));
+
+        let missing =
+            JobFile::from_toml("mode = \"range\"\n[[target]]\nname = \"a\"\nprefix = \"0\"\n")
+                .unwrap();
+        assert!(missing.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_and_missing_targets() {
+        let dup = JobFile::from_toml(
+            "[[target]]\nname = \"a\"\nprefix = \"1\"\n[[target]]\nname = \"a\"\nprefix = \"2\"\n",
+        )
+        .unwrap();
+        assert!(dup
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("duplicate"));
+
+        let empty = JobFile::from_toml("targets = []").unwrap();
+        assert!(empty.validate().is_err());
+
+        let bad = JobFile::from_toml("[[target]]\nname = \"a\"\nmask = \"zz\"\n").unwrap();
+        assert!(bad.into_runtime(&args(&[])).is_err());
+    }
+
+    #[test]
+    fn cli_targets_conflict_with_job() {
+        for extra in [
+            &["--prefix", "dead"][..],
+            &["--digits", "4"],
+            &["--chain", "conflux"],
+            &["--limit", "1"],
+        ] {
+            let mut argv = vec!["vanity-miner", "--job", "job.toml"];
+            argv.extend_from_slice(extra);
+            assert!(Args::try_parse_from(argv).is_err(), "{extra:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_targets() {
+        let error = |text: &str| {
+            JobFile::from_toml(text)
+                .unwrap()
+                .validate()
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error("[[target]]\nname = \"a\"\nprefx = \"dead\"\n").contains("prefx"));
+        assert!(error("[[target]]\nname = \"a\"\nlimit = 1\n").contains("no pattern"));
+        assert!(error("[[target]]\nname = \"a\"\nrun = 1\n").contains("run"));
+        assert!(
+            error("mnemonic-words = 13\n[[target]]\nname = \"a\"\nprefix = \"0\"\n")
+                .contains("12, 15, 18, 21, or 24")
+        );
+
+        let target = "[[target]]\nname = \"a\"\nprefix = \"0\"\n";
+        for (job, field) in [
+            ("range = \"0x1:0x2\"\n", "range"),
+            ("mnemonic-words = 24\n", "mnemonic-words"),
+            ("derivation-path = \"m/0\"\n", "derivation-path"),
+            ("mode = \"mnemonic\"\nrange = \"0x1:0x2\"\n", "range"),
+            (
+                "mode = \"range\"\nrange = \"0x1:0x2\"\nmnemonic-words = 12\n",
+                "mnemonic-words",
+            ),
+        ] {
+            let message = error(&format!("{job}{target}"));
+            assert!(
+                message.contains(&format!("{field} is not used")),
+                "{message}"
+            );
+        }
+        let mnemonic = "mode = \"mnemonic\"\nmnemonic-words = 24\nderivation-path = \"m/0\"\n";
+        JobFile::from_toml(&format!("{mnemonic}{target}"))
+            .unwrap()
+            .validate()
+            .unwrap();
+
+        let json = JobFile::from_json(r#"{"targets": [{"name": "a", "sufix": "beef"}]}"#).unwrap();
+        assert!(json.validate().unwrap_err().to_string().contains("sufix"));
+    }
+}
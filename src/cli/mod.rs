@@ -1,7 +1,31 @@
 mod args;
+mod combine;
+mod decrypt;
+mod estimate;
+mod job;
+mod password;
+mod results;
 mod runtime;
 mod validators;
+mod verify;
 
-pub use args::Args;
-pub use runtime::build_runtime_config;
-pub use validators::parse_mnemonic_word_count;
+pub use args::{
+    Args, CombineArgs, Command, DecryptArgs, EstimateArgs, PatternArgs, ResultsArgs, VerifyArgs,
+};
+pub use combine::run_combine;
+pub use decrypt::run_decrypt;
+pub use estimate::run_estimate;
+pub use job::{JobFile, JobTarget};
+pub use password::PasswordSource;
+pub use results::run_results;
+pub use runtime::{
+    build_runtime_config, KeystoreConfig, OutputEncryption, PaperWalletConfig, RuntimeConfig,
+    RuntimeTarget,
+};
+pub use validators::{
+    parse_case_mode, parse_cpu_list, parse_cpu_percent, parse_csv_columns, parse_deadline,
+    parse_hashrate, parse_kdf, parse_key_range, parse_key_template, parse_mnemonic_template,
+    parse_mnemonic_word_count, parse_nice, parse_output_format, parse_paper_format,
+    parse_pattern_length, parse_recipient, parse_seed, parse_share_policy, parse_time_budget,
+};
+pub use verify::run_verify;
@@ -0,0 +1,100 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{
+    secret::Secret,
+    types::{Result, VanityError},
+};
+
+/// Where a password is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordSource {
+    /// Ask on the terminal without echo.
+    Prompt,
+    /// First line of a file.
+    File(PathBuf),
+    /// An environment variable.
+    Env(String),
+}
+
+impl PasswordSource {
+    /// From `--password-file` / `--password-env`, prompting when neither is set.
+    pub fn from_flags(file: Option<&Path>, env: Option<&str>) -> Self {
+        match (file, env) {
+            (Some(path), _) => PasswordSource::File(path.to_path_buf()),
+            (None, Some(var)) => PasswordSource::Env(var.to_string()),
+            (None, None) => PasswordSource::Prompt,
+        }
+    }
+
+    /// Read a non-empty password; `confirm` asks twice when prompting.
+    pub fn read(&self, prompt: &str, confirm: bool) -> Result<Secret<String>> {
+        let password: Secret<String> = match self {
+            PasswordSource::Prompt => {
+                let password = Secret::new(rpassword::prompt_password(prompt).map_err(failed)?);
+                if confirm {
+                    let repeated = Secret::new(
+                        rpassword::prompt_password("Repeat password: ").map_err(failed)?,
+                    );
+                    if repeated.expose() != password.expose() {
+                        return Err(VanityError::Password("passwords do not match".into()));
+                    }
+                }
+                password
+            }
+            PasswordSource::File(path) => {
+                let contents = Secret::new(fs::read_to_string(path).map_err(|e| {
+                    VanityError::Password(format!("cannot read {}: {e}", path.display()))
+                })?);
+                contents.expose().lines().next().unwrap_or_default().into()
+            }
+            PasswordSource::Env(var) => std::env::var(var)
+                .map_err(|_| VanityError::Password(format!("${var} is not set")))?
+                .into(),
+        };
+
+        if password.expose().is_empty() {
+            return Err(VanityError::Password("password must not be empty".into()));
+        }
+        Ok(password)
+    }
+}
+
+fn failed(e: std::io::Error) -> VanityError {
+    VanityError::Password(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_first_line_of_file_and_env() {
+        let path = std::env::temp_dir().join("vanity_miner_password_test.txt");
+        fs::write(&path, "hunter2\nignored\n").unwrap();
+        assert_eq!(
+            PasswordSource::File(path.clone())
+                .read("", true)
+                .unwrap()
+                .expose(),
+            "hunter2"
+        );
+
+        fs::write(&path, "\n").unwrap();
+        assert!(PasswordSource::File(path).read("", false).is_err());
+
+        std::env::set_var("VANITY_MINER_PASSWORD_TEST", "from-env");
+        assert_eq!(
+            PasswordSource::Env("VANITY_MINER_PASSWORD_TEST".into())
+                .read("", false)
+                .unwrap()
+                .expose(),
+            "from-env"
+        );
+        assert!(PasswordSource::Env("VANITY_MINER_UNSET_TEST".into())
+            .read("", false)
+            .is_err());
+    }
+}
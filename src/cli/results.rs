@@ -0,0 +1,104 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::{
+    core::types::Result,
+    output::{
+        permissions::owner_only, read_results, CsvColumn, CsvResultSink, CsvSchema,
+        JsonlResultSink, OutputFormat, ResultFilter, ResultSink, ResultStore,
+    },
+};
+
+use super::args::ResultsArgs;
+
+/// List, export or import the results of a result database.
+pub fn run_results(args: &ResultsArgs) -> Result<()> {
+    let store = ResultStore::open(&args.db)?;
+
+    if !args.import.is_empty() {
+        for path in &args.import {
+            let results = read_results(path, None)?;
+            let added = store.import(&path.display().to_string(), &results)?;
+            println!(
+                "{}: {added} new, {} already stored",
+                path.display(),
+                results.len() - added
+            );
+        }
+        return Ok(());
+    }
+
+    if args.runs {
+        println!(
+            "{:>4}  {:<24}  {:<24}  {:>6}  {:>12}  {:<9}  CONFIG",
+            "RUN", "STARTED", "STOPPED", "FOUND", "ATTEMPTS", "STOP"
+        );
+        for run in store.runs()? {
+            let count = |n: Option<u64>| n.map_or_else(|| "-".into(), |n| n.to_string());
+            println!(
+                "{:>4}  {:<24}  {:<24}  {:>6}  {:>12}  {:<9}  {}",
+                run.id,
+                run.started_at,
+                run.stopped_at.as_deref().unwrap_or("-"),
+                count(run.found),
+                count(run.attempts),
+                run.stop_reason.as_deref().unwrap_or("-"),
+                run.config
+            );
+        }
+        return Ok(());
+    }
+
+    let filter = ResultFilter {
+        chain: args.chain.clone(),
+        pattern: args.pattern.clone(),
+        since: args.since.map(DateTime::<Utc>::from),
+        until: args.until.map(DateTime::<Utc>::from),
+        run: args.run,
+    };
+    let results = store.results(&filter)?;
+
+    let Some(format) = args.export else {
+        // Listings leave the secrets out; export them explicitly.
+        println!(
+            "{:>4}  {:<24}  {:<10}  {:<46}  PATTERN",
+            "RUN", "FOUND", "CHAIN", "ADDRESS"
+        );
+        for stored in &results {
+            let found = &stored.found;
+            println!(
+                "{:>4}  {:<24}  {:<10}  {:<46}  {}",
+                stored.run,
+                found.found_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+                found.chain,
+                found.address,
+                found.pattern
+            );
+        }
+        println!("{} result(s)", results.len());
+        return Ok(());
+    };
+
+    let writer: Box<dyn Write + Send> = match &args.output {
+        Some(path) => {
+            Box::new(owner_only(OpenOptions::new().write(true).create_new(true)).open(path)?)
+        }
+        None => Box::new(io::stdout()),
+    };
+    let mut sink: Box<dyn ResultSink> = match format {
+        OutputFormat::Csv => Box::new(CsvResultSink::from_writer(
+            writer,
+            CsvSchema::new(CsvColumn::ALL.to_vec()),
+            true,
+        )?),
+        OutputFormat::Jsonl => Box::new(JsonlResultSink::from_writer(writer)),
+    };
+    for stored in &results {
+        sink.handle(&stored.found)?;
+    }
+    Ok(())
+}
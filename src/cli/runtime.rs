@@ -1,81 +1,467 @@
-use std::sync::Arc;
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
-use super::Args as CliArgs;
+use super::{
+    job::load_job, verify::network_from_address, Args as CliArgs, PasswordSource, PatternArgs,
+};
 use crate::{
     core::{
         chain::Chain,
-        config::{AddressConfig, ChainConfig, Encoding, GenerationMode},
+        checkpoint::{Checkpoint, CheckpointConfig},
+        config::{AddressConfig, ChainConfig, Encoding, GenerationMode, MiningTarget},
+        difficulty::AddressSpace,
         matcher::DynMatcher,
+        recovery::RecoveryTemplate,
+        shamir::SharePolicy,
         types::{Result, VanityError},
         ChainProvider,
     },
-    matchers::{build_matcher, MatcherConfig},
+    executors::{Priority, Scheduling, Throttle},
+    matchers::{build_matcher, MatcherConfig, PrefixMatcher},
+    output::{CsvColumn, Kdf, OutputFormat, PaperFormat, SecretKind, Webhook},
 };
+use age::x25519::Recipient;
 use bip32::DerivationPath;
 use num_cpus;
+use serde_json::json;
 
 /// Fully resolved runtime configuration derived from CLI args.
 pub struct RuntimeConfig {
-    pub chain: Arc<dyn Chain>,
-    pub matcher: DynMatcher,
+    pub targets: Vec<RuntimeTarget>,
     pub generation_mode: GenerationMode,
-    pub address_config: AddressConfig,
+    pub seed: Option<[u8; 32]>,
+    pub checkpoint: Option<CheckpointConfig>,
     pub max_attempts: u64,
-    pub limit: u64,
+    /// Wall-clock budget for the run, from `--duration` or `--until`.
+    pub time_budget: Option<Duration>,
     pub threads: usize,
-    pub output: String,
+    pub scheduling: Scheduling,
     pub console: bool,
+    /// QR codes of the addresses on the console, from `--console-qr`.
+    pub console_qr: bool,
     pub no_file: bool,
+    /// Forced result file format; `None` picks it from each file's extension.
+    pub format: Option<OutputFormat>,
+    /// Columns from `--csv-columns`; `None` keeps the original layout.
+    pub csv_columns: Option<Vec<CsvColumn>>,
+    /// Result database from `--db`.
+    pub db: Option<PathBuf>,
+    /// Share files instead of the result file, from `--shares`.
+    pub shares: Option<SharePolicy>,
+    pub keystore: Option<KeystoreConfig>,
+    pub paper_wallet: Option<PaperWalletConfig>,
+    /// Result notifications from `--webhook`.
+    pub webhook: Option<Webhook>,
+    pub encryption: Option<OutputEncryption>,
+    /// Where secrets such as the keystore password come from.
+    pub password: PasswordSource,
+}
+
+/// Result file encryption from `--recipient` / `--passphrase`.
+#[derive(Clone)]
+pub enum OutputEncryption {
+    Recipients(Vec<Recipient>),
+    /// Encrypt with the password from [`RuntimeConfig::password`].
+    Passphrase,
+}
+
+/// Encrypted keystore output from `--keystore`.
+pub struct KeystoreConfig {
+    pub dir: PathBuf,
+    pub kdf: Kdf,
+}
+
+/// Paper wallet pages from `--paper-wallet`.
+pub struct PaperWalletConfig {
+    pub dir: PathBuf,
+    pub format: PaperFormat,
+}
+
+/// A mining target together with where its results are written.
+pub struct RuntimeTarget {
+    pub target: MiningTarget,
+    pub output: String,
+}
+
+impl RuntimeConfig {
+    pub fn mining_targets(&self) -> Vec<MiningTarget> {
+        self.targets.iter().map(|t| t.target.clone()).collect()
+    }
+
+    /// The secret each result carries once it reaches the result files.
+    pub fn secret_kind(&self) -> SecretKind {
+        match &self.generation_mode {
+            _ if self.keystore.is_some() => SecretKind::Keystore,
+            GenerationMode::Mnemonic { .. }
+            | GenerationMode::Recover(RecoveryTemplate::Mnemonic { .. }) => SecretKind::Mnemonic,
+            _ => SecretKind::PrivateKey,
+        }
+    }
+
+    /// What the run mines for, as recorded in the result database.
+    pub fn describe(&self) -> serde_json::Value {
+        let mode = match &self.generation_mode {
+            GenerationMode::PrivateKey => json!({ "type": "private-key" }),
+            GenerationMode::Mnemonic {
+                word_count,
+                derivation_path,
+            } => json!({
+                "type": "mnemonic",
+                "words": word_count,
+                "derivation_path": derivation_path.to_string(),
+            }),
+            GenerationMode::Range { start, end } => json!({
+                "type": "range",
+                "start": format!("{start:#x}"),
+                "end": format!("{end:#x}"),
+            }),
+            // The template holds most of the secret, so only its shape is kept.
+            GenerationMode::Recover(template) => json!({
+                "type": match template {
+                    RecoveryTemplate::Key(_) => "recover-key",
+                    RecoveryTemplate::Mnemonic { .. } => "recover-mnemonic",
+                },
+                "unknown": template.unknown(),
+            }),
+        };
+        let targets: Vec<_> = self
+            .targets
+            .iter()
+            .map(|t| {
+                json!({
+                    "name": t.target.name,
+                    "chain": t.target.chain.id(),
+                    "pattern": t.target.matcher.description(),
+                    "limit": t.target.limit,
+                })
+            })
+            .collect();
+        json!({
+            "mode": mode,
+            "targets": targets,
+            "seeded": self.seed.is_some(),
+            "max_attempts": self.max_attempts,
+            "threads": self.threads,
+        })
+    }
 }
 
 /// Build a `RuntimeConfig` from parsed CLI arguments.
+///
+/// With `--job`, targets, key source and defaults come from the job file;
+/// `--threads` and `--max-attempts` on the command line still take precedence.
 pub fn build_runtime_config(args: &CliArgs) -> Result<RuntimeConfig> {
-    let provider = ChainProvider::new();
-    let chain = provider
-        .get(&args.chain)
-        .ok_or_else(|| VanityError::UnsupportedChain(args.chain.clone()))?;
+    if let Some(path) = &args.job {
+        return load_job(path, args);
+    }
+
+    let address_config = address_config_for(&args.chain, args.cfx_network);
+    let chain = resolve_chain(&args.chain)?;
+
+    let recovery = recovery_template(args)?;
+    let target = match (&recovery, &args.address) {
+        // Recovery stops at the first candidate deriving the known address.
+        (Some(_), Some(address)) => MiningTarget {
+            name: None,
+            matcher: address_matcher(chain.as_ref(), &address_config, address)?,
+            chain,
+            address_config,
+            limit: 1,
+        },
+        _ => MiningTarget {
+            name: None,
+            chain,
+            matcher: build_pattern_matcher(
+                &args.pattern,
+                &AddressSpace::for_config(&address_config),
+            )?,
+            address_config,
+            limit: args.limit.unwrap_or(0),
+        },
+    };
+
+    let generation_mode = match (recovery, args.range) {
+        (Some(template), _) => GenerationMode::Recover(template),
+        (None, Some(range)) => GenerationMode::Range {
+            start: range.start,
+            end: range.end,
+        },
+        (None, None) => {
+            generation_mode_for(args.private_key, args.mnemonic_words, &args.derivation_path)?
+        }
+    };
+
+    // 5. Limits and threads.
+    let max_attempts = args.max_attempts.unwrap_or(0);
+    let threads = args.threads.unwrap_or_else(|| default_threads(args));
+
+    Ok(RuntimeConfig {
+        targets: vec![RuntimeTarget {
+            target,
+            output: args.output.clone(),
+        }],
+        generation_mode,
+        seed: args.seed,
+        checkpoint: checkpoint_config(args)?,
+        max_attempts,
+        time_budget: time_budget(args)?,
+        threads,
+        scheduling: scheduling(args),
+        console: args.console,
+        console_qr: args.console_qr,
+        no_file: args.no_file,
+        format: args.format,
+        csv_columns: args.csv_columns.clone(),
+        db: args.db.clone(),
+        shares: args.shares,
+        keystore: keystore_config(args),
+        paper_wallet: paper_wallet_config(args),
+        webhook: webhook_config(args),
+        encryption: output_encryption(args),
+        password: password_source(args),
+    })
+}
+
+/// The recovery template from `--recover-key` / `--recover-mnemonic`, if any.
+fn recovery_template(args: &CliArgs) -> Result<Option<RecoveryTemplate>> {
+    if let Some(key) = &args.recover_key {
+        return Ok(Some(RecoveryTemplate::Key(key.clone())));
+    }
+    let Some(template) = &args.recover_mnemonic else {
+        return Ok(None);
+    };
+    let derivation_path = args
+        .derivation_path
+        .parse()
+        .map_err(|e: bip32::Error| VanityError::InvalidDerivationPath(e.to_string()))?;
+    Ok(Some(RecoveryTemplate::Mnemonic {
+        template: template.clone(),
+        derivation_path,
+    }))
+}
+
+/// Matches exactly `address`, ignoring case and the chain prefix.
+///
+/// A Conflux address must be on the `--cfx-network` being searched: its
+/// checksum covers the network, so no candidate would ever match otherwise.
+fn address_matcher(chain: &dyn Chain, config: &AddressConfig, address: &str) -> Result<DynMatcher> {
+    if !chain.validate_address(address) {
+        return Err(VanityError::InvalidRecovery(format!(
+            "\"{address}\" is not a valid {} address",
+            chain.name()
+        )));
+    }
+    let address = address.trim();
+    if let ChainConfig::Conflux { network_id } = config.chain_config {
+        match network_from_address(address) {
+            Some(network) if network == network_id => {}
+            network => {
+                return Err(VanityError::InvalidRecovery(format!(
+                    "\"{address}\" is on network {}, but the search is on network {network_id}; \
+                     set --cfx-network to match",
+                    network.map_or_else(|| "unknown".to_string(), |n| n.to_string())
+                )))
+            }
+        }
+    }
+    let body = config.matching_body(address);
+    // Every address of a chain has the same length, so a full-length prefix
+    // is an exact match.
+    Ok(Arc::new(PrefixMatcher::new(body)))
+}
+
+/// Resolve `--duration` / `--until` into the time left for the run.
+pub(crate) fn time_budget(args: &CliArgs) -> Result<Option<Duration>> {
+    match (args.duration, args.until) {
+        (Some(duration), _) => Ok(Some(duration)),
+        (None, Some(until)) => until
+            .duration_since(SystemTime::now())
+            .map(Some)
+            .map_err(|_| VanityError::InvalidTimeBudget("--until is in the past".into())),
+        (None, None) => Ok(None),
+    }
+}
+
+/// One thread per `--cpus` core, or per available core.
+pub(crate) fn default_threads(args: &CliArgs) -> usize {
+    args.cpus.as_ref().map_or_else(num_cpus::get, Vec::len)
+}
+
+/// Pinning, priority and throttling from `--cpus`, `--nice`, `--idle`,
+/// `--max-cpu` and `--max-hashrate`.
+pub(crate) fn scheduling(args: &CliArgs) -> Scheduling {
+    let priority = match (args.idle, args.nice) {
+        (true, _) => Priority::Idle,
+        (false, Some(n)) => Priority::Nice(n),
+        (false, None) => Priority::Normal,
+    };
+    let throttle = match (args.max_cpu, args.max_hashrate) {
+        (Some(percent), _) => Some(Throttle::CpuPercent(percent)),
+        (None, Some(rate)) => Some(Throttle::Hashrate(rate)),
+        (None, None) => None,
+    };
+    Scheduling {
+        cpus: args.cpus.clone().unwrap_or_default(),
+        priority,
+        throttle,
+    }
+}
+
+pub(crate) fn keystore_config(args: &CliArgs) -> Option<KeystoreConfig> {
+    args.keystore.as_ref().map(|dir| KeystoreConfig {
+        dir: dir.clone(),
+        kdf: args.keystore_kdf,
+    })
+}
+
+pub(crate) fn paper_wallet_config(args: &CliArgs) -> Option<PaperWalletConfig> {
+    args.paper_wallet.as_ref().map(|dir| PaperWalletConfig {
+        dir: dir.clone(),
+        format: args.paper_format,
+    })
+}
+
+/// The webhook from `--webhook`. It never gets the secret when the run keeps
+/// keys off the disk in clear text (shares, keystores, encryption).
+pub(crate) fn webhook_config(args: &CliArgs) -> Option<Webhook> {
+    args.webhook.as_ref().map(|url| {
+        Webhook::new(url)
+            .with_redaction(args.webhook_redact || protects_secrets(args))
+            .with_spool(&args.webhook_spool)
+    })
+}
+
+/// Whether the run keeps secrets out of plaintext files, so other outputs
+/// must not write them either.
+pub(crate) fn protects_secrets(args: &CliArgs) -> bool {
+    args.shares.is_some()
+        || args.keystore.is_some()
+        || args.passphrase
+        || !args.recipient.is_empty()
+}
+
+pub(crate) fn output_encryption(args: &CliArgs) -> Option<OutputEncryption> {
+    if args.passphrase {
+        Some(OutputEncryption::Passphrase)
+    } else if !args.recipient.is_empty() {
+        Some(OutputEncryption::Recipients(args.recipient.clone()))
+    } else {
+        None
+    }
+}
+
+/// `--password-file`, `--password-env`, or an interactive prompt.
+pub(crate) fn password_source(args: &CliArgs) -> PasswordSource {
+    PasswordSource::from_flags(args.password_file.as_deref(), args.password_env.as_deref())
+}
+
+/// Resolve `--checkpoint` / `--resume`; resuming keeps writing to the same file.
+pub(crate) fn checkpoint_config(args: &CliArgs) -> Result<Option<CheckpointConfig>> {
+    let config = match (&args.checkpoint, &args.resume) {
+        (_, Some(path)) => CheckpointConfig {
+            path: path.clone(),
+            interval: args.checkpoint_interval,
+            resume: Some(Checkpoint::load(path)?),
+        },
+        (Some(path), None) => CheckpointConfig {
+            path: path.clone(),
+            interval: args.checkpoint_interval,
+            resume: None,
+        },
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(config))
+}
+
+pub(crate) fn resolve_chain(id: &str) -> Result<Arc<dyn Chain>> {
+    ChainProvider::new()
+        .get(id)
+        .ok_or_else(|| VanityError::UnsupportedChain(id.to_string()))
+}
+
+pub(crate) fn generation_mode_for(
+    private_key: bool,
+    mnemonic_words: Option<usize>,
+    derivation_path: &str,
+) -> Result<GenerationMode> {
+    if private_key {
+        return Ok(GenerationMode::PrivateKey);
+    }
+
+    let word_count = mnemonic_words.unwrap_or(12);
+    let path: DerivationPath = derivation_path
+        .parse()
+        .map_err(|e: bip32::Error| VanityError::InvalidDerivationPath(e.to_string()))?;
+
+    Ok(GenerationMode::Mnemonic {
+        word_count,
+        derivation_path: path,
+    })
+}
+
+/// Build the matcher described by the pattern options.
+pub(crate) fn build_pattern_matcher(
+    pattern: &PatternArgs,
+    space: &AddressSpace,
+) -> Result<DynMatcher> {
+    build_config_matcher(matcher_config_from(pattern), space)
+}
 
+/// Build a matcher from a resolved `MatcherConfig`.
+pub(crate) fn build_config_matcher(
+    config: MatcherConfig,
+    space: &AddressSpace,
+) -> Result<DynMatcher> {
+    build_matcher(config, space).map_err(|e| VanityError::InvalidPattern(e.to_string()))
+}
+
+fn matcher_config_from(pattern: &PatternArgs) -> MatcherConfig {
     let mut matcher_cfg = MatcherConfig::default();
 
-    if let Some(prefix) = args.prefix.clone() {
+    if let Some(prefix) = pattern.prefix.clone() {
         matcher_cfg.prefix = Some(prefix);
     }
 
-    if let Some(suffix) = args.suffix.clone() {
+    if let Some(suffix) = pattern.suffix.clone() {
         matcher_cfg.suffix = Some(suffix);
     }
 
-    if let Some(contains) = args.contains.clone() {
+    if let Some(contains) = pattern.contains.clone() {
         matcher_cfg.contains = contains;
     }
 
-    if let Some(regex) = args.regex.clone() {
+    if let Some(regex) = pattern.regex.clone() {
         matcher_cfg.regex = Some(regex);
     }
 
-    let matcher = build_matcher(matcher_cfg)
-        .map_err(|e| VanityError::CryptoError(format!("invalid regex: {e}")))?;
+    if let Some(mask) = pattern.mask.clone() {
+        matcher_cfg.mask = Some(mask);
+    }
 
-    let generation_mode = if args.private_key {
-        GenerationMode::PrivateKey
-    } else {
-        let word_count = args.mnemonic_words.unwrap_or(12);
-        let path: DerivationPath = args
-            .derivation_path
-            .parse()
-            .map_err(|e: bip32::Error| VanityError::InvalidDerivationPath(e.to_string()))?;
-
-        GenerationMode::Mnemonic {
-            word_count,
-            derivation_path: path,
-        }
-    };
+    matcher_cfg.case = pattern.case;
+    matcher_cfg.leet = pattern.leet;
+    matcher_cfg.repeat_prefix = pattern.repeat_prefix;
+    matcher_cfg.repeat_suffix = pattern.repeat_suffix;
+    matcher_cfg.mirror = pattern.mirror;
+    matcher_cfg.palindrome = pattern.palindrome;
+    matcher_cfg.run = pattern.run;
+    matcher_cfg.digits = pattern.digits;
+    matcher_cfg.letters = pattern.letters;
+    matcher_cfg.ascending = pattern.ascending;
+
+    matcher_cfg
+}
 
-    let address_config = match args.chain.as_str() {
+/// Address formatting used for a chain id.
+pub(crate) fn address_config_for(chain: &str, cfx_network: u32) -> AddressConfig {
+    match chain {
         "conflux" => AddressConfig::new(
             Encoding::Base32,
             ChainConfig::Conflux {
-                network_id: args.cfx_network,
+                network_id: cfx_network,
             },
         ),
         // Default to Ethereum-style hex checksum for other chains.
@@ -83,56 +469,199 @@ pub fn build_runtime_config(args: &CliArgs) -> Result<RuntimeConfig> {
             Encoding::HexChecksum,
             ChainConfig::Ethereum { checksum: true },
         ),
-    };
-
-    // 5. Limits and threads.
-    let max_attempts = args.max_attempts.unwrap_or(0);
-    let limit = args.limit.unwrap_or(0);
-    let threads = args.threads.unwrap_or_else(num_cpus::get);
-
-    Ok(RuntimeConfig {
-        chain,
-        matcher,
-        generation_mode,
-        address_config,
-        max_attempts,
-        limit,
-        threads,
-        output: args.output.clone(),
-        console: args.console,
-        no_file: args.no_file,
-    })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cli::Args;
+    use clap::Parser;
 
     #[test]
     fn runtime_config_basic_fields_are_resolved() {
-        let args = Args {
-            chain: "ethereum".to_string(),
-            cfx_network: 1029,
-            private_key: true,
-            mnemonic: false,
-            max_attempts: Some(100),
-            limit: Some(5),
-            threads: Some(2),
-            contains: None,
-            prefix: Some("dead".into()),
-            suffix: None,
-            regex: None,
-            derivation_path: "m/44'/60'/0'/0/0".into(),
-            mnemonic_words: None,
-            output: "vanity-addresses.csv".into(),
-            console: false,
-            no_file: false,
-        };
+        let args = Args::try_parse_from([
+            "vanity-miner",
+            "--chain",
+            "ethereum",
+            "--private-key",
+            "--max-attempts",
+            "100",
+            "--limit",
+            "5",
+            "--threads",
+            "2",
+            "--prefix",
+            "dead",
+        ])
+        .expect("parse args");
 
         let runtime = build_runtime_config(&args).expect("runtime config");
         assert_eq!(runtime.max_attempts, 100);
-        assert_eq!(runtime.limit, 5);
+        assert_eq!(runtime.targets.len(), 1);
+        assert_eq!(runtime.targets[0].target.limit, 5);
+        assert!(runtime.targets[0].target.name.is_none());
         assert_eq!(runtime.threads, 2);
     }
+
+    #[test]
+    fn webhooks_are_redacted_when_secrets_are_protected() {
+        let runtime = |extra: &[&str]| {
+            let mut argv = vec![
+                "vanity-miner",
+                "-k",
+                "-p",
+                "dead",
+                "--webhook",
+                "http://127.0.0.1:9/",
+            ];
+            argv.extend_from_slice(extra);
+            build_runtime_config(&Args::try_parse_from(argv).expect("parse args"))
+                .expect("runtime config")
+        };
+        let redacts = |extra: &[&str]| runtime(extra).webhook.expect("webhook").redacts();
+
+        assert!(!redacts(&[]));
+        assert!(redacts(&["--webhook-redact"]));
+        assert!(redacts(&["--shares", "2-of-3"]));
+        assert!(redacts(&["--keystore", "keys"]));
+        assert!(redacts(&["--passphrase"]));
+    }
+
+    #[test]
+    fn plaintext_outputs_conflict_with_encryption() {
+        let parses = |extra: &[&str]| {
+            let mut argv = vec!["vanity-miner", "-k", "-p", "dead"];
+            argv.extend_from_slice(extra);
+            Args::try_parse_from(argv).is_ok()
+        };
+
+        assert!(parses(&["--db", "results.db"]));
+        assert!(!parses(&["--db", "results.db", "--passphrase"]));
+        assert!(!parses(&[
+            "--db",
+            "results.db",
+            "--recipient",
+            "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p",
+        ]));
+        assert!(parses(&["--paper-wallet", "wallets"]));
+        assert!(!parses(&["--paper-wallet", "wallets", "--passphrase"]));
+        assert!(!parses(&[
+            "--paper-wallet",
+            "wallets",
+            "--recipient",
+            "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p",
+        ]));
+    }
+
+    #[test]
+    fn scheduling_options_are_resolved() {
+        let args = Args::try_parse_from([
+            "vanity-miner",
+            "--private-key",
+            "--prefix",
+            "dead",
+            "--cpus",
+            "1,3-4",
+            "--idle",
+            "--max-hashrate",
+            "5000",
+        ])
+        .expect("parse args");
+        let runtime = build_runtime_config(&args).expect("runtime");
+
+        // One worker per pinned core unless --threads says otherwise.
+        assert_eq!(runtime.threads, 3);
+        assert_eq!(runtime.scheduling.cpus, vec![1, 3, 4]);
+        assert_eq!(runtime.scheduling.priority, Priority::Idle);
+        assert_eq!(runtime.scheduling.throttle, Some(Throttle::Hashrate(5000)));
+
+        assert!(Args::try_parse_from(["vanity-miner", "-k", "--nice", "5", "--idle"]).is_err());
+        assert!(Args::try_parse_from([
+            "vanity-miner",
+            "-k",
+            "--max-cpu",
+            "50",
+            "--max-hashrate",
+            "9"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn recovery_targets_the_known_address() {
+        let key = format!("{}?1", "0".repeat(62));
+        let address = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+        let args =
+            Args::try_parse_from(["vanity-miner", "--recover-key", &key, "--address", address])
+                .expect("parse args");
+
+        let runtime = build_runtime_config(&args).expect("runtime config");
+        let target = &runtime.targets[0].target;
+        assert_eq!(target.limit, 1);
+        assert!(target
+            .matcher
+            .matches("7e5f4552091a69125d5dfcb7b8c2659029395bdf"));
+        assert!(matches!(
+            runtime.generation_mode,
+            GenerationMode::Recover(_)
+        ));
+        // The known nibbles never reach the result database.
+        let described = runtime.describe();
+        assert_eq!(
+            described["mode"],
+            json!({ "type": "recover-key", "unknown": 1 })
+        );
+        assert!(!described.to_string().contains(&"0".repeat(62)));
+
+        assert!(Args::try_parse_from(["vanity-miner", "--recover-key", &key]).is_err());
+    }
+
+    #[test]
+    fn pattern_errors_are_not_wrapped_twice() {
+        let config = MatcherConfig {
+            mask: Some("zz".into()),
+            ..MatcherConfig::default()
+        };
+        let err = build_config_matcher(config, &AddressSpace::ethereum(true))
+            .err()
+            .expect("bad mask");
+        assert_eq!(
+            err.to_string(),
+            "invalid mask: mask has 2 positions, but addresses have 40"
+        );
+    }
+
+    #[test]
+    fn recovery_address_must_be_on_the_searched_network() {
+        let key = format!("{}?1", "0".repeat(62));
+        let runtime = |address: &str, network: &str| {
+            build_runtime_config(
+                &Args::try_parse_from([
+                    "vanity-miner",
+                    "--chain",
+                    "conflux",
+                    "--cfx-network",
+                    network,
+                    "--recover-key",
+                    &key,
+                    "--address",
+                    address,
+                ])
+                .expect("parse args"),
+            )
+        };
+        let testnet = "cfxtest:ab9f8vmwbergwew7n18ntsgcp0jcwsm556sx6b25ey";
+
+        let config = runtime(testnet, "1").expect("runtime config");
+        assert!(config.targets[0]
+            .target
+            .matcher
+            .matches("ab9f8vmwbergwew7n18ntsgcp0jcwsm556sx6b25ey"));
+
+        let err = runtime(testnet, "1029").err().expect("network mismatch");
+        assert!(err
+            .to_string()
+            .contains("network 1, but the search is on network 1029"));
+    }
 }
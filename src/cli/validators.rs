@@ -1,5 +1,26 @@
+use std::{
+    collections::BTreeSet,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use sha3::{Digest, Sha3_256};
+
+use crate::{
+    core::{
+        range::KeyRange,
+        recovery::{KeyTemplate, MnemonicTemplate},
+        shamir::SharePolicy,
+    },
+    matchers::CaseMode,
+    output::{CsvColumn, Kdf, OutputFormat, PaperFormat},
+    utils::parse_duration,
+};
+
 const MIN_MNEMONIC_WORDS: usize = 12;
 const MAX_MNEMONIC_WORDS: usize = 24;
+/// CPUs a thread can be pinned to, the size of Linux's `cpu_set_t`.
+const MAX_CPUS: usize = 1024;
 
 /// Validates the mnemonic word count.
 pub fn parse_mnemonic_word_count(s: &str) -> Result<usize, String> {
@@ -7,7 +28,7 @@ pub fn parse_mnemonic_word_count(s: &str) -> Result<usize, String> {
         .parse()
         .map_err(|_| "Word count must be a number".to_string())?;
 
-    if count >= MIN_MNEMONIC_WORDS && count <= MAX_MNEMONIC_WORDS && count % 3 == 0 {
+    if (MIN_MNEMONIC_WORDS..=MAX_MNEMONIC_WORDS).contains(&count) && count.is_multiple_of(3) {
         Ok(count)
     } else {
         Err(format!(
@@ -17,6 +38,200 @@ pub fn parse_mnemonic_word_count(s: &str) -> Result<usize, String> {
     }
 }
 
+/// Validates a length for aesthetic patterns (at least two characters).
+pub fn parse_pattern_length(s: &str) -> Result<usize, String> {
+    let len: usize = s
+        .parse()
+        .map_err(|_| "Length must be a number".to_string())?;
+
+    if len >= 2 {
+        Ok(len)
+    } else {
+        Err(format!("Length must be at least 2. Got {}", len))
+    }
+}
+
+/// Parses a matcher case mode.
+pub fn parse_case_mode(s: &str) -> Result<CaseMode, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "insensitive" | "i" => Ok(CaseMode::Insensitive),
+        "exact" | "e" => Ok(CaseMode::Exact),
+        "checksum" | "c" => Ok(CaseMode::Checksum),
+        _ => Err(format!(
+            "Case mode must be insensitive, exact or checksum. Got {}",
+            s
+        )),
+    }
+}
+
+/// Turns a `--seed` phrase into a 256-bit key (SHA3-256 of its UTF-8 bytes).
+pub fn parse_seed(s: &str) -> Result<[u8; 32], String> {
+    if s.is_empty() {
+        return Err("Seed must not be empty".to_string());
+    }
+    Ok(Sha3_256::digest(s.as_bytes()).into())
+}
+
+/// Parses a private key range `START:END` (inclusive, hex).
+pub fn parse_key_range(s: &str) -> Result<KeyRange, String> {
+    KeyRange::parse(s).map_err(|e| e.to_string())
+}
+
+/// Parses a private key with `?` for unknown nibbles.
+pub fn parse_key_template(s: &str) -> Result<KeyTemplate, String> {
+    KeyTemplate::parse(s).map_err(|e| e.to_string())
+}
+
+/// Parses a mnemonic with `?` for unknown words.
+pub fn parse_mnemonic_template(s: &str) -> Result<MnemonicTemplate, String> {
+    MnemonicTemplate::parse(s).map_err(|e| e.to_string())
+}
+
+/// Parses a CPU list such as `0,2,4-7`.
+pub fn parse_cpu_list(s: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+    let mut seen = BTreeSet::new();
+    for part in s.split(',').map(str::trim) {
+        let (first, last) = part.split_once('-').unwrap_or((part, part));
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("CPU list must look like 0,2,4-7. Got {}", s))
+        };
+        let (first, last) = (parse(first)?, parse(last)?);
+        if first > last {
+            return Err(format!("CPU range {} is backwards", part));
+        }
+        if last >= MAX_CPUS {
+            return Err(format!(
+                "CPU indices must be below {}. Got {}",
+                MAX_CPUS, last
+            ));
+        }
+        for cpu in first..=last {
+            if seen.insert(cpu) {
+                cpus.push(cpu);
+            }
+        }
+    }
+    Ok(cpus)
+}
+
+/// Validates a nice level between 1 and 19.
+pub fn parse_nice(s: &str) -> Result<u8, String> {
+    match s.parse::<u8>() {
+        Ok(n) if (1..=19).contains(&n) => Ok(n),
+        _ => Err(format!("Nice level must be between 1 and 19. Got {}", s)),
+    }
+}
+
+/// Validates a CPU percentage between 1 and 100.
+pub fn parse_cpu_percent(s: &str) -> Result<u8, String> {
+    match s.trim_end_matches('%').parse::<u8>() {
+        Ok(p) if (1..=100).contains(&p) => Ok(p),
+        _ => Err(format!(
+            "CPU percentage must be between 1 and 100. Got {}",
+            s
+        )),
+    }
+}
+
+/// Validates a positive hashrate in addresses per second.
+pub fn parse_hashrate(s: &str) -> Result<u64, String> {
+    match s.parse::<u64>() {
+        Ok(r) if r > 0 => Ok(r),
+        _ => Err(format!("Hashrate must be a positive number. Got {}", s)),
+    }
+}
+
+/// Parses a keystore key derivation function name.
+pub fn parse_kdf(s: &str) -> Result<Kdf, String> {
+    s.parse()
+        .map_err(|e: crate::core::types::VanityError| e.to_string())
+}
+
+pub fn parse_output_format(s: &str) -> Result<OutputFormat, String> {
+    s.parse()
+        .map_err(|e: crate::core::types::VanityError| e.to_string())
+}
+
+pub fn parse_paper_format(s: &str) -> Result<PaperFormat, String> {
+    s.parse()
+        .map_err(|e: crate::core::types::VanityError| e.to_string())
+}
+
+/// Parses a share policy such as `3-of-5`.
+pub fn parse_share_policy(s: &str) -> Result<SharePolicy, String> {
+    s.parse()
+        .map_err(|e: crate::core::types::VanityError| e.to_string())
+}
+
+/// Parses a comma-separated list of CSV columns; `all` selects every column.
+pub fn parse_csv_columns(s: &str) -> Result<Vec<CsvColumn>, String> {
+    if s.trim() == "all" {
+        return Ok(CsvColumn::ALL.to_vec());
+    }
+    let mut columns = Vec::new();
+    for name in s.split(',') {
+        let column: CsvColumn = name
+            .parse()
+            .map_err(|e: crate::core::types::VanityError| e.to_string())?;
+        if columns.contains(&column) {
+            return Err(format!("CSV column {column} is listed twice"));
+        }
+        columns.push(column);
+    }
+    if !columns.contains(&CsvColumn::Address) {
+        return Err("CSV columns must include address".into());
+    }
+    Ok(columns)
+}
+
+/// Parses an age X25519 public key (`age1...`).
+pub fn parse_recipient(s: &str) -> Result<age::x25519::Recipient, String> {
+    s.trim()
+        .parse()
+        .map_err(|e| format!("Recipient must be an age public key (age1...): {}", e))
+}
+
+/// Validates a run time budget such as `2h` or `1h30m`.
+pub fn parse_time_budget(s: &str) -> Result<Duration, String> {
+    match parse_duration(s)? {
+        d if d.is_zero() => Err("Duration must be greater than zero".to_string()),
+        d => Ok(d),
+    }
+}
+
+/// Parses a deadline as RFC 3339 (`2026-10-18T06:00:00Z`), as local time
+/// (`2026-10-18T06:00`, `2026-10-18 06:00:30`) or as a local date, meaning
+/// its midnight (`2026-10-18`).
+pub fn parse_deadline(s: &str) -> Result<SystemTime, String> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.into());
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .map(|d| d.and_time(NaiveTime::MIN))
+    })
+    .ok_or_else(|| format!("Time must look like 2026-10-18T06:00. Got {}", s))?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(Into::into)
+        .ok_or_else(|| format!("{} does not exist in the local time zone", s))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,4 +252,81 @@ mod tests {
         assert!(parse_mnemonic_word_count("25").is_err());
         assert!(parse_mnemonic_word_count("abc").is_err());
     }
+
+    #[test]
+    fn test_pattern_lengths() {
+        assert_eq!(parse_pattern_length("6"), Ok(6));
+        assert!(parse_pattern_length("1").is_err());
+        assert!(parse_pattern_length("x").is_err());
+    }
+
+    #[test]
+    fn test_case_modes() {
+        assert_eq!(parse_case_mode("exact"), Ok(CaseMode::Exact));
+        assert_eq!(parse_case_mode("Checksum"), Ok(CaseMode::Checksum));
+        assert_eq!(parse_case_mode("i"), Ok(CaseMode::Insensitive));
+        assert!(parse_case_mode("upper").is_err());
+    }
+
+    #[test]
+    fn test_time_budgets_and_deadlines() {
+        assert_eq!(parse_time_budget("2h"), Ok(Duration::from_secs(7200)));
+        assert!(parse_time_budget("0s").is_err());
+
+        let utc = parse_deadline("2026-10-18T06:00:00Z").unwrap();
+        assert_eq!(
+            utc.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            1_792_303_200
+        );
+        assert!(parse_deadline("2026-10-18T06:00").is_ok());
+        assert_eq!(
+            parse_deadline("2026-10-18"),
+            parse_deadline("2026-10-18T00:00")
+        );
+        assert!(parse_deadline("tomorrow").is_err());
+    }
+
+    #[test]
+    fn test_seeds() {
+        assert_eq!(parse_seed("audit-1"), parse_seed("audit-1"));
+        assert_ne!(parse_seed("audit-1"), parse_seed("audit-2"));
+        assert!(parse_seed("").is_err());
+    }
+
+    #[test]
+    fn test_scheduling_values() {
+        assert_eq!(parse_cpu_list("0,2,4-6"), Ok(vec![0, 2, 4, 5, 6]));
+        assert_eq!(parse_cpu_list("3, 1-3"), Ok(vec![3, 1, 2]));
+        assert!(parse_cpu_list("4-2").is_err());
+        assert!(parse_cpu_list("a").is_err());
+        assert_eq!(parse_cpu_list("1023").map(|c| c.len()), Ok(1));
+        assert!(parse_cpu_list("1024").is_err());
+        assert!(parse_cpu_list("0-4000000000").is_err());
+        assert_eq!(parse_nice("19"), Ok(19));
+        assert!(parse_nice("0").is_err());
+        assert_eq!(parse_cpu_percent("50%"), Ok(50));
+        assert!(parse_cpu_percent("101").is_err());
+        assert!(parse_hashrate("0").is_err());
+    }
+
+    #[test]
+    fn test_csv_columns() {
+        assert_eq!(
+            parse_csv_columns("chain,address,secret"),
+            Ok(vec![
+                CsvColumn::Chain,
+                CsvColumn::Address,
+                CsvColumn::Secret
+            ])
+        );
+        assert_eq!(
+            parse_csv_columns("all").unwrap().len(),
+            CsvColumn::ALL.len()
+        );
+        assert!(parse_csv_columns("chain,secret").is_err());
+        assert!(parse_csv_columns("address,address").is_err());
+        assert!(parse_csv_columns("address,colour").is_err());
+    }
 }
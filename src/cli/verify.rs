@@ -0,0 +1,230 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    core::{
+        config::AddressConfig,
+        difficulty::AddressSpace,
+        types::{FoundAddress, Result, SecretInfo, VanityError},
+    },
+    output::{read_results, Keystore},
+};
+
+use super::{
+    args::VerifyArgs,
+    runtime::{address_config_for, build_pattern_matcher, resolve_chain},
+    PasswordSource,
+};
+
+/// Conflux mainnet, assumed when neither the record nor the address names a network.
+const DEFAULT_CFX_NETWORK: u32 = 1029;
+
+/// Re-derive every result of a result file from its secret and check it.
+pub fn run_verify(args: &VerifyArgs) -> Result<()> {
+    let results = read_results(&args.file, args.format)?;
+
+    let password = if results
+        .iter()
+        .any(|r| matches!(r.secret, SecretInfo::Keystore(_)))
+    {
+        let source =
+            PasswordSource::from_flags(args.password_file.as_deref(), args.password_env.as_deref());
+        Some(source.read("Keystore password: ", false)?)
+    } else {
+        None
+    };
+
+    let mut failed = 0;
+    for found in &results {
+        if let Err(reason) = verify(found, args, password.as_ref().map(|p| p.expose().as_str())) {
+            failed += 1;
+            println!("FAIL {}: {reason}", found.address);
+        }
+    }
+    println!(
+        "Verified {} result(s): {} ok, {failed} failed",
+        results.len(),
+        results.len() - failed
+    );
+
+    if failed > 0 {
+        return Err(VanityError::Verification(format!(
+            "{failed} of {} result(s) failed verification",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Check that the secret of `found` controls its address (and that the
+/// address matches the pattern options, if any).
+fn verify(
+    found: &FoundAddress,
+    args: &VerifyArgs,
+    password: Option<&str>,
+) -> std::result::Result<(), String> {
+    let (derived, config) = check_secret(found, args.chain.as_deref(), &args.file, password)?;
+    let matcher = build_pattern_matcher(&args.pattern, &AddressSpace::for_config(&config))
+        .map_err(|e| e.to_string())?;
+    if !matcher.matches(config.matching_body(&derived)) {
+        return Err(format!("does not match {}", matcher.description()));
+    }
+    Ok(())
+}
+
+/// Derive the address of the secret of `found` and check it is the recorded
+/// one. `chain` overrides the recorded chain; keystore paths may be relative
+/// to the `results` file. Returns the address and its configuration.
+pub(super) fn check_secret(
+    found: &FoundAddress,
+    chain: Option<&str>,
+    results: &Path,
+    password: Option<&str>,
+) -> std::result::Result<(String, AddressConfig), String> {
+    let chain_id = match (chain, found.chain.as_str()) {
+        (Some(chain), _) => chain,
+        (None, "") => guess_chain(&found.address),
+        (None, chain) => chain,
+    };
+    let chain = resolve_chain(chain_id).map_err(|e| e.to_string())?;
+    if !chain.validate_address(&found.address) {
+        return Err(format!("not a valid {} address", chain.name()));
+    }
+
+    let network = found
+        .network
+        .or_else(|| network_from_address(&found.address))
+        .unwrap_or(DEFAULT_CFX_NETWORK);
+    let config = address_config_for(chain_id, network);
+
+    let keypair = match &found.secret {
+        SecretInfo::Keystore(path) => {
+            let keystore: Keystore = fs::read(keystore_path(path, results))
+                .map_err(|e| format!("cannot read keystore {path}: {e}"))
+                .and_then(|json| {
+                    serde_json::from_slice(&json).map_err(|e| format!("bad keystore {path}: {e}"))
+                })?;
+            let secret = keystore
+                .decrypt(password.unwrap_or_default())
+                .map_err(|e| e.to_string())?;
+            chain.keypair_from_secret(secret.expose())
+        }
+        secret => secret.keypair(chain.as_ref()),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let derived = chain
+        .compute_address(keypair.public_key())
+        .and_then(|address| chain.format_address(&address, &config))
+        .map_err(|e| e.to_string())?;
+    if !derived.eq_ignore_ascii_case(&found.address) {
+        return Err(format!("secret derives {derived}"));
+    }
+
+    let public_key = keypair.public_key().to_hex();
+    if !found.public_key.is_empty() && !public_key.eq_ignore_ascii_case(&found.public_key) {
+        return Err("stored public key does not belong to the secret".into());
+    }
+    Ok((derived, config))
+}
+
+/// A keystore path as recorded, or else relative to the result file, which
+/// finds relative paths when verifying from another directory.
+fn keystore_path(recorded: &str, results: &Path) -> PathBuf {
+    let path = Path::new(recorded);
+    match results.parent() {
+        Some(dir) if path.is_relative() && !path.exists() => dir.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Chain of an address without a recorded chain, e.g. from a two-column CSV.
+fn guess_chain(address: &str) -> &'static str {
+    if address.contains(':') {
+        "conflux"
+    } else {
+        "ethereum"
+    }
+}
+
+/// Conflux network id from the address prefix (`cfx`, `cfxtest`, `net<id>`).
+pub(super) fn network_from_address(address: &str) -> Option<u32> {
+    let (prefix, _) = address.split_once(':')?;
+    match prefix.to_ascii_lowercase().as_str() {
+        "cfx" => Some(1029),
+        "cfxtest" => Some(1),
+        other => other.strip_prefix("net")?.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::PatternArgs, core::Secret};
+
+    fn args(prefix: Option<&str>) -> VerifyArgs {
+        VerifyArgs {
+            file: "unused".into(),
+            format: None,
+            chain: None,
+            pattern: PatternArgs {
+                prefix: prefix.map(str::to_string),
+                ..PatternArgs::default()
+            },
+            password_file: None,
+            password_env: None,
+        }
+    }
+
+    #[test]
+    fn checks_secret_address_and_pattern() {
+        let key_one: Secret<String> = format!("{}01", "0".repeat(62)).into();
+        let good = FoundAddress::new(
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+            SecretInfo::PrivateKey(key_one.clone()),
+        );
+        assert_eq!(verify(&good, &args(None), None), Ok(()));
+        assert_eq!(verify(&good, &args(Some("7e5f")), None), Ok(()));
+        assert!(verify(&good, &args(Some("dead")), None)
+            .unwrap_err()
+            .contains("does not match"));
+
+        let mnemonic = FoundAddress::new(
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            SecretInfo::Mnemonic {
+                phrase: "abandon abandon abandon abandon abandon abandon abandon abandon \
+                         abandon abandon abandon about"
+                    .into(),
+                derivation_path: "m/44'/60'/0'/0/0".into(),
+            },
+        );
+        assert_eq!(verify(&mnemonic, &args(None), None), Ok(()));
+
+        let wrong_key = FoundAddress::new(
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            SecretInfo::PrivateKey(key_one),
+        );
+        assert!(verify(&wrong_key, &args(None), None)
+            .unwrap_err()
+            .contains("secret derives 0x7E5F"));
+
+        let bad_checksum = FoundAddress::new(
+            "0x7e5F4552091A69125d5DfCb7b8C2659029395Bdf",
+            SecretInfo::PrivateKey("01".into()),
+        );
+        assert!(verify(&bad_checksum, &args(None), None)
+            .unwrap_err()
+            .contains("not a valid"));
+    }
+
+    #[test]
+    fn reads_the_network_from_conflux_addresses() {
+        assert_eq!(guess_chain("cfxtest:aak2rra2"), "conflux");
+        assert_eq!(network_from_address("CFX:aak2rra2"), Some(1029));
+        assert_eq!(network_from_address("cfxtest:aak2rra2"), Some(1));
+        assert_eq!(network_from_address("net8888:aak2rra2"), Some(8888));
+        assert_eq!(network_from_address("0xabc"), None);
+    }
+}
@@ -3,6 +3,7 @@ use bip39::Mnemonic;
 use rand::RngCore;
 
 use super::config::AddressConfig;
+use super::secret::Secret;
 use super::types::{Address, KeyPair, PublicKey, Result};
 
 pub trait Chain: Send + Sync {
@@ -15,11 +16,40 @@ pub trait Chain: Send + Sync {
 
     fn keypair_from_secret(&self, secret: &[u8]) -> Result<KeyPair>;
 
-    fn compute_address(&self, public_key: &PublicKey) -> Address;
-
-    fn format_address(&self, address: &Address, config: &AddressConfig) -> String;
-
-    fn format_secret(&self, keypair: &KeyPair) -> String;
+    fn compute_address(&self, public_key: PublicKey<'_>) -> Result<Address>;
+
+    /// Replace the contents of `out` with the address of each key, in order.
+    ///
+    /// Chains whose addresses are hashes of the key override this to hash
+    /// several keys per pass.
+    fn compute_addresses(
+        &self,
+        public_keys: &[PublicKey<'_>],
+        out: &mut Vec<Address>,
+    ) -> Result<()> {
+        out.clear();
+        for &public_key in public_keys {
+            out.push(self.compute_address(public_key)?);
+        }
+        Ok(())
+    }
+
+    /// Append the display form of `address` to `out`, so the executor can
+    /// reuse one buffer for every attempt.
+    fn format_address_into(
+        &self,
+        address: &Address,
+        config: &AddressConfig,
+        out: &mut String,
+    ) -> Result<()>;
+
+    fn format_address(&self, address: &Address, config: &AddressConfig) -> Result<String> {
+        let mut out = String::new();
+        self.format_address_into(address, config, &mut out)?;
+        Ok(out)
+    }
+
+    fn format_secret(&self, keypair: &KeyPair) -> Secret<String>;
 
     fn validate_address(&self, address: &str) -> bool;
 }
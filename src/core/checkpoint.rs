@@ -0,0 +1,165 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+use super::config::{GenerationMode, MiningConfig};
+use super::types::{Result, VanityError};
+
+/// Current checkpoint file format.
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// Where and how often an executor persists its progress.
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    pub path: PathBuf,
+    pub interval: Duration,
+    /// Progress to continue from; must match the run's configuration.
+    pub resume: Option<Checkpoint>,
+}
+
+/// Progress of a reproducible run, enough to continue it after a restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    /// Fingerprint of everything that determines which keys are generated
+    /// and how they are matched.
+    pub config_hash: String,
+    pub attempts: u64,
+    pub found: u64,
+    pub elapsed_secs: f64,
+    /// Matches found per target, in configuration order.
+    pub target_found: Vec<u64>,
+    pub workers: Vec<WorkerCheckpoint>,
+}
+
+/// Position of one worker in its key stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerCheckpoint {
+    pub attempts: u64,
+    /// Offset into the worker's key stream.
+    pub position: u128,
+}
+
+impl Checkpoint {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text)
+            .map_err(|e| VanityError::Checkpoint(format!("{}: {e}", path.display())))
+    }
+
+    /// Write the checkpoint atomically, so a crash never leaves a torn file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| VanityError::Checkpoint(e.to_string()))?;
+        let tmp = tmp_path(path);
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Check that this checkpoint was written by the same configuration.
+    pub fn validate(&self, config: &MiningConfig, threads: usize) -> Result<()> {
+        if self.version != CHECKPOINT_VERSION {
+            return Err(VanityError::Checkpoint(format!(
+                "unsupported checkpoint version {}",
+                self.version
+            )));
+        }
+        if self.config_hash != fingerprint(config, threads) {
+            return Err(VanityError::Checkpoint(
+                "configuration does not match the checkpoint (chain, pattern, seed, mode or threads changed)"
+                    .into(),
+            ));
+        }
+        if self.workers.len() != threads || self.target_found.len() != config.targets.len() {
+            return Err(VanityError::Checkpoint("checkpoint is inconsistent".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Hash of the parts of a run that must not change across a resume.
+///
+/// Stopping conditions (`max_attempts`, limits, deadlines) are left out so a
+/// resumed run may use different ones.
+pub fn fingerprint(config: &MiningConfig, threads: usize) -> String {
+    let mut hasher = Sha3_256::new();
+    hasher.update(format!("v{CHECKPOINT_VERSION}|threads={threads}|"));
+
+    match &config.generation_mode {
+        GenerationMode::PrivateKey => hasher.update("mode=private-key|"),
+        GenerationMode::Mnemonic {
+            word_count,
+            derivation_path,
+        } => hasher.update(format!("mode=mnemonic:{word_count}:{derivation_path}|")),
+        GenerationMode::Range { start, end } => {
+            hasher.update(format!("mode=range:{start:x}:{end:x}|"))
+        }
+        GenerationMode::Recover(template) => hasher.update(format!("mode=recover:{template}|")),
+    }
+
+    if let Some(seed) = &config.seed {
+        hasher.update("seed=");
+        hasher.update(Sha3_256::digest(seed));
+        hasher.update("|");
+    }
+
+    for target in &config.targets {
+        hasher.update(format!(
+            "target={}:{}:{:?}:{}|",
+            target.name.as_deref().unwrap_or_default(),
+            target.chain.id(),
+            target.address_config,
+            target.matcher.description()
+        ));
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+/// `<path>.tmp`, next to the file and unique to its full name.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Checkpoint {
+        Checkpoint {
+            version: CHECKPOINT_VERSION,
+            config_hash: "abc".into(),
+            attempts: 42,
+            found: 1,
+            elapsed_secs: 3.5,
+            target_found: vec![1],
+            workers: vec![WorkerCheckpoint {
+                attempts: 42,
+                position: u128::from(u64::MAX) + 7,
+            }],
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut path = std::env::temp_dir();
+        path.push("vanity_miner_checkpoint_test.json");
+
+        sample().save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), sample());
+        assert!(!tmp_path(&path).exists());
+        // Files that differ only in extension get their own temporary file.
+        assert_ne!(tmp_path(&path), tmp_path(&path.with_extension("tmp")));
+        assert_ne!(tmp_path(&path), tmp_path(&path.with_extension("ckpt")));
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1,13 +1,20 @@
-use std::sync::{mpsc, Arc};
+use std::{
+    sync::{mpsc, Arc},
+    time::Instant,
+};
 
 use bip32::DerivationPath;
 
+use super::cancel::CancellationToken;
 use super::chain::Chain;
+use super::checkpoint::CheckpointConfig;
 use super::matcher::Matcher;
+use super::range::U256;
+use super::recovery::RecoveryTemplate;
 use super::types::FoundAddress;
 
 /// Supported output encodings for formatted addresses.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Encoding {
     Hex,
     HexChecksum,
@@ -18,14 +25,14 @@ pub enum Encoding {
 }
 
 /// Chain-specific formatting options
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ChainConfig {
     Ethereum { checksum: bool },
     Conflux { network_id: u32 },
 }
 
 /// Address formatting configuration passed down to chains.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AddressConfig {
     pub encoding: Encoding,
     pub chain_config: ChainConfig,
@@ -38,6 +45,25 @@ impl AddressConfig {
             chain_config,
         }
     }
+
+    /// Strip the chain prefix so matchers see only the address body.
+    ///
+    /// The body keeps its display case (EIP-55 for Ethereum); matchers apply
+    /// their own `CaseMode`.
+    pub fn matching_body<'a>(&self, address: &'a str) -> &'a str {
+        match &self.chain_config {
+            // Ethereum: strip "0x" prefix, match on raw hex string.
+            ChainConfig::Ethereum { .. } => address
+                .strip_prefix("0x")
+                .or_else(|| address.strip_prefix("0X"))
+                .unwrap_or(address),
+            // Conflux: strip "prefix:" part (e.g. "cfx:" / "cfxtest:"), match on body only.
+            ChainConfig::Conflux { .. } => address
+                .split_once(':')
+                .map(|(_, body)| body)
+                .unwrap_or(address),
+        }
+    }
 }
 
 /// Controls how key material is generated.
@@ -48,24 +74,56 @@ pub enum GenerationMode {
         word_count: usize,
         derivation_path: DerivationPath,
     },
+    /// Walk private keys `start..=end` in order, split evenly across workers.
+    Range {
+        start: U256,
+        end: U256,
+    },
+    /// Enumerate the gaps of a partially known key or mnemonic, split evenly
+    /// across workers.
+    Recover(RecoveryTemplate),
 }
 
-/// Full configuration consumed by executors.
+/// One goal of a mining run: a chain/format, a matcher and a quota.
 #[derive(Clone)]
-pub struct MiningConfig {
+pub struct MiningTarget {
+    /// Name used to tag results; `None` for plain single-target runs.
+    pub name: Option<String>,
     pub chain: Arc<dyn Chain>,
     pub matcher: Arc<dyn Matcher>,
-    pub generation_mode: GenerationMode,
     pub address_config: AddressConfig,
-    pub max_attempts: u64,
+    /// Matches to collect before the target retires (0 = unlimited).
     pub limit: u64,
+}
+
+/// Full configuration consumed by executors.
+///
+/// Every candidate key is checked against all targets that have not yet
+/// reached their limit; the run ends once every target has retired or
+/// `max_attempts` is reached, the `deadline` passes or `cancel` is triggered. All targets share one
+/// key type, so the key is generated by the first target's chain.
+#[derive(Clone)]
+pub struct MiningConfig {
+    pub targets: Vec<MiningTarget>,
+    pub generation_mode: GenerationMode,
+    /// Derive keys from per-worker ChaCha20 streams of this seed instead of
+    /// the OS RNG. Anyone who knows the seed can recreate every key.
+    pub seed: Option<[u8; 32]>,
+    /// Periodically persist progress so the run can be resumed (seeded runs only).
+    pub checkpoint: Option<CheckpointConfig>,
+    pub max_attempts: u64,
+    /// Stop once this instant has passed.
+    pub deadline: Option<Instant>,
     pub result_sender: mpsc::Sender<FoundAddress>,
+    /// Stops the run early when cancelled from another thread.
+    pub cancel: CancellationToken,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::matcher::Matcher;
+    use crate::core::secret::Secret;
     use crate::core::types::{Address, KeyPair, PublicKey, Result, VanityError};
 
     struct TestChain;
@@ -90,16 +148,19 @@ mod tests {
         fn keypair_from_secret(&self, _secret: &[u8]) -> Result<KeyPair> {
             Err(VanityError::CryptoError("unimplemented".into()))
         }
-        fn compute_address(&self, _public_key: &PublicKey) -> Address {
-            Address {
-                raw: Vec::new(),
-                chain_id: "test".into(),
-            }
+        fn compute_address(&self, _public_key: PublicKey<'_>) -> Result<Address> {
+            Ok(Address::new("test", &[]))
         }
-        fn format_address(&self, _address: &Address, _config: &AddressConfig) -> String {
-            "addr".into()
+        fn format_address_into(
+            &self,
+            _address: &Address,
+            _config: &AddressConfig,
+            out: &mut String,
+        ) -> Result<()> {
+            out.push_str("addr");
+            Ok(())
         }
-        fn format_secret(&self, _keypair: &KeyPair) -> String {
+        fn format_secret(&self, _keypair: &KeyPair) -> Secret<String> {
             "secret".into()
         }
         fn validate_address(&self, _address: &str) -> bool {
@@ -127,19 +188,26 @@ mod tests {
     fn mining_config_fields() {
         let (tx, _rx) = mpsc::channel();
         let config = MiningConfig {
-            chain: Arc::new(TestChain),
-            matcher: Arc::new(TestMatcher),
+            targets: vec![MiningTarget {
+                name: None,
+                chain: Arc::new(TestChain),
+                matcher: Arc::new(TestMatcher),
+                address_config: AddressConfig::new(
+                    Encoding::Hex,
+                    ChainConfig::Ethereum { checksum: false },
+                ),
+                limit: 5,
+            }],
             generation_mode: GenerationMode::PrivateKey,
-            address_config: AddressConfig::new(
-                Encoding::Hex,
-                ChainConfig::Ethereum { checksum: false },
-            ),
+            seed: None,
+            checkpoint: None,
             max_attempts: 100,
-            limit: 5,
+            deadline: None,
             result_sender: tx,
+            cancel: CancellationToken::new(),
         };
 
         assert_eq!(config.max_attempts, 100);
-        assert_eq!(config.limit, 5);
+        assert_eq!(config.targets[0].limit, 5);
     }
 }
@@ -0,0 +1,310 @@
+use std::time::Duration;
+
+use super::config::{AddressConfig, ChainConfig};
+use super::matcher::Matcher;
+
+const HEX_CHARSET: &str = "0123456789abcdef";
+const CFX_BASE32_CHARSET: &str = "abcdefghjkmnprstuvwxyz0123456789";
+
+/// Shape of the normalized address body that matchers are applied to.
+///
+/// Used to turn a pattern into a per-candidate match probability. Positions
+/// that the encoding can only fill from a subset of the charset (e.g. the
+/// version bits at the start of a Conflux body) are tracked separately.
+#[derive(Debug, Clone)]
+pub struct AddressSpace {
+    charset: &'static str,
+    body_len: usize,
+    checksum_case: bool,
+    restricted: Vec<(usize, &'static str)>,
+}
+
+impl AddressSpace {
+    /// Lowercase hex body (without `0x`).
+    ///
+    /// With `checksum` set, every letter carries an extra EIP-55 case bit
+    /// when it is matched case-sensitively.
+    pub fn ethereum(checksum: bool) -> Self {
+        Self {
+            charset: HEX_CHARSET,
+            body_len: 40,
+            checksum_case: checksum,
+            restricted: Vec::new(),
+        }
+    }
+
+    /// Lowercase base32 body (without the `cfx:` network prefix).
+    ///
+    /// The first character always encodes the zero version byte, the second
+    /// carries only two address bits and the last payload character is padded
+    /// with two zero bits.
+    pub fn conflux() -> Self {
+        Self {
+            charset: CFX_BASE32_CHARSET,
+            body_len: 42,
+            checksum_case: false,
+            restricted: vec![(0, "a"), (1, "abcd"), (33, "aejnuy26")],
+        }
+    }
+
+    pub fn for_config(config: &AddressConfig) -> Self {
+        match &config.chain_config {
+            ChainConfig::Ethereum { checksum } => Self::ethereum(*checksum),
+            ChainConfig::Conflux { .. } => Self::conflux(),
+        }
+    }
+
+    pub fn charset(&self) -> &'static str {
+        self.charset
+    }
+
+    pub fn body_len(&self) -> usize {
+        self.body_len
+    }
+
+    pub fn checksum_case(&self) -> bool {
+        self.checksum_case
+    }
+
+    /// Characters that can appear at `pos` in the normalized body.
+    pub fn allowed_at(&self, pos: usize) -> &'static str {
+        self.restricted
+            .iter()
+            .find(|(p, _)| *p == pos)
+            .map(|(_, chars)| *chars)
+            .unwrap_or(self.charset)
+    }
+
+    /// Probability that the character at `pos` equals `c`.
+    ///
+    /// With `exact_case` the case of `c` must match as well: checksummed
+    /// encodings halve the odds for letters, lowercase-only encodings can never
+    /// produce an uppercase letter.
+    pub fn char_probability(&self, pos: usize, c: char, exact_case: bool) -> f64 {
+        if pos >= self.body_len {
+            return 0.0;
+        }
+
+        let allowed = self.allowed_at(pos);
+        if !allowed.contains(c.to_ascii_lowercase()) {
+            return 0.0;
+        }
+
+        let p = 1.0 / allowed.len() as f64;
+        if exact_case && c.is_ascii_alphabetic() {
+            if self.checksum_case {
+                return p * 0.5;
+            }
+            if c.is_ascii_uppercase() {
+                return 0.0;
+            }
+        }
+        p
+    }
+
+    /// Probability that `pattern` appears at exactly `start`.
+    pub fn probability_at(&self, start: usize, pattern: &str, exact_case: bool) -> f64 {
+        pattern
+            .chars()
+            .enumerate()
+            .map(|(i, c)| self.char_probability(start + i, c, exact_case))
+            .product()
+    }
+
+    pub fn prefix_probability(&self, pattern: &str, exact_case: bool) -> f64 {
+        self.probability_at(0, pattern, exact_case)
+    }
+
+    pub fn suffix_probability(&self, pattern: &str, exact_case: bool) -> f64 {
+        let len = pattern.chars().count();
+        if len > self.body_len {
+            return 0.0;
+        }
+        self.probability_at(self.body_len - len, pattern, exact_case)
+    }
+
+    /// Probability that `pattern` appears anywhere in the body.
+    pub fn contains_probability(&self, pattern: &str, exact_case: bool) -> f64 {
+        self.anywhere_probability(pattern.chars().count(), |start| {
+            self.probability_at(start, pattern, exact_case)
+        })
+    }
+
+    /// Combine the probabilities of a `len`-character window matching at each
+    /// possible start into the probability of matching somewhere.
+    ///
+    /// Treats the start positions as independent, which is accurate for
+    /// patterns that are rare compared to the body length.
+    pub fn anywhere_probability(&self, len: usize, at: impl Fn(usize) -> f64) -> f64 {
+        if len > self.body_len {
+            return 0.0;
+        }
+
+        let miss: f64 = (0..=self.body_len - len)
+            .map(|start| 1.0 - at(start))
+            .product();
+        1.0 - miss
+    }
+
+    /// Probability that the characters at `positions` are all the same.
+    pub fn same_char_probability(&self, positions: &[usize]) -> f64 {
+        let Some(&first) = positions.first() else {
+            return 1.0;
+        };
+
+        self.allowed_at(first)
+            .chars()
+            .map(|c| {
+                positions
+                    .iter()
+                    .map(|&pos| self.char_probability(pos, c, false))
+                    .product::<f64>()
+            })
+            .sum()
+    }
+}
+
+/// Per-attempt success probability and the statistics derived from it.
+///
+/// Attempts are independent Bernoulli trials, so the number of attempts until
+/// the first match is geometrically distributed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    probability: f64,
+}
+
+impl Difficulty {
+    /// Returns `None` when the pattern can never match.
+    pub fn from_probability(probability: f64) -> Option<Self> {
+        if probability.is_finite() && probability > 0.0 {
+            Some(Self {
+                probability: probability.min(1.0),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Estimate the difficulty of `matcher` over addresses in `space`.
+    pub fn for_matcher(matcher: &dyn Matcher, space: &AddressSpace) -> Option<Self> {
+        Self::from_probability(matcher.probability(space)?)
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    /// Mean number of attempts needed for one match.
+    pub fn expected_attempts(&self) -> f64 {
+        1.0 / self.probability
+    }
+
+    /// Attempts after which a match has been found with probability `target`.
+    pub fn attempts_for_probability(&self, target: f64) -> f64 {
+        if self.probability >= 1.0 {
+            return 1.0;
+        }
+        let target = target.clamp(0.0, 1.0);
+        ((-target).ln_1p() / (-self.probability).ln_1p()).max(1.0)
+    }
+
+    /// Probability of at least one match within `attempts` attempts.
+    ///
+    /// Compared against the actual outcome this is the "luck" of a run: values
+    /// close to 1 with nothing found yet mean the run is unlucky.
+    pub fn probability_after(&self, attempts: u64) -> f64 {
+        if self.probability >= 1.0 {
+            return if attempts > 0 { 1.0 } else { 0.0 };
+        }
+        -(attempts as f64 * (-self.probability).ln_1p()).exp_m1()
+    }
+
+    /// Time needed to reach `attempts` at `rate` attempts per second.
+    pub fn time_for_attempts(attempts: f64, rate: f64) -> Option<Duration> {
+        if rate <= 0.0 || !rate.is_finite() {
+            return None;
+        }
+        Duration::try_from_secs_f64(attempts / rate).ok()
+    }
+
+    pub fn expected_time(&self, rate: f64) -> Option<Duration> {
+        Self::time_for_attempts(self.expected_attempts(), rate)
+    }
+
+    /// Time until a match has been found with probability `target`.
+    pub fn time_for_probability(&self, target: f64, rate: f64) -> Option<Duration> {
+        Self::time_for_attempts(self.attempts_for_probability(target), rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() <= b.abs() * 1e-9
+    }
+
+    #[test]
+    fn ethereum_prefix_and_suffix_use_hex_alphabet() {
+        let space = AddressSpace::ethereum(true);
+        assert!(approx(
+            space.prefix_probability("dead", false),
+            16f64.powi(-4)
+        ));
+        assert!(approx(
+            space.suffix_probability("beef", false),
+            16f64.powi(-4)
+        ));
+        assert_eq!(space.prefix_probability("xyz", false), 0.0);
+    }
+
+    #[test]
+    fn eip55_case_halves_odds_for_letters() {
+        let space = AddressSpace::ethereum(true);
+        // Two letters and two digits: 16^-4 * 2^-2.
+        let p = space.prefix_probability("De12", true);
+        assert!(approx(p, 16f64.powi(-4) / 4.0));
+
+        let plain = AddressSpace::ethereum(false);
+        assert_eq!(plain.prefix_probability("De12", true), 0.0);
+    }
+
+    #[test]
+    fn conflux_accounts_for_fixed_version_bits() {
+        let space = AddressSpace::conflux();
+        // 'a' is forced at position 0, second char has four options.
+        assert!(approx(space.prefix_probability("aa", false), 0.25));
+        assert_eq!(space.prefix_probability("b", false), 0.0);
+        assert!(approx(
+            space.prefix_probability("aamm", false),
+            0.25 * 32f64.powi(-2)
+        ));
+    }
+
+    #[test]
+    fn contains_probability_grows_with_positions() {
+        let space = AddressSpace::ethereum(false);
+        let single = space.prefix_probability("abcd", false);
+        let anywhere = space.contains_probability("abcd", false);
+        assert!(anywhere > 30.0 * single && anywhere < 37.0 * single);
+    }
+
+    #[test]
+    fn difficulty_statistics() {
+        let d = Difficulty::from_probability(1.0 / 65536.0).unwrap();
+        assert!(approx(d.expected_attempts(), 65536.0));
+
+        let median = d.attempts_for_probability(0.5);
+        assert!((median - 65536.0 * std::f64::consts::LN_2).abs() < 1.0);
+        assert!(d.attempts_for_probability(0.99) > d.attempts_for_probability(0.9));
+
+        let luck = d.probability_after(median.round() as u64);
+        assert!((luck - 0.5).abs() < 1e-4);
+
+        assert!(Difficulty::from_probability(0.0).is_none());
+        let eta = d.expected_time(65536.0).unwrap();
+        assert_eq!(eta.as_secs(), 1);
+        assert!(d.expected_time(0.0).is_none());
+    }
+}
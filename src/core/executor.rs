@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
 use super::config::MiningConfig;
-use super::types::MiningResult;
+use super::types::{MiningResult, Result};
 
 /// Executors consume a `MiningConfig` and return aggregated stats.
+///
+/// If any worker fails, the run stops and `VanityError::WorkerFailed` carries
+/// each worker's error together with the statistics gathered so far.
 pub trait Executor: Send + Sync {
-    fn execute(&self, config: MiningConfig) -> MiningResult;
+    fn execute(&self, config: MiningConfig) -> Result<MiningResult>;
 }
 
 /// Shared executor handle.
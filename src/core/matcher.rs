@@ -1,16 +1,24 @@
 use std::sync::Arc;
 
+use super::difficulty::AddressSpace;
+
 /// Address matcher trait used by the executor.
 pub trait Matcher: Send + Sync {
     fn matches(&self, address: &str) -> bool;
     fn description(&self) -> String;
+
+    /// Probability that a random address in `space` matches.
+    ///
+    /// Returns `None` when the matcher cannot be estimated (e.g. regex).
+    fn probability(&self, _space: &AddressSpace) -> Option<f64> {
+        None
+    }
 }
 
 /// Convenient alias for sharing matcher instances.
 pub type DynMatcher = Arc<dyn Matcher>;
 
 #[cfg(test)]
-
 mod tests {
     use std::sync::Arc;
 
@@ -1,8 +1,18 @@
+pub mod cancel;
 pub mod chain;
+pub mod checkpoint;
 pub mod config;
+pub mod difficulty;
 pub mod executor;
 pub mod matcher;
 pub mod provider;
+pub mod range;
+pub mod recovery;
+pub mod secret;
+pub mod shamir;
+pub mod slip39;
 pub mod types;
 
+pub use cancel::CancellationToken;
 pub use provider::ChainProvider;
+pub use secret::Secret;
@@ -48,6 +48,7 @@ impl ChainProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::secret::Secret;
     use crate::core::types::{Address, KeyPair, PublicKey};
 
     struct DummyChain {
@@ -85,23 +86,21 @@ mod tests {
             ))
         }
 
-        fn compute_address(&self, _public_key: &PublicKey) -> Address {
-            Address {
-                raw: Vec::new(),
-                chain_id: self.id.to_string(),
-            }
+        fn compute_address(&self, _public_key: PublicKey<'_>) -> Result<Address> {
+            Ok(Address::new(self.id, &[]))
         }
 
-        fn format_address(
+        fn format_address_into(
             &self,
             _address: &Address,
             _config: &crate::core::config::AddressConfig,
-        ) -> String {
-            String::new()
+            _out: &mut String,
+        ) -> Result<()> {
+            Ok(())
         }
 
-        fn format_secret(&self, _keypair: &KeyPair) -> String {
-            String::new()
+        fn format_secret(&self, _keypair: &KeyPair) -> Secret<String> {
+            Secret::default()
         }
 
         fn validate_address(&self, _address: &str) -> bool {
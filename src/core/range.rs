@@ -0,0 +1,152 @@
+pub use primitive_types::U256;
+
+use super::{
+    secret::Secret,
+    types::{Result, VanityError},
+};
+
+/// Order of the secp256k1 group; valid private keys are `1..N`.
+const SECP256K1_ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+/// An inclusive interval of private keys, walked sequentially.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRange {
+    pub start: U256,
+    pub end: U256,
+}
+
+impl KeyRange {
+    /// Build a range, checking that it is non-empty and holds only valid
+    /// secp256k1 private keys.
+    pub fn new(start: U256, end: U256) -> Result<Self> {
+        let order = U256::from_str_radix(SECP256K1_ORDER, 16).expect("valid constant");
+        if start.is_zero() {
+            return Err(invalid("start must be at least 1"));
+        }
+        if start > end {
+            return Err(invalid("start must not exceed end"));
+        }
+        if end >= order {
+            return Err(invalid("end must be below the secp256k1 group order"));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Parse `START:END`, both hex with an optional `0x`.
+    pub fn parse(s: &str) -> Result<Self> {
+        let (start, end) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("expected START:END in hex"))?;
+        Self::new(parse_hex(start)?, parse_hex(end)?)
+    }
+
+    /// Number of keys in the range.
+    pub fn size(&self) -> U256 {
+        self.end - self.start + U256::one()
+    }
+
+    /// Split into `parts` contiguous sub-ranges of near-equal size, in order.
+    ///
+    /// Parts beyond the number of keys are `None`.
+    pub fn partition(&self, parts: usize) -> Vec<Option<KeyRange>> {
+        let parts_u = U256::from(parts.max(1));
+        let (chunk, rem) = self.size().div_mod(parts_u);
+
+        let mut next = self.start;
+        (0..parts)
+            .map(|i| {
+                let size = chunk + U256::from(u8::from(U256::from(i) < rem));
+                if size.is_zero() {
+                    return None;
+                }
+                let part = KeyRange {
+                    start: next,
+                    end: next + size - U256::one(),
+                };
+                next = part.end + U256::one();
+                Some(part)
+            })
+            .collect()
+    }
+
+    /// The `offset`-th key of the range, if it lies inside.
+    pub fn key_at(&self, offset: u64) -> Option<Secret<[u8; 32]>> {
+        let key = self.start.checked_add(U256::from(offset))?;
+        (key <= self.end).then(|| Secret::new(key.to_big_endian()))
+    }
+
+    /// Fraction of the range covered after `attempts` keys.
+    pub fn progress(&self, attempts: u64) -> f64 {
+        (attempts as f64 / to_f64(self.size())).min(1.0)
+    }
+}
+
+impl std::fmt::Display for KeyRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:x}:0x{:x}", self.start, self.end)
+    }
+}
+
+fn parse_hex(s: &str) -> Result<U256> {
+    let digits = s.trim();
+    let digits = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+        .unwrap_or(digits);
+    if digits.is_empty() || digits.len() > 64 {
+        return Err(invalid(&format!("\"{s}\" is not a 256-bit hex number")));
+    }
+    U256::from_str_radix(digits, 16)
+        .map_err(|_| invalid(&format!("\"{s}\" is not a 256-bit hex number")))
+}
+
+fn to_f64(value: U256) -> f64 {
+    value
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &limb| acc * 2f64.powi(64) + limb as f64)
+}
+
+fn invalid(reason: &str) -> VanityError {
+    VanityError::InvalidRange(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_ranges() {
+        let range = KeyRange::parse("0x10:0x1f").unwrap();
+        assert_eq!(range.size(), U256::from(16));
+        assert_eq!(range.to_string(), "0x10:0x1f");
+
+        assert!(KeyRange::parse("0:10").is_err());
+        assert!(KeyRange::parse("20:10").is_err());
+        assert!(KeyRange::parse("10").is_err());
+        assert!(KeyRange::parse(&format!("1:{SECP256K1_ORDER}")).is_err());
+    }
+
+    #[test]
+    fn partitions_evenly_and_contiguously() {
+        let range = KeyRange::parse("1:a").unwrap();
+        let parts: Vec<KeyRange> = range.partition(3).into_iter().flatten().collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].start, U256::from(1));
+        assert_eq!(parts[0].size(), U256::from(4));
+        assert_eq!(parts[1].start, U256::from(5));
+        assert_eq!(parts[2].end, U256::from(10));
+
+        let small = KeyRange::parse("1:2").unwrap().partition(4);
+        assert_eq!(small.iter().filter(|p| p.is_some()).count(), 2);
+    }
+
+    #[test]
+    fn walks_keys_and_reports_progress() {
+        let range = KeyRange::parse("ff:100").unwrap();
+        assert_eq!(range.key_at(1).unwrap().expose()[30..], [1, 0]);
+        assert!(range.key_at(2).is_none());
+        assert!((range.progress(1) - 0.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,353 @@
+use std::fmt;
+
+use bip32::DerivationPath;
+use bip39::{Language, Mnemonic};
+
+use super::{
+    chain::Chain,
+    secret::Secret,
+    types::{KeyPair, Result, VanityError},
+};
+
+/// Most unknown nibbles in a key template (16^15 candidates).
+const MAX_UNKNOWN_NIBBLES: usize = 15;
+/// Most unknown words in a mnemonic template (2048^5 candidates).
+const MAX_UNKNOWN_WORDS: usize = 5;
+
+/// A partially known secret whose gaps are enumerated to recover it.
+#[derive(Debug, Clone)]
+pub enum RecoveryTemplate {
+    /// A hex private key with `?` nibbles.
+    Key(KeyTemplate),
+    /// A BIP-39 mnemonic with `?` words, derived at `derivation_path`.
+    Mnemonic {
+        template: MnemonicTemplate,
+        derivation_path: DerivationPath,
+    },
+}
+
+impl RecoveryTemplate {
+    /// Number of unknown nibbles or words.
+    pub fn unknown(&self) -> usize {
+        match self {
+            RecoveryTemplate::Key(t) => t.unknown(),
+            RecoveryTemplate::Mnemonic { template, .. } => template.unknown(),
+        }
+    }
+
+    /// Number of candidates, valid or not, indexed `0..candidates()`.
+    pub fn candidates(&self) -> u64 {
+        match self {
+            RecoveryTemplate::Key(t) => t.candidates(),
+            RecoveryTemplate::Mnemonic { template, .. } => template.candidates(),
+        }
+    }
+
+    /// Key pair for candidate `index`, or `None` when the candidate is not a
+    /// valid secret (bad mnemonic checksum, key outside the curve order).
+    pub fn keypair_at(&self, chain: &dyn Chain, index: u64) -> Result<Option<KeyPair>> {
+        match self {
+            RecoveryTemplate::Key(t) => match t.candidate(index) {
+                Some(secret) => chain.keypair_from_secret(secret.expose()).map(Some),
+                None => Ok(None),
+            },
+            RecoveryTemplate::Mnemonic {
+                template,
+                derivation_path,
+            } => match template.candidate(index) {
+                Some(mnemonic) => chain
+                    .derive_from_mnemonic(&mnemonic, derivation_path)
+                    .map(Some),
+                None => Ok(None),
+            },
+        }
+    }
+}
+
+impl fmt::Display for RecoveryTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecoveryTemplate::Key(t) => write!(f, "key:{t}"),
+            RecoveryTemplate::Mnemonic {
+                template,
+                derivation_path,
+            } => write!(f, "mnemonic:{template}:{derivation_path}"),
+        }
+    }
+}
+
+/// A 32-byte private key in hex where `?` marks an unknown nibble.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyTemplate {
+    /// Known nibble values; unknown positions hold 0.
+    nibbles: [u8; 64],
+    /// Unknown positions, most significant first.
+    unknown: Vec<usize>,
+}
+
+impl KeyTemplate {
+    /// Parse 64 hex characters (optional `0x`) with `?` for unknown nibbles.
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if digits.chars().count() != 64 {
+            return Err(invalid(format!(
+                "a private key has 64 hex characters, got {}",
+                digits.chars().count()
+            )));
+        }
+
+        let mut nibbles = [0u8; 64];
+        let mut unknown = Vec::new();
+        for (i, c) in digits.chars().enumerate() {
+            match c {
+                '?' => unknown.push(i),
+                c => {
+                    nibbles[i] = c
+                        .to_digit(16)
+                        .ok_or_else(|| invalid(format!("'{c}' is not a hex digit or '?'")))?
+                        as u8
+                }
+            }
+        }
+
+        if unknown.len() > MAX_UNKNOWN_NIBBLES {
+            return Err(invalid(format!(
+                "at most {MAX_UNKNOWN_NIBBLES} unknown nibbles are supported, got {}",
+                unknown.len()
+            )));
+        }
+        Ok(Self { nibbles, unknown })
+    }
+
+    pub fn unknown(&self) -> usize {
+        self.unknown.len()
+    }
+
+    pub fn candidates(&self) -> u64 {
+        16u64.pow(self.unknown.len() as u32)
+    }
+
+    /// The key with the unknown nibbles filled from `index` (base 16), if it
+    /// is a valid secp256k1 secret.
+    pub fn candidate(&self, index: u64) -> Option<Secret<[u8; 32]>> {
+        let mut nibbles = Secret::new(self.nibbles);
+        let nibbles = nibbles.expose_mut();
+        let mut rest = index;
+        for &pos in self.unknown.iter().rev() {
+            nibbles[pos] = (rest % 16) as u8;
+            rest /= 16;
+        }
+        if rest != 0 {
+            return None;
+        }
+
+        let mut key = Secret::new([0u8; 32]);
+        for (byte, pair) in key.expose_mut().iter_mut().zip(nibbles.chunks(2)) {
+            *byte = pair[0] << 4 | pair[1];
+        }
+        secp256k1::SecretKey::from_byte_array(key.expose()).ok()?;
+        Some(key)
+    }
+}
+
+impl fmt::Debug for KeyTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyTemplate")
+            .field("nibbles", &format_args!("[REDACTED]"))
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+impl fmt::Display for KeyTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, n) in self.nibbles.iter().enumerate() {
+            if self.unknown.contains(&i) {
+                f.write_str("?")?;
+            } else {
+                write!(f, "{n:x}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An English BIP-39 mnemonic where `?` marks an unknown word.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MnemonicTemplate {
+    /// Word indices; unknown positions hold 0.
+    words: Vec<u16>,
+    /// Unknown positions, first word first.
+    unknown: Vec<usize>,
+}
+
+impl MnemonicTemplate {
+    /// Parse a space-separated phrase of 12-24 words with `?` placeholders.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut words = Vec::new();
+        let mut unknown = Vec::new();
+        for (i, word) in s.split_whitespace().enumerate() {
+            if word == "?" {
+                unknown.push(i);
+                words.push(0);
+            } else {
+                let index = Language::English
+                    .find_word(&word.to_lowercase())
+                    .ok_or_else(|| invalid(format!("\"{word}\" is not a BIP-39 English word")))?;
+                words.push(index);
+            }
+        }
+
+        if !(12..=24).contains(&words.len()) || words.len() % 3 != 0 {
+            return Err(invalid(format!(
+                "a mnemonic has 12, 15, 18, 21 or 24 words, got {}",
+                words.len()
+            )));
+        }
+        if unknown.len() > MAX_UNKNOWN_WORDS {
+            return Err(invalid(format!(
+                "at most {MAX_UNKNOWN_WORDS} unknown words are supported, got {}",
+                unknown.len()
+            )));
+        }
+        Ok(Self { words, unknown })
+    }
+
+    pub fn unknown(&self) -> usize {
+        self.unknown.len()
+    }
+
+    pub fn candidates(&self) -> u64 {
+        2048u64.pow(self.unknown.len() as u32)
+    }
+
+    /// The mnemonic with the unknown words filled from `index` (base 2048),
+    /// if its checksum is valid.
+    pub fn candidate(&self, index: u64) -> Option<Mnemonic> {
+        let mut words = self.words.clone();
+        let mut rest = index;
+        for &pos in self.unknown.iter().rev() {
+            words[pos] = (rest % 2048) as u16;
+            rest /= 2048;
+        }
+        if rest != 0 {
+            return None;
+        }
+
+        // Pack the 11-bit words; the entropy is everything but the checksum.
+        let mut bits = [0u8; 33];
+        for (i, &word) in words.iter().enumerate() {
+            for j in 0..11 {
+                if word & (1 << (10 - j)) != 0 {
+                    let bit = i * 11 + j;
+                    bits[bit / 8] |= 1 << (7 - bit % 8);
+                }
+            }
+        }
+        let entropy = &bits[..words.len() * 4 / 3];
+
+        // Re-deriving the phrase recomputes the checksum in the last word.
+        let mnemonic = Mnemonic::from_entropy(entropy).ok()?;
+        let last = mnemonic.word_indices().last()?;
+        (last == usize::from(*words.last()?)).then_some(mnemonic)
+    }
+}
+
+impl fmt::Debug for MnemonicTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MnemonicTemplate")
+            .field("words", &format_args!("[REDACTED]"))
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+impl fmt::Display for MnemonicTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = Language::English.word_list();
+        for (i, &word) in self.words.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            if self.unknown.contains(&i) {
+                f.write_str("?")?;
+            } else {
+                f.write_str(list[usize::from(word)])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid(reason: String) -> VanityError {
+    VanityError::InvalidRecovery(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn key_template_fills_unknown_nibbles() {
+        let template = KeyTemplate::parse(&format!("0x{}?1", "0".repeat(62))).unwrap();
+        assert_eq!(template.candidates(), 16);
+        assert_eq!(template.to_string(), format!("{}?1", "0".repeat(62)));
+
+        let key = template.candidate(0xa).unwrap();
+        assert_eq!(key.expose()[31], 0xa1);
+        assert!(KeyTemplate::parse("12?").is_err());
+        assert!(KeyTemplate::parse(&"?".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn key_template_skips_invalid_secrets() {
+        let zero = KeyTemplate::parse(&format!("{}?", "0".repeat(63))).unwrap();
+        assert!(zero.candidate(0).is_none());
+        assert!(zero.candidate(1).is_some());
+    }
+
+    #[test]
+    fn mnemonic_template_enumerates_checksum_valid_phrases() {
+        let template = MnemonicTemplate::parse(&PHRASE.replacen("abandon", "?", 1)).unwrap();
+        assert_eq!(template.unknown(), 1);
+        assert_eq!(template.candidates(), 2048);
+
+        let valid: Vec<Mnemonic> = (0..template.candidates())
+            .filter_map(|i| template.candidate(i))
+            .collect();
+        // A 4-bit checksum leaves about one in sixteen first words valid.
+        assert!(valid.len() > 64 && valid.len() < 200);
+        assert!(valid.iter().any(|m| m.to_string() == PHRASE));
+    }
+
+    #[test]
+    fn debug_hides_the_known_parts() {
+        let key = KeyTemplate::parse(&format!("{}?", "ab".repeat(31) + "c")).unwrap();
+        let debug = format!("{:?}", RecoveryTemplate::Key(key));
+        assert!(!debug.contains("abab"), "{debug}");
+        assert!(debug.contains("[REDACTED]"));
+
+        let mnemonic = MnemonicTemplate::parse(&PHRASE.replacen("abandon", "?", 1)).unwrap();
+        let debug = format!("{mnemonic:?}");
+        assert!(
+            !debug.contains("abandon") && !debug.contains("about"),
+            "{debug}"
+        );
+        assert!(debug.contains("unknown: [0]"));
+    }
+
+    #[test]
+    fn mnemonic_template_rejects_bad_input() {
+        assert!(MnemonicTemplate::parse("abandon ? zoo").is_err());
+        assert!(MnemonicTemplate::parse(&PHRASE.replace("about", "notaword")).is_err());
+        let many = ["?"; 6].join(" ") + &" abandon".repeat(6);
+        assert!(MnemonicTemplate::parse(&many).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use std::{fmt, iter, str::FromStr, sync::OnceLock};
+
+use rand::RngCore;
+
+use super::{
+    secret::Secret,
+    types::{Result, VanityError},
+};
+
+/// Most shares a secret can be split into (a SLIP-39 limit, kept for plain
+/// shares too).
+pub const MAX_SHARES: u8 = 16;
+
+/// How many shares to create and how many recover the secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharePolicy {
+    pub threshold: u8,
+    pub count: u8,
+}
+
+impl SharePolicy {
+    pub fn new(threshold: u8, count: u8) -> Result<Self> {
+        if threshold < 2 || threshold > count || count > MAX_SHARES {
+            return Err(VanityError::Shares(format!(
+                "need 2 <= threshold <= shares <= {MAX_SHARES}, got {threshold}-of-{count}"
+            )));
+        }
+        Ok(Self { threshold, count })
+    }
+}
+
+/// Parses `M-of-N`, e.g. `3-of-5`.
+impl FromStr for SharePolicy {
+    type Err = VanityError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || VanityError::Shares(format!("expected M-of-N like 3-of-5, got \"{s}\""));
+        let (threshold, count) = s.split_once("-of-").ok_or_else(invalid)?;
+        Self::new(
+            threshold.trim().parse().map_err(|_| invalid())?,
+            count.trim().parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl fmt::Display for SharePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-of-{}", self.threshold, self.count)
+    }
+}
+
+/// One share: the value of the sharing polynomial at `index`.
+#[derive(Debug, Clone)]
+pub struct Share {
+    pub index: u8,
+    pub value: Secret<Vec<u8>>,
+}
+
+/// Split `secret` with a random polynomial; share `i` (1-based) is its value
+/// at `x = i`, the secret its value at `x = 0`.
+pub fn split(secret: &[u8], policy: SharePolicy, rng: &mut dyn RngCore) -> Vec<Share> {
+    // The first threshold - 1 shares are random; with the secret they fix
+    // the polynomial the remaining shares are read from.
+    let mut shares: Vec<Share> = (1..policy.threshold)
+        .map(|index| {
+            let mut value = Secret::new(vec![0u8; secret.len()]);
+            rng.fill_bytes(value.expose_mut());
+            Share { index, value }
+        })
+        .collect();
+    let base = Share {
+        index: 0,
+        value: secret.to_vec().into(),
+    };
+    let points: Vec<&Share> = iter::once(&base).chain(&shares).collect();
+    let rest: Vec<Share> = (policy.threshold..=policy.count)
+        .map(|index| Share {
+            index,
+            value: interpolate(&points, index),
+        })
+        .collect();
+    shares.extend(rest);
+    shares
+}
+
+/// Recover the secret from at least `threshold` shares of [`split`].
+pub fn combine(shares: &[Share]) -> Result<Secret<Vec<u8>>> {
+    check_shares(shares)?;
+    Ok(interpolate(&shares.iter().collect::<Vec<_>>(), 0))
+}
+
+/// Shares must have distinct indexes and equally long values.
+pub(crate) fn check_shares(shares: &[Share]) -> Result<()> {
+    let Some(first) = shares.first() else {
+        return Err(VanityError::Shares("no shares given".into()));
+    };
+    for (i, share) in shares.iter().enumerate() {
+        if share.value.expose().len() != first.value.expose().len() {
+            return Err(VanityError::Shares("shares have different lengths".into()));
+        }
+        if shares[..i].iter().any(|s| s.index == share.index) {
+            return Err(VanityError::Shares(format!(
+                "share {} given twice",
+                share.index
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Value at `x` of the polynomial through `shares` (Lagrange interpolation
+/// in GF(256)). Indexes must be distinct.
+pub(crate) fn interpolate(shares: &[&Share], x: u8) -> Secret<Vec<u8>> {
+    if let Some(share) = shares.iter().find(|s| s.index == x) {
+        return share.value.clone();
+    }
+    let (exp, log) = tables();
+    let log_of = |a: u8| i32::from(log[usize::from(a)]);
+    let log_product: i32 = shares.iter().map(|s| log_of(s.index ^ x)).sum();
+
+    let mut result = Secret::new(vec![0u8; shares[0].value.expose().len()]);
+    for share in shares {
+        let others: i32 = shares
+            .iter()
+            .filter(|other| other.index != share.index)
+            .map(|other| log_of(share.index ^ other.index))
+            .sum();
+        // log of the Lagrange basis polynomial of `share`, evaluated at `x`.
+        let basis = log_product - log_of(share.index ^ x) - others;
+        for (out, &y) in result.expose_mut().iter_mut().zip(share.value.expose()) {
+            if y != 0 {
+                *out ^= exp[(log_of(y) + basis).rem_euclid(255) as usize];
+            }
+        }
+    }
+    result
+}
+
+/// Exponent and logarithm tables of GF(256) with the AES polynomial
+/// x^8 + x^4 + x^3 + x + 1 and generator 3.
+fn tables() -> &'static ([u8; 255], [u8; 256]) {
+    static TABLES: OnceLock<([u8; 255], [u8; 256])> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut exp = [0u8; 255];
+        let mut log = [0u8; 256];
+        let mut value: u16 = 1;
+        for (i, e) in exp.iter_mut().enumerate() {
+            *e = value as u8;
+            log[usize::from(value)] = i as u8;
+            value ^= value << 1;
+            if value & 0x100 != 0 {
+                value ^= 0x11b;
+            }
+        }
+        (exp, log)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn any_threshold_shares_recover_the_secret() {
+        let secret: Vec<u8> = (1..=32).collect();
+        let policy: SharePolicy = "3-of-5".parse().unwrap();
+        let shares = split(&secret, policy, &mut StdRng::seed_from_u64(7));
+        assert_eq!(shares.len(), 5);
+        assert_eq!(
+            shares.iter().map(|s| s.index).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5]
+        );
+
+        for picked in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<Share> = picked.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(combine(&subset).unwrap().expose(), &secret);
+        }
+        // Fewer than the threshold give an unrelated value.
+        assert_ne!(combine(&shares[..2]).unwrap().expose(), &secret);
+        assert!(combine(&[shares[0].clone(), shares[0].clone()]).is_err());
+    }
+
+    #[test]
+    fn parses_policies() {
+        assert_eq!(
+            "2-of-3".parse::<SharePolicy>().unwrap(),
+            SharePolicy {
+                threshold: 2,
+                count: 3
+            }
+        );
+        for bad in ["1-of-3", "4-of-3", "2-of-17", "2/3", "x-of-3"] {
+            assert!(bad.parse::<SharePolicy>().is_err(), "{bad}");
+        }
+    }
+}
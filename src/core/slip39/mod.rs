@@ -0,0 +1,488 @@
+//! SLIP-39 mnemonic shares (<https://github.com/satoshilabs/slips/blob/master/slip-0039.md>).
+
+mod wordlist;
+
+use std::collections::BTreeMap;
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use super::{
+    secret::Secret,
+    shamir::{check_shares, interpolate, Share, SharePolicy},
+    types::{Result, VanityError},
+};
+use wordlist::WORDLIST;
+
+const RADIX_BITS: usize = 10;
+/// Identifier, flags and sharing parameters: 40 bits.
+const HEADER_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+const MIN_SECRET_LEN: usize = 16;
+/// PBKDF2 work of the encryption, split over its rounds.
+const BASE_ITERATIONS: u32 = 10_000;
+const ROUNDS: u8 = 4;
+/// The iteration exponent of new shares: 20000 PBKDF2 iterations in total.
+const ITERATION_EXPONENT: u8 = 1;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_LEN: usize = 4;
+
+/// Split `master_secret` into SLIP-39 mnemonics: one group whose members are
+/// the shares, any `policy.threshold` of which recover it. The passphrase is
+/// empty.
+pub fn split(
+    master_secret: &[u8],
+    policy: SharePolicy,
+    rng: &mut dyn RngCore,
+) -> Result<Vec<Secret<String>>> {
+    if master_secret.len() < MIN_SECRET_LEN || !master_secret.len().is_multiple_of(2) {
+        return Err(VanityError::Shares(format!(
+            "SLIP-39 secrets must be an even number of bytes, at least {MIN_SECRET_LEN}"
+        )));
+    }
+
+    let identifier = (rng.next_u32() & 0x7fff) as u16;
+    let encrypted = feistel(
+        master_secret,
+        b"",
+        ITERATION_EXPONENT,
+        identifier,
+        false,
+        true,
+    );
+    let members = split_with_digest(encrypted.expose(), policy, rng);
+    Ok(members
+        .into_iter()
+        .map(|member| {
+            ShareData {
+                identifier,
+                extendable: false,
+                iteration_exponent: ITERATION_EXPONENT,
+                group_index: 0,
+                group_threshold: 1,
+                group_count: 1,
+                member_index: member.index,
+                member_threshold: policy.threshold,
+                value: member.value,
+            }
+            .to_mnemonic()
+        })
+        .collect())
+}
+
+/// Recover the master secret from SLIP-39 mnemonics, with any group layout.
+pub fn combine(mnemonics: &[&str], passphrase: &str) -> Result<Secret<Vec<u8>>> {
+    let shares = mnemonics
+        .iter()
+        .map(|m| ShareData::parse(m))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(first) = shares.first() else {
+        return Err(VanityError::Shares("no SLIP-39 shares given".into()));
+    };
+    if shares.iter().any(|s| {
+        (s.identifier, s.extendable, s.iteration_exponent)
+            != (first.identifier, first.extendable, first.iteration_exponent)
+            || (s.group_threshold, s.group_count) != (first.group_threshold, first.group_count)
+    }) {
+        return Err(VanityError::Shares(
+            "the SLIP-39 shares belong to different secrets".into(),
+        ));
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&ShareData>> = BTreeMap::new();
+    for share in &shares {
+        groups.entry(share.group_index).or_default().push(share);
+    }
+    let mut group_shares = Vec::new();
+    let mut incomplete = Vec::new();
+    for (&group_index, members) in &groups {
+        let threshold = members[0].member_threshold;
+        if members.iter().any(|m| m.member_threshold != threshold) {
+            return Err(VanityError::Shares(format!(
+                "group {group_index} has mixed member thresholds"
+            )));
+        }
+        if members.len() < usize::from(threshold) {
+            incomplete.push(format!(
+                "group {group_index} has {} of its {threshold} shares",
+                members.len()
+            ));
+            continue;
+        }
+        let members: Vec<Share> = members
+            .iter()
+            .map(|m| Share {
+                index: m.member_index,
+                value: m.value.clone(),
+            })
+            .collect();
+        group_shares.push(Share {
+            index: group_index,
+            value: recover_with_digest(&members, threshold)?,
+        });
+    }
+    if group_shares.len() < usize::from(first.group_threshold) {
+        return Err(VanityError::Shares(format!(
+            "not enough SLIP-39 shares: {} of {} groups complete; {}",
+            group_shares.len(),
+            first.group_threshold,
+            incomplete.join(", ")
+        )));
+    }
+
+    let encrypted = recover_with_digest(&group_shares, first.group_threshold)?;
+    Ok(feistel(
+        encrypted.expose(),
+        passphrase.as_bytes(),
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+        false,
+    ))
+}
+
+/// SLIP-39 sharing: members `0..count`, with the secret at index 255 and a
+/// digest of it at 254 so recovery can detect bad shares.
+fn split_with_digest(secret: &[u8], policy: SharePolicy, rng: &mut dyn RngCore) -> Vec<Share> {
+    let random_count = policy.threshold - 2;
+    let mut base: Vec<Share> = (0..random_count)
+        .map(|index| {
+            let mut value = Secret::new(vec![0u8; secret.len()]);
+            rng.fill_bytes(value.expose_mut());
+            Share { index, value }
+        })
+        .collect();
+
+    let mut random_part = Secret::new(vec![0u8; secret.len() - DIGEST_LEN]);
+    rng.fill_bytes(random_part.expose_mut());
+    let mut digest = Secret::new(digest(random_part.expose(), secret));
+    digest.expose_mut().extend_from_slice(random_part.expose());
+    base.push(Share {
+        index: DIGEST_INDEX,
+        value: digest,
+    });
+    base.push(Share {
+        index: SECRET_INDEX,
+        value: secret.to_vec().into(),
+    });
+
+    let points: Vec<&Share> = base.iter().collect();
+    let rest: Vec<Share> = (random_count..policy.count)
+        .map(|index| Share {
+            index,
+            value: interpolate(&points, index),
+        })
+        .collect();
+    base.truncate(usize::from(random_count));
+    base.extend(rest);
+    base
+}
+
+fn recover_with_digest(shares: &[Share], threshold: u8) -> Result<Secret<Vec<u8>>> {
+    check_shares(shares)?;
+    if threshold == 1 {
+        return Ok(shares[0].value.clone());
+    }
+    let points: Vec<&Share> = shares.iter().collect();
+    let secret = interpolate(&points, SECRET_INDEX);
+    let digest_share = interpolate(&points, DIGEST_INDEX);
+    let (expected, random_part) = digest_share.expose().split_at(DIGEST_LEN);
+    if digest(random_part, secret.expose())[..] != *expected {
+        return Err(VanityError::Shares(
+            "SLIP-39 digest mismatch: a share is wrong or missing".into(),
+        ));
+    }
+    Ok(secret)
+}
+
+fn digest(key: &[u8], secret: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes any key length");
+    mac.update(secret);
+    mac.finalize().into_bytes()[..DIGEST_LEN].to_vec()
+}
+
+/// The four-round Feistel cipher that protects the master secret.
+fn feistel(
+    input: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    encrypt: bool,
+) -> Secret<Vec<u8>> {
+    let half = input.len() / 2;
+    let mut left = Secret::new(input[..half].to_vec());
+    let mut right = Secret::new(input[half..].to_vec());
+    let mut salt_prefix = Vec::new();
+    if !extendable {
+        salt_prefix.extend_from_slice(b"shamir");
+        salt_prefix.extend_from_slice(&identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATIONS << iteration_exponent) / u32::from(ROUNDS);
+
+    let rounds: Vec<u8> = if encrypt {
+        (0..ROUNDS).collect()
+    } else {
+        (0..ROUNDS).rev().collect()
+    };
+    for round in rounds {
+        let mut password = Secret::new(Vec::with_capacity(1 + passphrase.len()));
+        password.expose_mut().push(round);
+        password.expose_mut().extend_from_slice(passphrase);
+        let mut salt = Secret::new(Vec::with_capacity(salt_prefix.len() + half));
+        salt.expose_mut().extend_from_slice(&salt_prefix);
+        salt.expose_mut().extend_from_slice(right.expose());
+
+        let mut round_key = Secret::new(vec![0u8; half]);
+        pbkdf2::pbkdf2_hmac::<Sha256>(
+            password.expose(),
+            salt.expose(),
+            iterations,
+            round_key.expose_mut(),
+        );
+        for (l, k) in left.expose_mut().iter_mut().zip(round_key.expose()) {
+            *l ^= k;
+        }
+        std::mem::swap(&mut left, &mut right);
+    }
+
+    let mut output = Secret::new(Vec::with_capacity(input.len()));
+    output.expose_mut().extend_from_slice(right.expose());
+    output.expose_mut().extend_from_slice(left.expose());
+    output
+}
+
+/// One decoded SLIP-39 mnemonic.
+struct ShareData {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Secret<Vec<u8>>,
+}
+
+impl ShareData {
+    fn header(&self) -> u64 {
+        u64::from(self.identifier) << 25
+            | u64::from(self.extendable) << 24
+            | u64::from(self.iteration_exponent) << 20
+            | u64::from(self.group_index) << 16
+            | u64::from(self.group_threshold - 1) << 12
+            | u64::from(self.group_count - 1) << 8
+            | u64::from(self.member_index) << 4
+            | u64::from(self.member_threshold - 1)
+    }
+
+    fn to_mnemonic(&self) -> Secret<String> {
+        let value = self.value.expose();
+        let mut words = Secret::new(Vec::with_capacity(
+            HEADER_WORDS + (value.len() * 8).div_ceil(RADIX_BITS) + CHECKSUM_WORDS,
+        ));
+        let header = self.header();
+        for i in (0..HEADER_WORDS).rev() {
+            words
+                .expose_mut()
+                .push((header >> (i * RADIX_BITS)) as u16 & 0x3ff);
+        }
+
+        // The value is left-padded with zero bits to a whole number of words.
+        let mut acc: u32 = 0;
+        let mut bits = (RADIX_BITS - value.len() * 8 % RADIX_BITS) % RADIX_BITS;
+        for &byte in value {
+            acc = acc << 8 | u32::from(byte);
+            bits += 8;
+            while bits >= RADIX_BITS {
+                bits -= RADIX_BITS;
+                words.expose_mut().push((acc >> bits) as u16 & 0x3ff);
+            }
+            acc &= (1 << bits) - 1;
+        }
+
+        words.expose_mut().extend([0; CHECKSUM_WORDS]);
+        let checksum = polymod(customization(self.extendable), words.expose()) ^ 1;
+        for (i, word) in words
+            .expose_mut()
+            .iter_mut()
+            .rev()
+            .take(CHECKSUM_WORDS)
+            .enumerate()
+        {
+            *word = (checksum >> (i * RADIX_BITS)) as u16 & 0x3ff;
+        }
+
+        let mut mnemonic = Secret::new(String::with_capacity(words.expose().len() * 9));
+        for (i, &word) in words.expose().iter().enumerate() {
+            if i > 0 {
+                mnemonic.expose_mut().push(' ');
+            }
+            mnemonic.expose_mut().push_str(WORDLIST[usize::from(word)]);
+        }
+        mnemonic
+    }
+
+    fn parse(mnemonic: &str) -> Result<Self> {
+        let invalid = |what: &str| VanityError::Shares(format!("invalid SLIP-39 share: {what}"));
+        let words = Secret::new(
+            mnemonic
+                .split_whitespace()
+                .map(|word| {
+                    WORDLIST
+                        .binary_search(&word.to_ascii_lowercase().as_str())
+                        .map(|i| i as u16)
+                        .map_err(|_| invalid(&format!("unknown word \"{word}\"")))
+                })
+                .collect::<Result<Vec<u16>>>()?,
+        );
+        let words = words.expose();
+        let min_words = HEADER_WORDS + (MIN_SECRET_LEN * 8).div_ceil(RADIX_BITS) + CHECKSUM_WORDS;
+        if words.len() < min_words {
+            return Err(invalid(&format!("{} words is too short", words.len())));
+        }
+
+        let header = words[..HEADER_WORDS]
+            .iter()
+            .fold(0u64, |acc, &w| acc << RADIX_BITS | u64::from(w));
+        let extendable = header >> 24 & 1 == 1;
+        if polymod(customization(extendable), words) != 1 {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        let value_words = &words[HEADER_WORDS..words.len() - CHECKSUM_WORDS];
+        let padding = value_words.len() * RADIX_BITS % 16;
+        if padding > 8 {
+            return Err(invalid(&format!(
+                "{} words is not a valid length",
+                words.len()
+            )));
+        }
+        let mut value = Secret::new(Vec::with_capacity(value_words.len() * RADIX_BITS / 8));
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        let mut skip = padding;
+        for &word in value_words {
+            acc = acc << RADIX_BITS | u32::from(word);
+            bits += RADIX_BITS;
+            if skip > 0 {
+                bits -= skip;
+                if acc >> bits != 0 {
+                    return Err(invalid("padding bits are not zero"));
+                }
+                skip = 0;
+            }
+            while bits >= 8 {
+                bits -= 8;
+                value.expose_mut().push((acc >> bits) as u8);
+            }
+            acc &= (1 << bits) - 1;
+        }
+
+        let share = ShareData {
+            identifier: (header >> 25) as u16,
+            extendable,
+            iteration_exponent: (header >> 20 & 0xf) as u8,
+            group_index: (header >> 16 & 0xf) as u8,
+            group_threshold: (header >> 12 & 0xf) as u8 + 1,
+            group_count: (header >> 8 & 0xf) as u8 + 1,
+            member_index: (header >> 4 & 0xf) as u8,
+            member_threshold: (header & 0xf) as u8 + 1,
+            value,
+        };
+        if share.group_threshold > share.group_count {
+            return Err(invalid("group threshold exceeds the group count"));
+        }
+        Ok(share)
+    }
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    }
+}
+
+/// RS1024 remainder of `words`; it is 1 over a share with a valid checksum.
+fn polymod(customization: &[u8], words: &[u16]) -> u32 {
+    const GENERATOR: [u32; 10] = [
+        0xe0e040, 0x1c1c080, 0x3838100, 0x7070200, 0xe0e0009, 0x1c0c2412, 0x38086c24, 0x3090fc48,
+        0x21b1f890, 0x3f3f120,
+    ];
+    let values = customization
+        .iter()
+        .map(|&b| u32::from(b))
+        .chain(words.iter().map(|&w| u32::from(w)));
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 20;
+        chk = (chk & 0xfffff) << 10 ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if top >> i & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Test vectors 1 and 4 of SLIP-39, passphrase "TREZOR".
+    const SINGLE: &str = "duckling enlarge academic academic agency result length solution fridge \
+                          kidney coal piece deal husband erode duke ajar critical decision keyboard";
+    const TWO_OF_THREE: [&str; 2] = [
+        "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist \
+         rescue view short owner flip making coding armed",
+        "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip \
+         twice unkind craft early superior advocate guest smoking",
+    ];
+
+    #[test]
+    fn recovers_reference_vectors() {
+        assert_eq!(
+            hex::encode(combine(&[SINGLE], "TREZOR").unwrap().expose()),
+            "bb54aac4b89dc868ba37d9cc21b2cece"
+        );
+        assert_eq!(
+            hex::encode(combine(&TWO_OF_THREE, "TREZOR").unwrap().expose()),
+            "b43ceb7e57a0ea8766221624d01b0864"
+        );
+        assert!(combine(&TWO_OF_THREE[..1], "TREZOR").is_err());
+        // A changed word breaks the checksum.
+        let typo = SINGLE.replace("keyboard", "kidney");
+        assert!(combine(&[typo.as_str()], "TREZOR").is_err());
+    }
+
+    #[test]
+    fn split_shares_round_trip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for len in [16, 32] {
+            let secret: Vec<u8> = (0..len as u8).collect();
+            let policy = SharePolicy::new(3, 5).unwrap();
+            let shares = split(&secret, policy, &mut rng).unwrap();
+            assert_eq!(shares.len(), 5);
+            assert_eq!(
+                shares[0].expose().split(' ').count(),
+                if len == 16 { 20 } else { 33 }
+            );
+
+            let picked: Vec<&str> = [4, 0, 2]
+                .iter()
+                .map(|&i| shares[i].expose().as_str())
+                .collect();
+            assert_eq!(combine(&picked, "").unwrap().expose(), &secret);
+            let too_few: Vec<&str> = shares[..2].iter().map(|s| s.expose().as_str()).collect();
+            assert!(combine(&too_few, "").is_err());
+        }
+        assert!(split(&[0u8; 15], SharePolicy::new(2, 3).unwrap(), &mut rng).is_err());
+    }
+}
@@ -1,6 +1,10 @@
 use bip32::DerivationPath;
 use bip39::Mnemonic;
+use chrono::{DateTime, Utc};
+
+use super::{chain::Chain, secret::Secret};
 use thiserror::Error;
+use zeroize::Zeroizing;
 pub type Result<T> = std::result::Result<T, VanityError>;
 
 /// Unified error enum for the vanity miner
@@ -18,42 +22,160 @@ pub enum VanityError {
     #[error("cryptographic error: {0}")]
     CryptoError(String),
 
+    #[error("address formatting error: {0}")]
+    AddressFormat(String),
+
+    /// The matcher error already names what is invalid, e.g. "invalid mask: ...".
+    #[error("{0}")]
+    InvalidPattern(String),
+
+    #[error("invalid key range: {0}")]
+    InvalidRange(String),
+
+    #[error("invalid recovery template: {0}")]
+    InvalidRecovery(String),
+
+    #[error("invalid time budget: {0}")]
+    InvalidTimeBudget(String),
+
+    #[error("invalid job file: {0}")]
+    InvalidJob(String),
+
     #[error("duplicate chain id: {0}")]
     DuplicateChainId(String),
 
+    #[error("checkpoint error: {0}")]
+    Checkpoint(String),
+
+    #[error("executor error: {0}")]
+    Executor(String),
+
+    #[error("scheduling error: {0}")]
+    Scheduling(String),
+
+    #[error("keystore error: {0}")]
+    Keystore(String),
+
+    #[error("password error: {0}")]
+    Password(String),
+
+    #[error("invalid output format: {0}")]
+    OutputFormat(String),
+
+    #[error("database error: {0}")]
+    Database(String),
+
+    #[error("secret sharing error: {0}")]
+    Shares(String),
+
+    #[error("verification failed: {0}")]
+    Verification(String),
+
+    #[error("decryption failed: {0}")]
+    Decryption(String),
+
+    #[error("result receiver disconnected")]
+    ResultChannelClosed,
+
+    #[error("{}", describe_worker_errors(.errors))]
+    WorkerFailed {
+        errors: Vec<WorkerError>,
+        /// Statistics up to the failure; `stop_reason` is `StopReason::Error`.
+        partial: Box<MiningResult>,
+    },
+
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
+/// An error raised by one executor worker.
+#[derive(Debug, Error)]
+#[error("worker {worker}: {error}")]
+pub struct WorkerError {
+    pub worker: usize,
+    pub error: VanityError,
+}
+
+fn describe_worker_errors(errors: &[WorkerError]) -> String {
+    let details: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    format!("{} worker(s) failed: {}", errors.len(), details.join("; "))
+}
+
+/// Length of an uncompressed secp256k1 public key (`0x04 || x || y`).
+pub const SECP256K1_PUBLIC_LEN: usize = 65;
+
+/// Longest raw address any chain produces.
+pub const MAX_ADDRESS_LEN: usize = 32;
+
 /// key pair variants supported by different chains
 #[derive(Debug, Clone)]
 pub enum KeyPair {
     Secp256k1 {
-        secret: [u8; 32],
-        public: Vec<u8>,
-        mnemonic: Option<Mnemonic>,
+        secret: Secret<[u8; 32]>,
+        /// Uncompressed public key.
+        public: [u8; SECP256K1_PUBLIC_LEN],
+        mnemonic: Option<Secret<Mnemonic>>,
         derivation_path: Option<DerivationPath>,
     },
     Ed25519 {
-        secret: [u8; 32],
+        secret: Secret<[u8; 32]>,
         public: [u8; 32],
-        mnemonic: Option<Mnemonic>,
+        mnemonic: Option<Secret<Mnemonic>>,
         derivation_path: Option<DerivationPath>,
     },
 }
 
-/// Public key container
-#[derive(Debug, Clone)]
-pub enum PublicKey {
-    Secp256k1(Vec<u8>),
-    Ed25519([u8; 32]),
+impl KeyPair {
+    /// The public half, borrowed from the key pair.
+    pub fn public_key(&self) -> PublicKey<'_> {
+        match self {
+            KeyPair::Secp256k1 { public, .. } => PublicKey::Secp256k1(public),
+            KeyPair::Ed25519 { public, .. } => PublicKey::Ed25519(public),
+        }
+    }
 }
 
-/// Raw address with chain ID
-#[derive(Debug, Clone)]
+/// Public key container, borrowed from a `KeyPair`.
+#[derive(Debug, Clone, Copy)]
+pub enum PublicKey<'a> {
+    Secp256k1(&'a [u8]),
+    Ed25519(&'a [u8; 32]),
+}
+
+impl PublicKey<'_> {
+    /// Lowercase hex of the key bytes, as stored with each result.
+    pub fn to_hex(&self) -> String {
+        match self {
+            PublicKey::Secp256k1(public) => hex::encode(public),
+            PublicKey::Ed25519(public) => hex::encode(public),
+        }
+    }
+}
+
+/// Raw address with chain ID, stored inline so computing one never allocates.
+#[derive(Debug, Clone, Copy)]
 pub struct Address {
-    pub raw: Vec<u8>,
-    pub chain_id: String,
+    bytes: [u8; MAX_ADDRESS_LEN],
+    len: u8,
+    pub chain_id: &'static str,
+}
+
+impl Address {
+    /// Panics if `raw` is longer than `MAX_ADDRESS_LEN`.
+    pub fn new(chain_id: &'static str, raw: &[u8]) -> Self {
+        assert!(raw.len() <= MAX_ADDRESS_LEN, "address too long");
+        let mut bytes = [0u8; MAX_ADDRESS_LEN];
+        bytes[..raw.len()].copy_from_slice(raw);
+        Self {
+            bytes,
+            len: raw.len() as u8,
+            chain_id,
+        }
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
 }
 
 /// Information emitted when a matching address is found.
@@ -61,16 +183,93 @@ pub struct Address {
 pub struct FoundAddress {
     pub address: String,
     pub secret: SecretInfo,
+    /// Name of the target that matched, for multi-target runs.
+    pub target: Option<String>,
+    /// Id of the chain the address belongs to, e.g. `ethereum`.
+    pub chain: String,
+    /// Network id for chains that have one (Conflux).
+    pub network: Option<u32>,
+    /// Public key as hex (uncompressed for secp256k1).
+    pub public_key: String,
+    /// Description of the pattern that matched.
+    pub pattern: String,
+    /// 1-based attempt number within the worker that found the address.
+    pub attempt: u64,
+    pub worker: usize,
+    pub found_at: DateTime<Utc>,
+}
+
+impl FoundAddress {
+    /// A result without metadata, found now.
+    pub fn new(address: impl Into<String>, secret: SecretInfo) -> Self {
+        Self {
+            address: address.into(),
+            secret,
+            target: None,
+            chain: String::new(),
+            network: None,
+            public_key: String::new(),
+            pattern: String::new(),
+            attempt: 0,
+            worker: 0,
+            found_at: Utc::now(),
+        }
+    }
 }
 
 /// Secrets can be private keys or mnemonic phrases.
 #[derive(Debug, Clone)]
 pub enum SecretInfo {
-    PrivateKey(String),
+    PrivateKey(Secret<String>),
     Mnemonic {
-        phrase: String,
+        phrase: Secret<String>,
         derivation_path: String,
     },
+    /// Path of an encrypted keystore file holding the key.
+    Keystore(String),
+}
+
+impl SecretInfo {
+    /// The secret as one string: the hex key, `mnemonic:<phrase>|path:<path>`
+    /// or `keystore:<path>`.
+    pub fn formatted(&self) -> Secret<String> {
+        match self {
+            SecretInfo::PrivateKey(key) => key.clone(),
+            SecretInfo::Mnemonic {
+                phrase,
+                derivation_path,
+            } => format!("mnemonic:{}|path:{derivation_path}", phrase.expose()).into(),
+            SecretInfo::Keystore(path) => format!("keystore:{path}").into(),
+        }
+    }
+
+    /// Re-derive the key pair this secret stands for on `chain`.
+    ///
+    /// Keystore files have to be decrypted first, so they are an error here.
+    pub fn keypair(&self, chain: &dyn Chain) -> Result<KeyPair> {
+        match self {
+            SecretInfo::PrivateKey(key) => {
+                let secret = hex::decode(key.expose().trim_start_matches("0x"))
+                    .map(Zeroizing::new)
+                    .map_err(|e| VanityError::CryptoError(format!("bad private key: {e}")))?;
+                chain.keypair_from_secret(&secret)
+            }
+            SecretInfo::Mnemonic {
+                phrase,
+                derivation_path,
+            } => {
+                let mnemonic = Mnemonic::parse(phrase.expose())
+                    .map_err(|e| VanityError::InvalidMnemonic(e.to_string()))?;
+                let path: DerivationPath = derivation_path
+                    .parse()
+                    .map_err(|e: bip32::Error| VanityError::InvalidDerivationPath(e.to_string()))?;
+                chain.derive_from_mnemonic(&mnemonic, &path)
+            }
+            SecretInfo::Keystore(path) => Err(VanityError::Keystore(format!(
+                "{path} must be decrypted to derive its key"
+            ))),
+        }
+    }
 }
 
 /// Statistics for a mining run.
@@ -80,6 +279,55 @@ pub struct MiningResult {
     pub attempts: u64,
     pub duration_secs: f64,
     pub hashrate: f64,
+    /// Per-target results, in configuration order.
+    pub targets: Vec<TargetResult>,
+    /// Which condition ended the run.
+    pub stop_reason: StopReason,
+}
+
+/// Why a mining run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// Every target reached its limit.
+    LimitReached,
+    /// `max_attempts` candidates were generated.
+    AttemptsExhausted,
+    /// The time budget ran out.
+    Deadline,
+    /// Every key of a sequential range was tried.
+    RangeExhausted,
+    /// The run was cancelled through its token.
+    Cancelled,
+    /// Workers stopped because of an error.
+    Error,
+}
+
+impl StopReason {
+    /// Stable lowercase name, suitable for scripts.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StopReason::LimitReached => "limit",
+            StopReason::AttemptsExhausted => "attempts",
+            StopReason::Deadline => "deadline",
+            StopReason::RangeExhausted => "range",
+            StopReason::Cancelled => "cancelled",
+            StopReason::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Outcome of a single target within a run.
+#[derive(Debug, Clone)]
+pub struct TargetResult {
+    pub name: Option<String>,
+    pub found: u64,
+    pub limit: u64,
 }
 
 #[cfg(test)]
@@ -89,8 +337,8 @@ mod tests {
     #[test]
     fn keypair_and_secret_info() {
         let kp = KeyPair::Secp256k1 {
-            secret: [0u8; 32],
-            public: vec![1, 2, 3],
+            secret: [0u8; 32].into(),
+            public: [4; SECP256K1_PUBLIC_LEN],
             mnemonic: None,
             derivation_path: None,
         };
@@ -102,8 +350,8 @@ mod tests {
                 mnemonic,
                 derivation_path,
             } => {
-                assert_eq!(secret, [0u8; 32]);
-                assert_eq!(public, vec![1, 2, 3]);
+                assert_eq!(secret.expose(), &[0u8; 32]);
+                assert_eq!(public, [4; SECP256K1_PUBLIC_LEN]);
                 assert!(mnemonic.is_none());
                 assert!(derivation_path.is_none());
             }
@@ -119,10 +367,46 @@ mod tests {
             derivation_path,
         } = secret
         {
-            assert_eq!(phrase, "test phrase");
+            assert_eq!(phrase.expose(), "test phrase");
             assert_eq!(derivation_path, "m/44'/60'/0'/0/0");
         } else {
             panic!("expected mnemonic variant");
         }
     }
+
+    #[test]
+    fn debug_never_shows_key_material() {
+        let chain = crate::chains::EthereumChain::new();
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                      abandon abandon about";
+        let mnemonic = FoundAddress::new(
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            SecretInfo::Mnemonic {
+                phrase: phrase.into(),
+                derivation_path: "m/44'/60'/0'/0/0".into(),
+            },
+        );
+        let keypair = mnemonic.secret.keypair(&chain).unwrap();
+        let KeyPair::Secp256k1 { secret, .. } = &keypair else {
+            panic!("expected a secp256k1 key");
+        };
+        let key_hex = hex::encode(secret.expose());
+        let key_bytes = format!("{:?}", secret.expose());
+        let private = FoundAddress::new(
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            SecretInfo::PrivateKey(key_hex.as_str().into()),
+        );
+
+        for debug in [
+            format!("{keypair:?}"),
+            format!("{mnemonic:?}"),
+            format!("{private:#?}"),
+        ] {
+            assert!(debug.contains("REDACTED"), "{debug}");
+            assert!(!debug.contains(&key_hex), "{debug}");
+            assert!(!debug.contains(&key_bytes), "{debug}");
+            assert!(!debug.contains("abandon"), "{debug}");
+        }
+        assert!(format!("{:?}", private.secret.formatted()).contains("REDACTED"));
+    }
 }
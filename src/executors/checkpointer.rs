@@ -0,0 +1,88 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::core::{
+    checkpoint::{Checkpoint, CheckpointConfig, WorkerCheckpoint, CHECKPOINT_VERSION},
+    types::Result,
+};
+
+/// How often the background writer checks whether the run has finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tracks per-worker stream positions and writes checkpoints.
+///
+/// Each worker holds its own slot locked while processing a candidate, so a
+/// snapshot (which locks every slot) never sees a half-processed attempt and
+/// the positions, attempts and found counts it records always agree.
+pub(crate) struct Checkpointer<'a> {
+    config: &'a CheckpointConfig,
+    config_hash: String,
+    slots: Vec<Mutex<WorkerCheckpoint>>,
+}
+
+impl<'a> Checkpointer<'a> {
+    pub(crate) fn new(config: &'a CheckpointConfig, config_hash: String, workers: usize) -> Self {
+        let slots = match &config.resume {
+            Some(resume) => resume.workers.iter().copied().map(Mutex::new).collect(),
+            None => (0..workers)
+                .map(|_| Mutex::new(WorkerCheckpoint::default()))
+                .collect(),
+        };
+
+        Self {
+            config,
+            config_hash,
+            slots,
+        }
+    }
+
+    pub(crate) fn slot(&self, worker: usize) -> MutexGuard<'_, WorkerCheckpoint> {
+        self.slots[worker].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Capture a consistent checkpoint; `target_found` is read while every
+    /// worker is between attempts.
+    pub(crate) fn snapshot(
+        &self,
+        target_found: impl Fn() -> Vec<u64>,
+        elapsed: Duration,
+    ) -> Checkpoint {
+        let guards: Vec<_> = (0..self.slots.len()).map(|w| self.slot(w)).collect();
+        let target_found = target_found();
+
+        Checkpoint {
+            version: CHECKPOINT_VERSION,
+            config_hash: self.config_hash.clone(),
+            attempts: guards.iter().map(|w| w.attempts).sum(),
+            found: target_found.iter().sum(),
+            elapsed_secs: elapsed.as_secs_f64(),
+            target_found,
+            workers: guards.iter().map(|w| **w).collect(),
+        }
+    }
+
+    pub(crate) fn save(&self, checkpoint: &Checkpoint) -> Result<()> {
+        checkpoint.save(&self.config.path)
+    }
+
+    /// Save a checkpoint every interval until `finished` is set.
+    ///
+    /// Failed writes are retried at the next interval; the final checkpoint
+    /// written by the executor reports errors.
+    pub(crate) fn run_periodic(&self, finished: &AtomicBool, snapshot: impl Fn() -> Checkpoint) {
+        let mut last = Instant::now();
+        while !finished.load(Ordering::Acquire) {
+            thread::sleep(POLL_INTERVAL);
+            if last.elapsed() >= self.config.interval {
+                let _ = self.save(&snapshot());
+                last = Instant::now();
+            }
+        }
+    }
+}
@@ -1,31 +1,77 @@
-use std::{borrow::Cow, sync::Arc};
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use rand::RngCore;
+use chrono::Utc;
+use rand::{rngs::ThreadRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use rayon::{prelude::*, ThreadPoolBuilder};
 
 use crate::{
     core::{
         chain::Chain,
-        config::{GenerationMode, MiningConfig},
+        checkpoint::{fingerprint, CheckpointConfig},
+        config::{AddressConfig, ChainConfig, GenerationMode, MiningConfig, MiningTarget},
         executor::Executor,
-        types::{Address, FoundAddress, KeyPair, MiningResult, PublicKey, Result, SecretInfo},
+        range::KeyRange,
+        recovery::RecoveryTemplate,
+        types::{
+            Address, FoundAddress, KeyPair, MiningResult, PublicKey, Result, SecretInfo,
+            StopReason, TargetResult, VanityError, WorkerError,
+        },
     },
     stats::MiningStats,
+    utils::keccak,
+};
+
+use super::{
+    checkpointer::Checkpointer,
+    scheduling::{Pacer, Scheduling, Throttle},
 };
 
-/// Threshold for batching local counters before flushing to shared stats.
+/// Attempts a worker makes between checks of the shared state (stop
+/// conditions, retired targets, stats).
 const LOCAL_COUNTER_THRESHOLD: u64 = 1000;
 
+/// Smaller batch for mnemonic derivation, which is ~1000x slower per attempt,
+/// so cancellation and deadlines stay responsive.
+const MNEMONIC_BATCH: u64 = 16;
+
+/// Keys whose addresses are computed together, so chains can hash them in
+/// one multi-lane pass.
+const ADDRESS_BATCH: usize = keccak::MAX_LANES;
+
 /// CPU-base executor
 pub struct CpuExecutor {
     threads: usize,
     stats: Arc<MiningStats>,
+    scheduling: Scheduling,
 }
 
 impl CpuExecutor {
     pub fn new(threads: usize, stats: Arc<MiningStats>) -> Self {
         let threads = threads.max(1);
-        Self { threads, stats }
+        Self {
+            threads,
+            stats,
+            scheduling: Scheduling::default(),
+        }
+    }
+
+    /// Pin, deprioritise or throttle the worker threads.
+    pub fn with_scheduling(mut self, scheduling: Scheduling) -> Self {
+        self.scheduling = scheduling;
+        self
+    }
+
+    pub fn scheduling(&self) -> &Scheduling {
+        &self.scheduling
     }
 
     pub fn stats(&self) -> Arc<MiningStats> {
@@ -35,103 +81,656 @@ impl CpuExecutor {
     pub fn threads(&self) -> usize {
         self.threads
     }
+
+    /// Validate checkpointing for `config` and restore progress when resuming.
+    fn prepare_checkpoint<'a>(
+        &self,
+        config: &MiningConfig,
+        checkpoint: &'a CheckpointConfig,
+        state: &TargetState,
+    ) -> Result<Checkpointer<'a>> {
+        if !is_reproducible(config) {
+            return Err(VanityError::Checkpoint(
+                "checkpoints need a reproducible run (a seed, key range or recovery)".into(),
+            ));
+        }
+
+        if let Some(resume) = &checkpoint.resume {
+            resume.validate(config, self.threads)?;
+            self.stats.restore(
+                resume.attempts,
+                resume.found,
+                Duration::from_secs_f64(resume.elapsed_secs),
+            );
+            state.restore(&config.targets, &resume.target_found);
+        }
+
+        Ok(Checkpointer::new(
+            checkpoint,
+            fingerprint(config, self.threads),
+            self.threads,
+        ))
+    }
 }
 
 impl Executor for CpuExecutor {
-    fn execute(&self, config: MiningConfig) -> MiningResult {
+    fn execute(&self, config: MiningConfig) -> Result<MiningResult> {
         let pool = ThreadPoolBuilder::new()
             .num_threads(self.threads)
             .build()
-            .expect("failed to build thread pool");
+            .map_err(|e| VanityError::Executor(format!("failed to build thread pool: {e}")))?;
+        if self.scheduling.affects_threads() {
+            pool.broadcast(|ctx| self.scheduling.apply_to_current_thread(ctx.index()))
+                .into_iter()
+                .collect::<Result<()>>()?;
+        }
 
         let stats = Arc::clone(&self.stats);
+        let groups = group_targets(&config.targets);
+        let state = TargetState::new(&config.targets);
+        let errors = Mutex::new(Vec::new());
+
+        let partitions: Vec<Option<Partition>> = match &config.generation_mode {
+            GenerationMode::Range { start, end } => KeyRange::new(*start, *end)?
+                .partition(self.threads)
+                .into_iter()
+                .map(|part| part.map(Partition::Keys))
+                .collect(),
+            GenerationMode::Recover(template) => (0..self.threads)
+                .map(|index| {
+                    let id = WorkerId {
+                        index,
+                        count: self.threads,
+                    };
+                    let slice = id.slice_of(template.candidates());
+                    (!slice.is_empty()).then_some(Partition::Candidates(slice))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let checkpointer = match &config.checkpoint {
+            Some(cp) => Some(self.prepare_checkpoint(&config, cp, &state)?),
+            None => None,
+        };
+        let snapshot_checkpoint =
+            |cp: &Checkpointer| cp.snapshot(|| state.found_all(), self.stats.elapsed());
+        let finished = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            if let Some(cp) = &checkpointer {
+                let finished = &finished;
+                scope.spawn(move || cp.run_periodic(finished, || snapshot_checkpoint(cp)));
+            }
 
-        // run workers in a dedicated thread pool
-        pool.install(|| {
-            (0..self.threads)
-                .into_par_iter()
-                .for_each(|_| worker_loop(config.clone(), Arc::clone(&stats)));
+            // run workers in a dedicated thread pool
+            pool.install(|| {
+                (0..self.threads).into_par_iter().for_each(|worker| {
+                    let id = WorkerId {
+                        index: worker,
+                        count: self.threads,
+                    };
+                    let ctx = WorkerContext {
+                        id,
+                        config: &config,
+                        groups: &groups,
+                        state: &state,
+                        stats: &stats,
+                        checkpointer: checkpointer.as_ref(),
+                        partition: partitions.get(worker).cloned().flatten(),
+                        throttle: self.scheduling.throttle,
+                    };
+                    if let Err(error) = worker_loop(ctx) {
+                        // Stop the other workers too; the run is reported as failed.
+                        state.fail();
+                        errors
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push(WorkerError { worker, error });
+                    }
+                });
+            });
+
+            finished.store(true, Ordering::Release);
         });
 
+        if let Some(cp) = &checkpointer {
+            cp.save(&snapshot_checkpoint(cp))?;
+        }
+
         let snapshot = self.stats.get_snapshot();
+        let stop_reason = stop_reason(&config, &state, snapshot.attempts, self.threads);
 
-        MiningResult {
+        let result = MiningResult {
             found: snapshot.found,
             attempts: snapshot.attempts,
             duration_secs: snapshot.elapsed.as_secs_f64(),
             hashrate: snapshot.hashrate(),
+            targets: config
+                .targets
+                .iter()
+                .enumerate()
+                .map(|(i, target)| TargetResult {
+                    name: target.name.clone(),
+                    found: state.found(i),
+                    limit: target.limit,
+                })
+                .collect(),
+            stop_reason,
+        };
+
+        let mut errors = errors.into_inner().unwrap_or_else(|e| e.into_inner());
+        if errors.is_empty() {
+            Ok(result)
+        } else {
+            errors.sort_by_key(|e| e.worker);
+            Err(VanityError::WorkerFailed {
+                errors,
+                partial: Box::new(result),
+            })
         }
     }
 }
 
-fn worker_loop(config: MiningConfig, stats: Arc<MiningStats>) {
-    let mut rng = rand::rng();
+/// Whether the same configuration always generates the same keys.
+fn is_reproducible(config: &MiningConfig) -> bool {
+    config.seed.is_some() || is_sequential(config)
+}
 
-    let mut local_attempts: u64 = 0;
-    let mut local_found: u64 = 0;
+/// Whether workers walk a fixed partition instead of drawing random keys.
+fn is_sequential(config: &MiningConfig) -> bool {
+    matches!(
+        config.generation_mode,
+        GenerationMode::Range { .. } | GenerationMode::Recover(_)
+    )
+}
 
-    loop {
-        let global_attempts = stats.attempts();
-        let global_found = stats.found();
-
-        if (config.max_attempts > 0
-            && global_attempts.saturating_add(local_attempts) >= config.max_attempts)
-            || (config.limit > 0 && global_found.saturating_add(local_found) >= config.limit)
-        {
-            flush_local_counters(&stats, &mut local_attempts, &mut local_found);
-            break;
-        }
-
-        let keypair = match generate_keypair_for_mode(
-            config.chain.as_ref(),
-            &config.generation_mode,
-            &mut rng,
-        ) {
-            Ok(kp) => kp,
-            Err(_) => {
-                flush_local_counters(&stats, &mut local_attempts, &mut local_found);
-                break;
+/// Work out which stop condition ended the run once all workers have exited.
+fn stop_reason(
+    config: &MiningConfig,
+    state: &TargetState,
+    attempts: u64,
+    workers: usize,
+) -> StopReason {
+    if state.has_failed() {
+        StopReason::Error
+    } else if state.all_retired() {
+        StopReason::LimitReached
+    } else if config.cancel.is_cancelled() {
+        StopReason::Cancelled
+    } else if config.deadline.is_some_and(|d| Instant::now() >= d) {
+        StopReason::Deadline
+    } else if state.exhausted_workers() == workers {
+        StopReason::RangeExhausted
+    } else {
+        debug_assert!(config.max_attempts > 0 && attempts >= config.max_attempts);
+        StopReason::AttemptsExhausted
+    }
+}
+
+/// Targets sharing a chain and address format, so each candidate address is
+/// computed and formatted once per group rather than once per target.
+struct TargetGroup {
+    chain: Arc<dyn Chain>,
+    address_config: AddressConfig,
+    targets: Vec<usize>,
+}
+
+fn group_targets(targets: &[MiningTarget]) -> Vec<TargetGroup> {
+    let mut groups: Vec<TargetGroup> = Vec::new();
+    for (index, target) in targets.iter().enumerate() {
+        match groups.iter_mut().find(|g| {
+            g.chain.id() == target.chain.id() && g.address_config == target.address_config
+        }) {
+            Some(group) => group.targets.push(index),
+            None => groups.push(TargetGroup {
+                chain: Arc::clone(&target.chain),
+                address_config: target.address_config.clone(),
+                targets: vec![index],
+            }),
+        }
+    }
+    groups
+}
+
+/// Per-target match counters shared by all workers.
+struct TargetState {
+    found: Vec<AtomicU64>,
+    /// Targets that have not reached their limit yet.
+    remaining: AtomicUsize,
+    /// Set when any worker fails, so the others stop too.
+    failed: AtomicBool,
+    /// Workers that ran out of keys in their part of a range.
+    exhausted: AtomicUsize,
+    /// Attempts handed out to workers when `max_attempts` is shared.
+    reserved: AtomicU64,
+}
+
+impl TargetState {
+    fn new(targets: &[MiningTarget]) -> Self {
+        Self {
+            found: targets.iter().map(|_| AtomicU64::new(0)).collect(),
+            remaining: AtomicUsize::new(targets.len()),
+            failed: AtomicBool::new(false),
+            exhausted: AtomicUsize::new(0),
+            reserved: AtomicU64::new(0),
+        }
+    }
+
+    /// Continue from per-target counts saved by an earlier session.
+    fn restore(&self, targets: &[MiningTarget], found: &[u64]) {
+        for (counter, &n) in self.found.iter().zip(found) {
+            counter.store(n, Ordering::Release);
+        }
+        let remaining = targets
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| !self.is_retired(*i, t.limit))
+            .count();
+        self.remaining.store(remaining, Ordering::Release);
+    }
+
+    fn found(&self, target: usize) -> u64 {
+        self.found[target].load(Ordering::Acquire)
+    }
+
+    fn found_all(&self) -> Vec<u64> {
+        (0..self.found.len()).map(|t| self.found(t)).collect()
+    }
+
+    fn is_retired(&self, target: usize, limit: u64) -> bool {
+        limit > 0 && self.found(target) >= limit
+    }
+
+    fn all_retired(&self) -> bool {
+        self.remaining.load(Ordering::Acquire) == 0
+    }
+
+    fn fail(&self) {
+        self.failed.store(true, Ordering::Release);
+    }
+
+    fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Acquire)
+    }
+
+    fn worker_exhausted(&self) {
+        self.exhausted.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn exhausted_workers(&self) -> usize {
+        self.exhausted.load(Ordering::Acquire)
+    }
+
+    /// Hand out up to `want` of the `max` attempts shared by all workers.
+    fn reserve_attempts(&self, max: u64, want: u64) -> u64 {
+        let previous = self
+            .reserved
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then(|| n + want.min(max - n))
+            });
+        match previous {
+            Ok(n) => want.min(max - n),
+            Err(_) => 0,
+        }
+    }
+
+    /// Reserve one result slot for `target`; fails once its limit is reached,
+    /// so quotas are exact even with many workers.
+    fn claim(&self, target: usize, limit: u64) -> bool {
+        let claimed = self.found[target].fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+            (limit == 0 || n < limit).then_some(n + 1)
+        });
+
+        match claimed {
+            Ok(previous) => {
+                if limit > 0 && previous + 1 == limit {
+                    self.remaining.fetch_sub(1, Ordering::AcqRel);
+                }
+                true
             }
-        };
+            Err(_) => false,
+        }
+    }
+}
 
-        let public = match public_key_from_keypair(&keypair) {
-            Some(pk) => pk,
-            None => {
-                flush_local_counters(&stats, &mut local_attempts, &mut local_found);
-                break;
+/// Position of a worker within the pool.
+#[derive(Debug, Clone, Copy)]
+struct WorkerId {
+    index: usize,
+    count: usize,
+}
+
+impl WorkerId {
+    /// This worker's even share of `total` attempts.
+    fn share_of(self, total: u64) -> u64 {
+        let count = self.count as u64;
+        let index = self.index as u64;
+        total / count + u64::from(index < total % count)
+    }
+
+    /// This worker's contiguous slice of `0..total`.
+    fn slice_of(self, total: u64) -> Range<u64> {
+        let count = self.count as u64;
+        let index = self.index as u64;
+        let start = index * (total / count) + index.min(total % count);
+        start..start + self.share_of(total)
+    }
+}
+
+/// The keys a worker walks in order in range and recovery modes.
+#[derive(Debug, Clone)]
+enum Partition {
+    /// Private keys of a `--range`.
+    Keys(KeyRange),
+    /// Candidate indices of a recovery template.
+    Candidates(Range<u64>),
+}
+
+/// Key material source for one worker.
+///
+/// Seeded runs give each worker its own ChaCha20 stream (the worker index),
+/// so streams never overlap and replaying a seed with the same thread count
+/// regenerates the same keys.
+enum WorkerRng {
+    Seeded(Box<ChaCha20Rng>),
+    Os(ThreadRng),
+}
+
+impl WorkerRng {
+    fn new(id: WorkerId, seed: Option<[u8; 32]>) -> Self {
+        match seed {
+            Some(seed) => {
+                let mut rng = ChaCha20Rng::from_seed(seed);
+                rng.set_stream(id.index as u64);
+                WorkerRng::Seeded(Box::new(rng))
             }
-        };
-        let address: Address = config.chain.compute_address(&public);
+            None => WorkerRng::Os(rand::rng()),
+        }
+    }
+
+    /// Offset into a seeded stream; always 0 for the OS RNG.
+    fn position(&self) -> u128 {
+        match self {
+            WorkerRng::Seeded(rng) => rng.get_word_pos(),
+            WorkerRng::Os(_) => 0,
+        }
+    }
+
+    fn seek(&mut self, position: u128) {
+        if let WorkerRng::Seeded(rng) = self {
+            rng.set_word_pos(position);
+        }
+    }
+}
+
+impl RngCore for WorkerRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            WorkerRng::Seeded(rng) => rng.next_u32(),
+            WorkerRng::Os(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            WorkerRng::Seeded(rng) => rng.next_u64(),
+            WorkerRng::Os(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        match self {
+            WorkerRng::Seeded(rng) => rng.fill_bytes(dst),
+            WorkerRng::Os(rng) => rng.fill_bytes(dst),
+        }
+    }
+}
+
+/// Everything one worker needs, borrowed from `execute`.
+struct WorkerContext<'a> {
+    id: WorkerId,
+    config: &'a MiningConfig,
+    groups: &'a [TargetGroup],
+    state: &'a TargetState,
+    stats: &'a MiningStats,
+    checkpointer: Option<&'a Checkpointer<'a>>,
+    /// This worker's share of the keys in range and recovery modes.
+    partition: Option<Partition>,
+    throttle: Option<Throttle>,
+}
 
-        // Format the address as the user will see it (hex for Ethereum, base32 for Conflux).
-        let formatted = config
-            .chain
-            .format_address(&address, &config.address_config);
+fn worker_loop(ctx: WorkerContext<'_>) -> Result<()> {
+    let WorkerContext {
+        id,
+        config,
+        groups,
+        state,
+        stats,
+        checkpointer,
+        partition,
+        throttle,
+    } = ctx;
+
+    let Some(key_chain) = config.targets.first().map(|t| t.chain.as_ref()) else {
+        return Ok(());
+    };
 
-        let normalized = normalize_for_matching(&formatted, &config.address_config);
+    let sequential = is_sequential(config);
+    if sequential && partition.is_none() {
+        // More workers than keys: nothing to do.
+        state.worker_exhausted();
+        return Ok(());
+    }
 
-        local_attempts += 1;
+    let mut rng = WorkerRng::new(id, config.seed);
+    let mut local = LocalCounters::new(stats);
+
+    // Reproducible runs split `max_attempts` up front so every worker walks a
+    // fixed prefix of its stream regardless of scheduling.
+    let quota = (is_reproducible(config) && config.max_attempts > 0)
+        .then(|| id.share_of(config.max_attempts));
+    let mut done: u64 = 0;
+    // Next candidate index in recovery mode, which skips invalid candidates.
+    let mut cursor: u64 = 0;
+
+    // Shared state is only consulted between batches; within a batch the
+    // worker runs on its own budget and its own view of retired targets.
+    let batch = batch_size(&config.generation_mode);
+    let mut budget: u64 = 0;
+    let mut active: Vec<bool> = vec![true; config.targets.len()];
+    let mut formatted = String::with_capacity(64);
+    let mut keypairs: Vec<KeyPair> = Vec::with_capacity(ADDRESS_BATCH);
+    let mut addresses: Vec<Address> = Vec::with_capacity(ADDRESS_BATCH);
+
+    if let Some(cp) = checkpointer {
+        let slot = cp.slot(id.index);
+        rng.seek(slot.position);
+        done = slot.attempts;
+        cursor = slot.position as u64;
+    }
 
-        if config.matcher.matches(normalized.as_ref()) {
-            let found = FoundAddress {
-                address: formatted,
-                secret: build_secret_info(&keypair),
+    let mut pacer = throttle.map(|t| Pacer::new(t, id.count, Instant::now()));
+    let mut paced = done;
+    let should_stop = || {
+        state.all_retired()
+            || state.has_failed()
+            || config.cancel.is_cancelled()
+            || config.deadline.is_some_and(|d| Instant::now() >= d)
+    };
+
+    loop {
+        if budget == 0 {
+            local.flush();
+            if let Some(pacer) = pacer.as_mut() {
+                pacer.pace(done - paced, should_stop);
+                paced = done;
+            }
+            if should_stop() {
+                return Ok(());
+            }
+
+            for (t, target) in config.targets.iter().enumerate() {
+                active[t] = !state.is_retired(t, target.limit);
+            }
+            budget = match quota {
+                Some(quota) => quota.saturating_sub(done).min(batch),
+                None if config.max_attempts > 0 => {
+                    state.reserve_attempts(config.max_attempts, batch)
+                }
+                None => batch,
             };
+            if budget == 0 {
+                return Ok(());
+            }
+        }
 
-            if config.result_sender.send(found).is_err() {
-                flush_local_counters(&stats, &mut local_attempts, &mut local_found);
+        // Held for the whole batch so checkpoints see whole attempts only.
+        let mut slot = checkpointer.map(|cp| cp.slot(id.index));
+
+        // Generate a few keys so their addresses can be hashed in one pass.
+        keypairs.clear();
+        let mut exhausted = false;
+        while keypairs.len() < ADDRESS_BATCH && budget > 0 {
+            let next = match &partition {
+                Some(Partition::Keys(range)) => match range.key_at(done) {
+                    Some(secret) => Some(key_chain.keypair_from_secret(secret.expose())?),
+                    None => None,
+                },
+                Some(Partition::Candidates(indices)) => {
+                    let GenerationMode::Recover(template) = &config.generation_mode else {
+                        unreachable!("candidate partitions only exist in recovery mode");
+                    };
+                    next_candidate(key_chain, template, indices, &mut cursor)?
+                }
+                None => Some(generate_keypair_for_mode(
+                    key_chain,
+                    &config.generation_mode,
+                    &mut rng,
+                )?),
+            };
+            let Some(keypair) = next else {
+                exhausted = true;
                 break;
+            };
+            keypairs.push(keypair);
+            budget -= 1;
+            local.attempts += 1;
+            done += 1;
+        }
+
+        if let Some(slot) = slot.as_deref_mut() {
+            slot.attempts = done;
+            // Range workers are positioned by their attempt count alone.
+            slot.position = match &partition {
+                Some(Partition::Keys(_)) => 0,
+                Some(Partition::Candidates(_)) => u128::from(cursor),
+                None => rng.position(),
+            };
+        }
+
+        let mut publics = [PublicKey::Secp256k1(&[]); ADDRESS_BATCH];
+        for (public, keypair) in publics.iter_mut().zip(&keypairs) {
+            *public = keypair.public_key();
+        }
+        let publics = &publics[..keypairs.len()];
+
+        for group in groups {
+            if !group.targets.iter().any(|&t| active[t]) {
+                continue;
             }
 
-            local_found += 1;
+            group.chain.compute_addresses(publics, &mut addresses)?;
+
+            for (i, (keypair, address)) in keypairs.iter().zip(&addresses).enumerate() {
+                // Format the address as the user will see it (hex for Ethereum, base32 for Conflux).
+                formatted.clear();
+                group
+                    .chain
+                    .format_address_into(address, &group.address_config, &mut formatted)?;
+
+                let normalized = group.address_config.matching_body(&formatted);
+
+                for &t in &group.targets {
+                    let target = &config.targets[t];
+                    if !active[t] || !target.matcher.matches(normalized) {
+                        continue;
+                    }
+                    if !state.claim(t, target.limit) {
+                        // Another worker filled the quota first.
+                        active[t] = false;
+                        continue;
+                    }
+
+                    let found = FoundAddress {
+                        address: formatted.clone(),
+                        secret: build_secret_info(keypair),
+                        target: target.name.clone(),
+                        chain: group.chain.id().to_string(),
+                        network: match group.address_config.chain_config {
+                            ChainConfig::Conflux { network_id } => Some(network_id),
+                            ChainConfig::Ethereum { .. } => None,
+                        },
+                        public_key: keypair.public_key().to_hex(),
+                        pattern: target.matcher.description(),
+                        // `done` already counts the whole batch.
+                        attempt: done - (keypairs.len() - i) as u64 + 1,
+                        worker: id.index,
+                        found_at: Utc::now(),
+                    };
+
+                    config
+                        .result_sender
+                        .send(found)
+                        .map_err(|_| VanityError::ResultChannelClosed)?;
+
+                    local.found += 1;
+                    if state.is_retired(t, target.limit) {
+                        active[t] = false;
+                    }
+                }
+            }
         }
 
-        if local_attempts >= LOCAL_COUNTER_THRESHOLD || local_found >= LOCAL_COUNTER_THRESHOLD {
-            flush_local_counters(&stats, &mut local_attempts, &mut local_found);
+        if exhausted {
+            state.worker_exhausted();
+            return Ok(());
+        }
+        if !active.contains(&true) {
+            // Every target is done; stop without waiting for the batch to end.
+            budget = 0;
         }
     }
 }
 
+/// Attempts between checks of the shared state for `mode`.
+fn batch_size(mode: &GenerationMode) -> u64 {
+    match mode {
+        GenerationMode::PrivateKey | GenerationMode::Range { .. } => LOCAL_COUNTER_THRESHOLD,
+        GenerationMode::Mnemonic { .. } | GenerationMode::Recover(_) => MNEMONIC_BATCH,
+    }
+}
+
+/// Derive the next valid candidate of `indices`, advancing `cursor` past it.
+fn next_candidate(
+    chain: &dyn Chain,
+    template: &RecoveryTemplate,
+    indices: &Range<u64>,
+    cursor: &mut u64,
+) -> Result<Option<KeyPair>> {
+    while let Some(index) = indices
+        .start
+        .checked_add(*cursor)
+        .filter(|i| *i < indices.end)
+    {
+        *cursor += 1;
+        if let Some(keypair) = template.keypair_at(chain, index)? {
+            return Ok(Some(keypair));
+        }
+    }
+    Ok(None)
+}
+
 fn generate_keypair_for_mode(
     chain: &dyn Chain,
     mode: &GenerationMode,
@@ -143,18 +742,24 @@ fn generate_keypair_for_mode(
             word_count,
             derivation_path,
         } => {
-            let mnemonic = bip39::Mnemonic::generate(*word_count)
-                .map_err(|e| crate::core::types::VanityError::InvalidMnemonic(e.to_string()))?;
+            // Draw the entropy from `rng` so seeded runs are reproducible.
+            if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+                return Err(VanityError::InvalidMnemonic(format!(
+                    "unsupported word count {word_count}"
+                )));
+            }
+            let mut entropy = [0u8; 32];
+            let entropy = &mut entropy[..word_count / 3 * 4];
+            rng.fill_bytes(entropy);
+
+            let mnemonic = bip39::Mnemonic::from_entropy(entropy)
+                .map_err(|e| VanityError::InvalidMnemonic(e.to_string()))?;
 
             chain.derive_from_mnemonic(&mnemonic, derivation_path)
         }
-    }
-}
-
-fn public_key_from_keypair(keypair: &KeyPair) -> Option<PublicKey> {
-    match keypair {
-        KeyPair::Secp256k1 { public, .. } => Some(PublicKey::Secp256k1(public.clone())),
-        KeyPair::Ed25519 { public, .. } => Some(PublicKey::Ed25519(*public)),
+        GenerationMode::Range { .. } | GenerationMode::Recover(_) => Err(VanityError::Executor(
+            "range and recovery keys are assigned per worker, not generated".into(),
+        )),
     }
 }
 
@@ -174,68 +779,48 @@ fn build_secret_info(keypair: &KeyPair) -> SecretInfo {
         } => {
             let _ = secret;
             SecretInfo::Mnemonic {
-                phrase: m.to_string(),
+                phrase: m.expose().to_string().into(),
                 derivation_path: path.to_string(),
             }
         }
         KeyPair::Secp256k1 { secret, .. } | KeyPair::Ed25519 { secret, .. } => {
-            SecretInfo::PrivateKey(hex::encode(secret))
+            SecretInfo::PrivateKey(hex::encode(secret.expose()).into())
         }
     }
 }
 
-fn normalize_for_matching<'a>(
-    addr: &'a str,
-    cfg: &crate::core::config::AddressConfig,
-) -> Cow<'a, str> {
-    let mut s = addr.to_owned();
-    s.make_ascii_lowercase();
-
-    match &cfg.chain_config {
-        // Ethereum: strip "0x" prefix, match on raw hex string.
-        crate::core::config::ChainConfig::Ethereum { .. } => normalize_for_matching_eth(addr),
-        // Conflux: strip "prefix:" part (e.g. "cfx:" / "cfxtest:"), match on body only.
-        crate::core::config::ChainConfig::Conflux { .. } => normalize_for_matching_cfx(addr),
-    }
+/// Per-worker counters, batched into the shared stats and flushed on drop so
+/// every exit path (including errors) is accounted for.
+struct LocalCounters<'a> {
+    stats: &'a MiningStats,
+    attempts: u64,
+    found: u64,
 }
 
-fn normalize_for_matching_eth<'a>(addr: &'a str) -> Cow<'a, str> {
-    let body = addr
-        .strip_prefix("0x")
-        .or_else(|| addr.strip_prefix("0X"))
-        .unwrap_or(addr);
-
-    if body
-        .chars()
-        .all(|c| !c.is_ascii_alphabetic() || c.is_ascii_lowercase())
-    {
-        Cow::Borrowed(body)
-    } else {
-        Cow::Owned(body.to_ascii_lowercase())
+impl<'a> LocalCounters<'a> {
+    fn new(stats: &'a MiningStats) -> Self {
+        Self {
+            stats,
+            attempts: 0,
+            found: 0,
+        }
     }
-}
-
-fn normalize_for_matching_cfx<'a>(addr: &'a str) -> Cow<'a, str> {
-    let body = addr.split_once(":").map(|(_, b)| b).unwrap_or(addr);
 
-    if body
-        .chars()
-        .all(|c| !c.is_ascii_alphabetic() || c.is_ascii_lowercase())
-    {
-        Cow::Borrowed(body)
-    } else {
-        Cow::Owned(body.to_ascii_lowercase())
+    fn flush(&mut self) {
+        if self.attempts > 0 {
+            self.stats.add_attempts(self.attempts);
+            self.attempts = 0;
+        }
+        if self.found > 0 {
+            self.stats.add_found(self.found);
+            self.found = 0;
+        }
     }
 }
 
-fn flush_local_counters(stats: &MiningStats, attempts: &mut u64, found: &mut u64) {
-    if *attempts > 0 {
-        stats.add_attempts(*attempts);
-        *attempts = 0;
-    }
-    if *found > 0 {
-        stats.add_found(*found);
-        *found = 0;
+impl Drop for LocalCounters<'_> {
+    fn drop(&mut self) {
+        self.flush();
     }
 }
 
@@ -244,11 +829,17 @@ mod tests {
     use super::*;
     use std::sync::{mpsc, Arc};
 
+    use crate::chains::EthereumChain;
     use crate::core::{
+        cancel::CancellationToken,
         chain::Chain,
-        config::{AddressConfig, ChainConfig, Encoding, GenerationMode, MiningConfig},
+        checkpoint::Checkpoint,
+        config::{
+            AddressConfig, ChainConfig, Encoding, GenerationMode, MiningConfig, MiningTarget,
+        },
         matcher::Matcher,
-        types::{Address, KeyPair, PublicKey, Result},
+        secret::Secret,
+        types::{Address, KeyPair, PublicKey, Result, SECP256K1_PUBLIC_LEN},
     };
 
     /// A simple chain implementation for testing the executor.
@@ -266,8 +857,8 @@ mod tests {
 
         fn generate_keypair(&self, _rng: &mut dyn RngCore) -> Result<KeyPair> {
             Ok(KeyPair::Secp256k1 {
-                secret: [0u8; 32],
-                public: Vec::new(),
+                secret: [0u8; 32].into(),
+                public: [0; SECP256K1_PUBLIC_LEN],
                 mnemonic: None,
                 derivation_path: None,
             })
@@ -284,26 +875,29 @@ mod tests {
 
         fn keypair_from_secret(&self, _secret: &[u8]) -> Result<KeyPair> {
             Ok(KeyPair::Secp256k1 {
-                secret: [0u8; 32],
-                public: Vec::new(),
+                secret: [0u8; 32].into(),
+                public: [0; SECP256K1_PUBLIC_LEN],
                 mnemonic: None,
                 derivation_path: None,
             })
         }
 
-        fn compute_address(&self, _public_key: &PublicKey) -> Address {
-            Address {
-                raw: vec![0u8],
-                chain_id: "dummy".to_string(),
-            }
+        fn compute_address(&self, _public_key: PublicKey<'_>) -> Result<Address> {
+            Ok(Address::new("dummy", &[0]))
         }
 
-        fn format_address(&self, _address: &Address, _config: &AddressConfig) -> String {
-            "addr".to_string()
+        fn format_address_into(
+            &self,
+            _address: &Address,
+            _config: &AddressConfig,
+            out: &mut String,
+        ) -> Result<()> {
+            out.push_str("addr");
+            Ok(())
         }
 
-        fn format_secret(&self, _keypair: &KeyPair) -> String {
-            "secret".to_string()
+        fn format_secret(&self, _keypair: &KeyPair) -> Secret<String> {
+            "secret".into()
         }
 
         fn validate_address(&self, _address: &str) -> bool {
@@ -311,6 +905,56 @@ mod tests {
         }
     }
 
+    /// Chain whose address derivation always fails.
+    struct BrokenChain;
+
+    impl Chain for BrokenChain {
+        fn id(&self) -> &str {
+            "broken"
+        }
+
+        fn name(&self) -> &str {
+            "Broken"
+        }
+
+        fn generate_keypair(&self, rng: &mut dyn RngCore) -> Result<KeyPair> {
+            DummyChain.generate_keypair(rng)
+        }
+
+        fn derive_from_mnemonic(
+            &self,
+            mnemonic: &bip39::Mnemonic,
+            path: &bip32::DerivationPath,
+        ) -> Result<KeyPair> {
+            DummyChain.derive_from_mnemonic(mnemonic, path)
+        }
+
+        fn keypair_from_secret(&self, secret: &[u8]) -> Result<KeyPair> {
+            DummyChain.keypair_from_secret(secret)
+        }
+
+        fn compute_address(&self, _public_key: PublicKey<'_>) -> Result<Address> {
+            Err(VanityError::CryptoError("bad key".into()))
+        }
+
+        fn format_address_into(
+            &self,
+            address: &Address,
+            config: &AddressConfig,
+            out: &mut String,
+        ) -> Result<()> {
+            DummyChain.format_address_into(address, config, out)
+        }
+
+        fn format_secret(&self, keypair: &KeyPair) -> Secret<String> {
+            DummyChain.format_secret(keypair)
+        }
+
+        fn validate_address(&self, _address: &str) -> bool {
+            false
+        }
+    }
+
     /// Matcher that always returns the configured boolean.
     #[derive(Debug)]
     struct AlwaysMatch(bool);
@@ -341,19 +985,27 @@ mod tests {
         };
 
         let config = MiningConfig {
-            chain,
-            matcher,
+            targets: vec![MiningTarget {
+                name: None,
+                chain,
+                matcher,
+                address_config,
+                limit: 0,
+            }],
             generation_mode: GenerationMode::PrivateKey,
-            address_config,
+            seed: None,
+            checkpoint: None,
             max_attempts: 100,
-            limit: 0,
+            deadline: None,
             result_sender: tx,
+            cancel: CancellationToken::new(),
         };
 
-        let result = executor.execute(config);
+        let result = executor.execute(config).unwrap();
 
         assert_eq!(result.found, 0);
         assert_eq!(result.attempts, 100);
+        assert_eq!(result.stop_reason, StopReason::AttemptsExhausted);
 
         // No results should have been sent.
         assert!(rx.try_recv().is_err());
@@ -375,19 +1027,27 @@ mod tests {
         };
 
         let config = MiningConfig {
-            chain,
-            matcher,
+            targets: vec![MiningTarget {
+                name: None,
+                chain,
+                matcher,
+                address_config,
+                limit: 5,
+            }],
             generation_mode: GenerationMode::PrivateKey,
-            address_config,
+            seed: None,
+            checkpoint: None,
             max_attempts: 10_000,
-            limit: 5,
+            deadline: None,
             result_sender: tx,
+            cancel: CancellationToken::new(),
         };
 
-        let result = executor.execute(config);
+        let result = executor.execute(config).unwrap();
 
         assert_eq!(result.found, 5);
         assert!(result.attempts >= 5);
+        assert_eq!(result.stop_reason, StopReason::LimitReached);
 
         // Exactly 5 results should have been sent.
         let mut received = 0;
@@ -396,4 +1056,447 @@ mod tests {
         }
         assert_eq!(received, 5);
     }
+
+    #[test]
+    fn cpu_executor_retires_targets_independently() {
+        let stats = Arc::new(MiningStats::new());
+        let executor = CpuExecutor::new(2, stats.clone());
+
+        let (tx, rx) = mpsc::channel();
+
+        let chain = Arc::new(DummyChain) as Arc<dyn Chain>;
+        let address_config = AddressConfig {
+            encoding: Encoding::Hex,
+            chain_config: ChainConfig::Ethereum { checksum: false },
+        };
+        let target = |name: &str, matches: bool, limit: u64| MiningTarget {
+            name: Some(name.into()),
+            chain: Arc::clone(&chain),
+            matcher: Arc::new(AlwaysMatch(matches)) as Arc<dyn Matcher>,
+            address_config: address_config.clone(),
+            limit,
+        };
+
+        let config = MiningConfig {
+            targets: vec![target("two", true, 2), target("three", true, 3)],
+            generation_mode: GenerationMode::PrivateKey,
+            seed: None,
+            checkpoint: None,
+            max_attempts: 10_000,
+            deadline: None,
+            result_sender: tx,
+            cancel: CancellationToken::new(),
+        };
+
+        let result = executor.execute(config).unwrap();
+
+        assert_eq!(result.found, 5);
+        assert!(result.attempts < 10_000);
+        assert_eq!(result.targets[0].found, 2);
+        assert_eq!(result.targets[1].found, 3);
+
+        let tags: Vec<String> = rx.try_iter().filter_map(|f| f.target).collect();
+        assert_eq!(tags.iter().filter(|t| *t == "two").count(), 2);
+        assert_eq!(tags.iter().filter(|t| *t == "three").count(), 3);
+    }
+
+    #[test]
+    fn cpu_executor_runs_until_max_attempts_with_unfinished_target() {
+        let stats = Arc::new(MiningStats::new());
+        let executor = CpuExecutor::new(1, stats.clone());
+
+        let (tx, _rx) = mpsc::channel();
+
+        let chain = Arc::new(DummyChain) as Arc<dyn Chain>;
+        let address_config = AddressConfig {
+            encoding: Encoding::Hex,
+            chain_config: ChainConfig::Ethereum { checksum: false },
+        };
+
+        let config = MiningConfig {
+            targets: vec![
+                MiningTarget {
+                    name: Some("done".into()),
+                    chain: Arc::clone(&chain),
+                    matcher: Arc::new(AlwaysMatch(true)),
+                    address_config: address_config.clone(),
+                    limit: 1,
+                },
+                MiningTarget {
+                    name: Some("never".into()),
+                    chain,
+                    matcher: Arc::new(AlwaysMatch(false)),
+                    address_config,
+                    limit: 1,
+                },
+            ],
+            generation_mode: GenerationMode::PrivateKey,
+            seed: None,
+            checkpoint: None,
+            max_attempts: 50,
+            deadline: None,
+            result_sender: tx,
+            cancel: CancellationToken::new(),
+        };
+
+        let result = executor.execute(config).unwrap();
+
+        assert_eq!(result.attempts, 50);
+        assert_eq!(result.targets[0].found, 1);
+        assert_eq!(result.targets[1].found, 0);
+    }
+
+    #[test]
+    fn cpu_executor_stops_when_cancelled_from_another_thread() {
+        let stats = Arc::new(MiningStats::new());
+        let executor = CpuExecutor::new(2, stats.clone());
+
+        let (tx, _rx) = mpsc::channel();
+        let cancel = CancellationToken::new();
+
+        let config = MiningConfig {
+            targets: vec![MiningTarget {
+                name: None,
+                chain: Arc::new(DummyChain),
+                matcher: Arc::new(AlwaysMatch(false)),
+                address_config: AddressConfig {
+                    encoding: Encoding::Hex,
+                    chain_config: ChainConfig::Ethereum { checksum: false },
+                },
+                limit: 0,
+            }],
+            generation_mode: GenerationMode::PrivateKey,
+            // Unlimited: only cancellation can end this run.
+            seed: None,
+            checkpoint: None,
+            max_attempts: 0,
+            deadline: None,
+            result_sender: tx,
+            cancel: cancel.clone(),
+        };
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            cancel.cancel();
+        });
+
+        let result = executor.execute(config).unwrap();
+        canceller.join().unwrap();
+
+        assert!(result.attempts > 0);
+        assert_eq!(result.attempts, stats.attempts());
+        assert_eq!(result.stop_reason, StopReason::Cancelled);
+    }
+
+    #[test]
+    fn cpu_executor_stops_at_deadline() {
+        let stats = Arc::new(MiningStats::new());
+        let executor = CpuExecutor::new(2, stats.clone());
+
+        let (tx, _rx) = mpsc::channel();
+
+        let config = MiningConfig {
+            targets: vec![MiningTarget {
+                name: None,
+                chain: Arc::new(DummyChain),
+                matcher: Arc::new(AlwaysMatch(false)),
+                address_config: AddressConfig {
+                    encoding: Encoding::Hex,
+                    chain_config: ChainConfig::Ethereum { checksum: false },
+                },
+                limit: 0,
+            }],
+            generation_mode: GenerationMode::PrivateKey,
+            seed: None,
+            checkpoint: None,
+            max_attempts: 0,
+            deadline: Some(Instant::now() + std::time::Duration::from_millis(50)),
+            result_sender: tx,
+            cancel: CancellationToken::new(),
+        };
+
+        let result = executor.execute(config).unwrap();
+
+        assert!(result.attempts > 0);
+        assert_eq!(result.stop_reason, StopReason::Deadline);
+    }
+
+    fn single_target_config(
+        chain: Arc<dyn Chain>,
+        matches: bool,
+        tx: mpsc::Sender<FoundAddress>,
+    ) -> MiningConfig {
+        MiningConfig {
+            targets: vec![MiningTarget {
+                name: None,
+                chain,
+                matcher: Arc::new(AlwaysMatch(matches)),
+                address_config: AddressConfig {
+                    encoding: Encoding::Hex,
+                    chain_config: ChainConfig::Ethereum { checksum: false },
+                },
+                limit: 0,
+            }],
+            generation_mode: GenerationMode::PrivateKey,
+            seed: None,
+            checkpoint: None,
+            max_attempts: 1_000,
+            deadline: None,
+            result_sender: tx,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    #[test]
+    fn cpu_executor_reports_worker_errors() {
+        let stats = Arc::new(MiningStats::new());
+        let executor = CpuExecutor::new(2, stats.clone());
+        let (tx, _rx) = mpsc::channel();
+
+        let err = executor
+            .execute(single_target_config(Arc::new(BrokenChain), true, tx))
+            .unwrap_err();
+
+        match err {
+            VanityError::WorkerFailed { errors, partial } => {
+                assert!(!errors.is_empty());
+                assert!(errors[0].to_string().contains("bad key"));
+                assert_eq!(partial.stop_reason, StopReason::Error);
+                assert_eq!(partial.found, 0);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn cpu_executor_fails_when_receiver_is_dropped() {
+        let stats = Arc::new(MiningStats::new());
+        let executor = CpuExecutor::new(1, stats.clone());
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+
+        let err = executor
+            .execute(single_target_config(Arc::new(DummyChain), true, tx))
+            .unwrap_err();
+
+        assert!(err.to_string().contains("result receiver disconnected"));
+        assert!(stats.attempts() >= 1);
+    }
+
+    fn seeded_run(seed: [u8; 32], threads: usize, mode: GenerationMode) -> Vec<String> {
+        let executor = CpuExecutor::new(threads, Arc::new(MiningStats::new()));
+        let (tx, rx) = mpsc::channel();
+
+        let mut config = single_target_config(Arc::new(EthereumChain::new()), true, tx);
+        config.generation_mode = mode;
+        config.seed = Some(seed);
+        config.max_attempts = 7;
+
+        let result = executor.execute(config).unwrap();
+        assert_eq!(result.attempts, 7);
+
+        let mut found: Vec<String> = rx.try_iter().map(|f| f.address).collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let first = seeded_run([7; 32], 3, GenerationMode::PrivateKey);
+        assert_eq!(first.len(), 7);
+        assert_eq!(first, seeded_run([7; 32], 3, GenerationMode::PrivateKey));
+        assert_ne!(first, seeded_run([8; 32], 3, GenerationMode::PrivateKey));
+
+        let mnemonic = || GenerationMode::Mnemonic {
+            word_count: 12,
+            derivation_path: "m/44'/60'/0'/0/0".parse().unwrap(),
+        };
+        assert_eq!(
+            seeded_run([7; 32], 2, mnemonic()),
+            seeded_run([7; 32], 2, mnemonic())
+        );
+    }
+
+    #[test]
+    fn mnemonic_mode_rejects_unsupported_word_counts() {
+        let mode = |word_count| GenerationMode::Mnemonic {
+            word_count,
+            derivation_path: "m/44'/60'/0'/0/0".parse().unwrap(),
+        };
+        let chain = EthereumChain::new();
+        let mut rng = rand::rng();
+        for word_count in [12, 24] {
+            assert!(generate_keypair_for_mode(&chain, &mode(word_count), &mut rng).is_ok());
+        }
+        for word_count in [0, 11, 13, 27] {
+            assert!(generate_keypair_for_mode(&chain, &mode(word_count), &mut rng).is_err());
+        }
+    }
+
+    #[test]
+    fn seeded_worker_streams_do_not_overlap() {
+        let id = |index| WorkerId { index, count: 2 };
+        let mut a = WorkerRng::new(id(0), Some([1; 32]));
+        let mut b = WorkerRng::new(id(1), Some([1; 32]));
+        assert_ne!(a.next_u64(), b.next_u64());
+
+        assert_eq!(id(0).share_of(7), 4);
+        assert_eq!(id(1).share_of(7), 3);
+    }
+
+    #[test]
+    fn range_run_scans_every_key_once() {
+        let executor = CpuExecutor::new(3, Arc::new(MiningStats::new()));
+        let (tx, rx) = mpsc::channel();
+
+        let mut config = single_target_config(Arc::new(EthereumChain::new()), true, tx);
+        config.generation_mode = GenerationMode::Range {
+            start: 1.into(),
+            end: 10.into(),
+        };
+
+        let result = executor.execute(config).unwrap();
+        assert_eq!(result.attempts, 10);
+        assert_eq!(result.found, 10);
+        assert_eq!(result.stop_reason, StopReason::RangeExhausted);
+
+        let found: Vec<FoundAddress> = rx.try_iter().collect();
+        let one = found
+            .iter()
+            .find(|f| f.address == "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf")
+            .expect("address of private key 1");
+        assert!(matches!(&one.secret, SecretInfo::PrivateKey(k) if k.expose().ends_with("01")));
+        // Key 1 is the first key of the first worker's part.
+        assert_eq!((one.worker, one.attempt), (0, 1));
+        assert_eq!(one.chain, "ethereum");
+        assert_eq!(one.network, None);
+        assert!(one.public_key.starts_with("04") && one.public_key.len() == 130);
+    }
+
+    #[test]
+    fn hashrate_throttle_slows_workers_down() {
+        let executor =
+            CpuExecutor::new(2, Arc::new(MiningStats::new())).with_scheduling(Scheduling {
+                throttle: Some(Throttle::Hashrate(20_000)),
+                ..Scheduling::default()
+            });
+        let (tx, _rx) = mpsc::channel();
+
+        let mut config = single_target_config(Arc::new(EthereumChain::new()), true, tx);
+        config.generation_mode = GenerationMode::Range {
+            start: 1.into(),
+            end: 2000.into(),
+        };
+        config.max_attempts = 0;
+
+        // Each worker gets 1000 keys at 10k addr/s: at least 100ms.
+        let started = Instant::now();
+        let result = executor.execute(config).unwrap();
+        assert_eq!(result.attempts, 2000);
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+
+    fn recovery_run(
+        template: RecoveryTemplate,
+        address: &str,
+    ) -> (MiningResult, Vec<FoundAddress>) {
+        let executor = CpuExecutor::new(2, Arc::new(MiningStats::new()));
+        let (tx, rx) = mpsc::channel();
+
+        let mut config = single_target_config(Arc::new(EthereumChain::new()), true, tx);
+        config.targets[0].matcher = Arc::new(crate::matchers::PrefixMatcher::new(address));
+        config.targets[0].limit = 1;
+        config.generation_mode = GenerationMode::Recover(template);
+
+        let result = executor.execute(config).unwrap();
+        (result, rx.try_iter().collect())
+    }
+
+    #[test]
+    fn recovery_finds_missing_mnemonic_word() {
+        let template = crate::core::recovery::MnemonicTemplate::parse(
+            "? abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let (result, found) = recovery_run(
+            RecoveryTemplate::Mnemonic {
+                template,
+                derivation_path: "m/44'/60'/0'/0/0".parse().unwrap(),
+            },
+            "9858effd232b4033e47d90003d41ec34ecaeda94",
+        );
+
+        assert_eq!(result.stop_reason, StopReason::LimitReached);
+        assert!(matches!(
+            &found[0].secret,
+            SecretInfo::Mnemonic { phrase, .. } if phrase.expose().starts_with("abandon abandon")
+        ));
+    }
+
+    #[test]
+    fn recovery_exhausts_key_candidates_without_match() {
+        let template =
+            crate::core::recovery::KeyTemplate::parse(&format!("{}??", "0".repeat(62))).unwrap();
+        let (result, found) = recovery_run(RecoveryTemplate::Key(template), "ffffffffff");
+
+        // Candidate 0 is not a valid key, leaving 255 to derive.
+        assert_eq!(result.attempts, 255);
+        assert_eq!(result.stop_reason, StopReason::RangeExhausted);
+        assert!(found.is_empty());
+    }
+
+    fn checkpointed_run(
+        seed: [u8; 32],
+        max_attempts: u64,
+        checkpoint: CheckpointConfig,
+    ) -> Result<(MiningResult, Vec<String>)> {
+        let executor = CpuExecutor::new(2, Arc::new(MiningStats::new()));
+        let (tx, rx) = mpsc::channel();
+
+        let mut config = single_target_config(Arc::new(EthereumChain::new()), true, tx);
+        config.seed = Some(seed);
+        config.max_attempts = max_attempts;
+        config.checkpoint = Some(checkpoint);
+
+        let result = executor.execute(config)?;
+        Ok((result, rx.try_iter().map(|f| f.address).collect()))
+    }
+
+    #[test]
+    fn resumed_run_continues_where_checkpoint_left_off() {
+        let mut path = std::env::temp_dir();
+        path.push("vanity_miner_resume_test.json");
+        let checkpoint = |resume| CheckpointConfig {
+            path: path.clone(),
+            interval: std::time::Duration::from_secs(3600),
+            resume,
+        };
+
+        let mut expected = seeded_run([9; 32], 2, GenerationMode::PrivateKey);
+        let mut all = Vec::new();
+
+        // `seeded_run` stops at 7 attempts; go to 3, then resume up to 7.
+        let (first, found) = checkpointed_run([9; 32], 3, checkpoint(None)).unwrap();
+        assert_eq!(first.attempts, 3);
+        all.extend(found);
+
+        let saved = Checkpoint::load(&path).unwrap();
+        assert_eq!(saved.attempts, 3);
+        assert_eq!(saved.found, 3);
+
+        let (second, found) =
+            checkpointed_run([9; 32], 7, checkpoint(Some(saved.clone()))).unwrap();
+        assert_eq!(second.attempts, 7);
+        assert_eq!(second.found, 7);
+        all.extend(found);
+
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+
+        let err = checkpointed_run([1; 32], 7, checkpoint(Some(saved))).unwrap_err();
+        assert!(err.to_string().contains("does not match"));
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
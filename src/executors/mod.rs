@@ -1,3 +1,6 @@
+mod checkpointer;
 pub mod cpu;
+pub mod scheduling;
 
 pub use cpu::CpuExecutor;
+pub use scheduling::{Priority, Scheduling, Throttle};
@@ -0,0 +1,319 @@
+use std::{
+    fmt, thread,
+    time::{Duration, Instant},
+};
+
+use crate::core::types::{Result, VanityError};
+
+/// Longest single sleep while throttled, so stop requests are noticed quickly.
+const MAX_SLEEP_SLICE: Duration = Duration::from_millis(100);
+
+/// OS scheduling priority for worker threads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Priority {
+    #[default]
+    Normal,
+    /// Nice level 1-19; higher yields more readily to other processes.
+    Nice(u8),
+    /// Only run when the CPU would otherwise be idle (Linux `SCHED_IDLE`).
+    Idle,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Priority::Normal => f.write_str("normal priority"),
+            Priority::Nice(n) => write!(f, "nice {n}"),
+            Priority::Idle => f.write_str("idle priority"),
+        }
+    }
+}
+
+/// Caps how much work the miner does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Throttle {
+    /// Keep each worker busy at most this percentage (1-100) of the time.
+    CpuPercent(u8),
+    /// Total addresses per second across all workers.
+    Hashrate(u64),
+}
+
+impl fmt::Display for Throttle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Throttle::CpuPercent(p) => write!(f, "throttled to {p}% CPU per thread"),
+            Throttle::Hashrate(r) => write!(f, "throttled to {r} addr/s"),
+        }
+    }
+}
+
+/// Where and how eagerly the worker threads run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scheduling {
+    /// Cores to pin workers to, round-robin; empty leaves placement to the OS.
+    pub cpus: Vec<usize>,
+    pub priority: Priority,
+    pub throttle: Option<Throttle>,
+}
+
+impl Scheduling {
+    /// Whether worker threads need OS-level changes when they start.
+    pub fn affects_threads(&self) -> bool {
+        !self.cpus.is_empty() || self.priority != Priority::Normal
+    }
+
+    /// The core worker `index` is pinned to, if any.
+    pub fn cpu_for(&self, index: usize) -> Option<usize> {
+        (!self.cpus.is_empty()).then(|| self.cpus[index % self.cpus.len()])
+    }
+
+    /// Pin the calling thread (worker `index`) and set its priority.
+    pub fn apply_to_current_thread(&self, index: usize) -> Result<()> {
+        if let Some(cpu) = self.cpu_for(index) {
+            sys::pin_to_cpu(cpu)
+                .map_err(|e| failed(format!("cannot pin worker {index} to CPU {cpu}: {e}")))?;
+        }
+        match self.priority {
+            Priority::Normal => Ok(()),
+            Priority::Nice(n) => sys::set_nice(n)
+                .map_err(|e| failed(format!("cannot set nice {n} for worker {index}: {e}"))),
+            Priority::Idle => sys::set_idle()
+                .map_err(|e| failed(format!("cannot set idle priority for worker {index}: {e}"))),
+        }
+    }
+}
+
+impl fmt::Display for Scheduling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cpus.is_empty() {
+            f.write_str("unpinned")?;
+        } else {
+            let cpus: Vec<String> = self.cpus.iter().map(usize::to_string).collect();
+            write!(f, "pinned to CPUs {}", cpus.join(","))?;
+        }
+        write!(f, ", {}", self.priority)?;
+        match &self.throttle {
+            Some(throttle) => write!(f, ", {throttle}"),
+            None => f.write_str(", unthrottled"),
+        }
+    }
+}
+
+/// Cores the process may run on, or `None` where this cannot be queried.
+pub fn available_cpus() -> Option<Vec<usize>> {
+    sys::available_cpus()
+}
+
+/// Slows one worker down to its share of a [`Throttle`].
+pub(crate) struct Pacer {
+    throttle: Throttle,
+    /// Workers sharing a hashrate limit.
+    workers: u64,
+    started: Instant,
+    last: Instant,
+    attempts: u64,
+}
+
+impl Pacer {
+    pub(crate) fn new(throttle: Throttle, workers: usize, now: Instant) -> Self {
+        Self {
+            throttle,
+            workers: workers.max(1) as u64,
+            started: now,
+            last: now,
+            attempts: 0,
+        }
+    }
+
+    /// How long to rest after `attempts` more attempts ending at `now`.
+    fn delay(&mut self, attempts: u64, now: Instant) -> Duration {
+        self.attempts += attempts;
+        let delay = match self.throttle {
+            Throttle::CpuPercent(percent) => {
+                let percent = u32::from(percent.clamp(1, 100));
+                now.duration_since(self.last) * (100 - percent) / percent
+            }
+            Throttle::Hashrate(rate) => {
+                let per_worker = (rate as f64 / self.workers as f64).max(f64::MIN_POSITIVE);
+                let due = Duration::from_secs_f64(self.attempts as f64 / per_worker);
+                due.saturating_sub(now.duration_since(self.started))
+            }
+        };
+        self.last = now + delay;
+        delay
+    }
+
+    /// Rest after `attempts` attempts, waking early once `stop` returns true.
+    pub(crate) fn pace(&mut self, attempts: u64, stop: impl Fn() -> bool) {
+        let delay = self.delay(attempts, Instant::now());
+        let until = Instant::now() + delay;
+        loop {
+            let left = until.saturating_duration_since(Instant::now());
+            if left.is_zero() || stop() {
+                break;
+            }
+            thread::sleep(left.min(MAX_SLEEP_SLICE));
+        }
+        self.last = Instant::now();
+    }
+}
+
+fn failed(reason: String) -> VanityError {
+    VanityError::Scheduling(reason)
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::{io, mem};
+
+    pub(super) fn pin_to_cpu(cpu: usize) -> io::Result<()> {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "CPU index out of range",
+            ));
+        }
+        // SAFETY: `set` is a plain bitmask sized for `sched_setaffinity`, and
+        // pid 0 targets the calling thread.
+        unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+            libc::CPU_SET(cpu, &mut set);
+            if libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// On Linux, `setpriority` with who = 0 changes the calling thread only.
+    pub(super) fn set_nice(nice: u8) -> io::Result<()> {
+        // SAFETY: plain syscall without pointers.
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, 0, libc::c_int::from(nice)) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub(super) fn set_idle() -> io::Result<()> {
+        let param = libc::sched_param { sched_priority: 0 };
+        // SAFETY: `param` outlives the call; pid 0 targets the calling thread.
+        if unsafe { libc::sched_setscheduler(0, libc::SCHED_IDLE, &param) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub(super) fn available_cpus() -> Option<Vec<usize>> {
+        // SAFETY: `set` is filled by the kernel and only read through CPU_ISSET.
+        unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+            if libc::sched_getaffinity(0, mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+                return None;
+            }
+            Some(
+                (0..libc::CPU_SETSIZE as usize)
+                    .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(io::ErrorKind::Unsupported, "only supported on Linux")
+    }
+
+    pub(super) fn pin_to_cpu(_cpu: usize) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub(super) fn set_nice(_nice: u8) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub(super) fn set_idle() -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub(super) fn available_cpus() -> Option<Vec<usize>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_effective_settings() {
+        assert_eq!(
+            Scheduling::default().to_string(),
+            "unpinned, normal priority, unthrottled"
+        );
+        let scheduling = Scheduling {
+            cpus: vec![2, 3],
+            priority: Priority::Nice(10),
+            throttle: Some(Throttle::CpuPercent(50)),
+        };
+        assert_eq!(
+            scheduling.to_string(),
+            "pinned to CPUs 2,3, nice 10, throttled to 50% CPU per thread"
+        );
+        assert_eq!(scheduling.cpu_for(0), Some(2));
+        assert_eq!(scheduling.cpu_for(3), Some(3));
+        assert!(scheduling.affects_threads());
+        assert!(!Scheduling::default().affects_threads());
+    }
+
+    #[test]
+    fn cpu_percent_rests_in_proportion_to_work() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new(Throttle::CpuPercent(25), 4, start);
+        let delay = pacer.delay(1000, start + Duration::from_millis(10));
+        assert_eq!(delay, Duration::from_millis(30));
+    }
+
+    #[test]
+    fn hashrate_splits_the_limit_between_workers() {
+        let start = Instant::now();
+        // 1000 addr/s over 2 workers: 500 attempts are due after one second.
+        let mut pacer = Pacer::new(Throttle::Hashrate(1000), 2, start);
+        let delay = pacer.delay(500, start + Duration::from_millis(200));
+        assert_eq!(delay, Duration::from_millis(800));
+        // Already behind schedule: no rest.
+        let delay = pacer.delay(100, start + Duration::from_secs(5));
+        assert!(delay.is_zero());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pins_a_thread_to_an_available_cpu() {
+        let cpus = available_cpus().expect("affinity is queryable");
+        let cpu = cpus[0];
+        let scheduling = Scheduling {
+            cpus: vec![cpu],
+            priority: Priority::Nice(5),
+            throttle: None,
+        };
+        let pinned = thread::spawn(move || {
+            scheduling.apply_to_current_thread(0).unwrap();
+            available_cpus()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(pinned, Some(vec![cpu]));
+
+        let bad = Scheduling {
+            cpus: vec![usize::MAX],
+            ..Scheduling::default()
+        };
+        assert!(thread::spawn(move || bad.apply_to_current_thread(0))
+            .join()
+            .unwrap()
+            .is_err());
+    }
+}
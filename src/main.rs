@@ -1,17 +1,33 @@
-use std::sync::{mpsc, Arc};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{mpsc, Arc},
+    time::Instant,
+};
 
 use clap::Parser;
 use indicatif::ProgressBar;
 
 use vanity_miner::{
-    cli::{build_runtime_config, Args},
+    cli::{
+        build_runtime_config, run_combine, run_decrypt, run_estimate, run_results, run_verify,
+        Args, Command, OutputEncryption, RuntimeConfig,
+    },
     core::{
-        config::MiningConfig,
+        config::{GenerationMode, MiningConfig},
+        difficulty::{AddressSpace, Difficulty},
         executor::Executor,
-        types::{FoundAddress, VanityError},
+        range::KeyRange,
+        types::{FoundAddress, MiningResult, VanityError},
+        CancellationToken,
     },
     executors::CpuExecutor,
-    output::{CombinedResultSink, ConsoleResultSink, ConsoleStatsSink, CsvResultSink, ResultSink},
+    output::{
+        share_paths, CombinedResultSink, ConsoleResultSink, ConsoleStatsSink, CsvResultSink,
+        CsvSchema, DynResultSink, EncryptedResultSink, EncryptionKey, JsonlResultSink,
+        KeystoreResultSink, OutputFormat, PaperWalletSink, ResultStore, RoutedResultSink,
+        ShareResultSink, SqliteResultSink, WebhookResultSink,
+    },
     stats::{MiningStats, StatsReporter},
 };
 
@@ -26,29 +42,107 @@ fn main() {
 
 fn run() -> Result<()> {
     let args = Args::parse();
-    let runtime = build_runtime_config(&args)?;
+
+    match &args.command {
+        Some(Command::Estimate(estimate)) => run_estimate(estimate),
+        Some(Command::Decrypt(decrypt)) => run_decrypt(decrypt),
+        Some(Command::Results(results)) => run_results(results),
+        Some(Command::Verify(verify)) => run_verify(verify),
+        Some(Command::Combine(combine)) => run_combine(combine),
+        None => run_mining(&args),
+    }
+}
+
+fn run_mining(args: &Args) -> Result<()> {
+    let runtime = build_runtime_config(args)?;
+
+    if runtime.seed.is_some() {
+        warn_seeded_run();
+    }
+
+    if let Some(resume) = runtime.checkpoint.as_ref().and_then(|c| c.resume.as_ref()) {
+        println!(
+            "Resuming: {} attempts, {} found so far",
+            resume.attempts, resume.found
+        );
+    }
 
     let stats = Arc::new(MiningStats::new());
-    let executor = CpuExecutor::new(runtime.threads, Arc::clone(&stats));
+    let executor = CpuExecutor::new(runtime.threads, Arc::clone(&stats))
+        .with_scheduling(runtime.scheduling.clone());
+    println!(
+        "Workers: {} threads, {}",
+        executor.threads(),
+        executor.scheduling()
+    );
 
     let (tx, rx) = mpsc::channel::<FoundAddress>();
 
     let progress = ProgressBar::new_spinner();
 
+    let cancel = CancellationToken::new();
+    install_signal_handler(cancel.clone(), progress.clone())?;
+
     let mut combined_sink = CombinedResultSink::default();
 
     if !runtime.no_file {
-        let csv_sink = CsvResultSink::new(&runtime.output).map_err(VanityError::IoError)?;
-        combined_sink.push(Box::new(csv_sink));
+        let encryption = match &runtime.encryption {
+            Some(OutputEncryption::Recipients(recipients)) => {
+                Some(EncryptionKey::Recipients(recipients.clone()))
+            }
+            Some(OutputEncryption::Passphrase) => Some(EncryptionKey::Passphrase(
+                runtime
+                    .password
+                    .read("Output passphrase: ", true)?
+                    .expose()
+                    .clone()
+                    .into(),
+            )),
+            None => None,
+        };
+        combined_sink.push(Box::new(build_file_sink(&runtime, encryption)?));
+    }
+
+    let run = match &runtime.db {
+        Some(db) => {
+            let store = ResultStore::open(db)?;
+            let run = store.begin_run(&runtime.describe().to_string())?;
+            combined_sink.push(Box::new(SqliteResultSink::new(store, run)));
+            println!("Database: {} (run {run})", db.display());
+            Some((db.clone(), run))
+        }
+        None => None,
+    };
+
+    if let Some(paper) = &runtime.paper_wallet {
+        combined_sink.push(Box::new(PaperWalletSink::new(&paper.dir, paper.format)?));
+        println!("Paper wallets: {} ({})", paper.dir.display(), paper.format);
     }
 
-    if runtime.console || runtime.no_file {
-        combined_sink.push(Box::new(ConsoleResultSink::with_progress_bar(
-            progress.clone(),
-        )));
+    if let Some(webhook) = &runtime.webhook {
+        combined_sink.push(Box::new(WebhookResultSink::new(webhook.clone())?));
+        let redacted = if webhook.redacts() { " (redacted)" } else { "" };
+        println!("Webhook: {}{redacted}", webhook.url());
+    }
+
+    if runtime.console || runtime.console_qr || runtime.no_file {
+        combined_sink.push(Box::new(
+            ConsoleResultSink::with_progress_bar(progress.clone()).with_qr(runtime.console_qr),
+        ));
+    }
+
+    let mut sink: DynResultSink = Box::new(combined_sink);
+    if let Some(keystore) = &runtime.keystore {
+        let password = runtime.password.read("Keystore password: ", true)?;
+        let mut keystore_sink =
+            KeystoreResultSink::new(&keystore.dir, keystore.kdf, password, sink)?;
+        for t in &runtime.targets {
+            keystore_sink.route(t.target.name.clone(), Arc::clone(&t.target.chain));
+        }
+        println!("Keystores: {} ({})", keystore.dir.display(), keystore.kdf);
+        sink = Box::new(keystore_sink);
     }
 
-    let mut sink = combined_sink;
     let sink_handle = std::thread::spawn(move || {
         for found in rx {
             if let Err(e) = sink.handle(&found) {
@@ -57,17 +151,33 @@ fn run() -> Result<()> {
         }
     });
 
+    // The ETA only makes sense for a single goal.
+    let mut stats_sink = ConsoleStatsSink::new(progress.clone());
+    if let GenerationMode::Range { start, end } = &runtime.generation_mode {
+        stats_sink = stats_sink.with_range(KeyRange::new(*start, *end).ok());
+    }
+    if let [only] = runtime.targets.as_slice() {
+        stats_sink = stats_sink.with_difficulty(Difficulty::for_matcher(
+            only.target.matcher.as_ref(),
+            &AddressSpace::for_config(&only.target.address_config),
+        ));
+    }
+
     let mut reporter = StatsReporter::new(Arc::clone(&stats));
-    reporter.start(Box::new(ConsoleStatsSink::new(progress.clone())));
+    reporter.start(Box::new(stats_sink));
 
     let config = MiningConfig {
-        chain: runtime.chain,
-        matcher: runtime.matcher,
+        targets: runtime.mining_targets(),
         generation_mode: runtime.generation_mode,
-        address_config: runtime.address_config,
+        seed: runtime.seed,
+        checkpoint: runtime.checkpoint,
         max_attempts: runtime.max_attempts,
-        limit: runtime.limit,
+        // A budget too long to represent as an instant never runs out.
+        deadline: runtime
+            .time_budget
+            .and_then(|budget| Instant::now().checked_add(budget)),
         result_sender: tx,
+        cancel: cancel.clone(),
     };
 
     let result = executor.execute(config);
@@ -77,10 +187,126 @@ fn run() -> Result<()> {
         .join()
         .expect("result sink thread should join successfully");
 
+    // Worker failures still carry the statistics gathered before the error.
+    let summary = match &result {
+        Ok(result) => Some(result),
+        Err(VanityError::WorkerFailed { partial, .. }) => Some(partial.as_ref()),
+        Err(_) => None,
+    };
+    if let Some(summary) = summary {
+        print_summary(summary);
+        if let Some((db, run)) = &run {
+            ResultStore::open(db)?.finish_run(*run, summary)?;
+        }
+    }
+
+    result.map(|_| ())
+}
+
+fn warn_seeded_run() {
+    eprintln!("================================================================");
+    eprintln!("WARNING: --seed makes every generated key reproducible.");
+    eprintln!("Anyone who knows or guesses the seed can recreate these keys and");
+    eprintln!("steal their funds. Do NOT fund them unless the seed is itself");
+    eprintln!("secret and high-entropy (e.g. 32 random bytes). For audits and");
+    eprintln!("tests only.");
+    eprintln!("================================================================");
+}
+
+fn print_summary(result: &MiningResult) {
     println!("Done");
+    println!("Stop reason: {}", result.stop_reason);
     println!("Found: {}", result.found);
     println!("Attempts: {}", result.attempts);
     println!("Hashrate: {:.2} addr/s", result.hashrate);
 
-    Ok(())
+    for target in result.targets.iter().filter(|t| t.name.is_some()) {
+        let name = target.name.as_deref().unwrap_or_default();
+        match target.limit {
+            0 => println!("  {name}: {}", target.found),
+            limit => println!("  {name}: {}/{limit}", target.found),
+        }
+    }
+}
+
+/// Cancel the run on SIGINT/SIGTERM so results and the summary are not lost.
+/// A second signal exits immediately.
+fn install_signal_handler(cancel: CancellationToken, progress: ProgressBar) -> Result<()> {
+    ctrlc::set_handler(move || {
+        if cancel.is_cancelled() {
+            std::process::exit(130);
+        }
+        progress.println("Stopping... (press Ctrl-C again to quit immediately)");
+        cancel.cancel();
+    })
+    .map_err(|e| VanityError::IoError(std::io::Error::other(e)))
+}
+
+/// Result files for every target, one per distinct output path, encrypted
+/// with `encryption` if given, or share files with `--shares`.
+fn build_file_sink(
+    runtime: &RuntimeConfig,
+    encryption: Option<EncryptionKey>,
+) -> Result<RoutedResultSink> {
+    let mut routed = RoutedResultSink::new();
+    let mut by_path: HashMap<&str, usize> = HashMap::new();
+
+    for t in &runtime.targets {
+        let index = match by_path.get(t.output.as_str()) {
+            Some(&index) => index,
+            None => {
+                let target_column = t.target.name.is_some();
+                let format = runtime
+                    .format
+                    .unwrap_or_else(|| OutputFormat::for_path(Path::new(&t.output)));
+                let schema = match &runtime.csv_columns {
+                    Some(columns) => CsvSchema::new(columns.clone()),
+                    None => CsvSchema::legacy(target_column, runtime.keystore.is_some()),
+                };
+                schema.check_holds(runtime.secret_kind())?;
+                let sink: DynResultSink = match (runtime.shares, &encryption, format) {
+                    (Some(policy), _, _) => {
+                        let paths = share_paths(Path::new(&t.output), policy.count);
+                        let names: Vec<String> =
+                            paths.iter().map(|p| p.display().to_string()).collect();
+                        println!("Shares ({policy}): {}", names.join(", "));
+                        Box::new(ShareResultSink::new(&paths, policy)?)
+                    }
+                    (None, Some(key), format) => Box::new(EncryptedResultSink::new(
+                        encrypted_path(&t.output),
+                        key.clone(),
+                        |buffer| {
+                            Ok(match format {
+                                OutputFormat::Csv => {
+                                    Box::new(CsvResultSink::from_writer(buffer, schema, true)?)
+                                }
+                                OutputFormat::Jsonl => {
+                                    Box::new(JsonlResultSink::from_writer(buffer))
+                                }
+                            })
+                        },
+                    )?),
+                    (None, None, OutputFormat::Jsonl) => Box::new(JsonlResultSink::new(&t.output)?),
+                    (None, None, OutputFormat::Csv) => {
+                        Box::new(CsvResultSink::with_schema(&t.output, schema)?)
+                    }
+                };
+                let index = routed.push(sink);
+                by_path.insert(&t.output, index);
+                index
+            }
+        };
+        routed.route(t.target.name.clone(), index);
+    }
+
+    Ok(routed)
+}
+
+/// Encrypted results go to `<output>.age`.
+fn encrypted_path(output: &str) -> String {
+    if output.ends_with(".age") {
+        output.to_string()
+    } else {
+        format!("{output}.age")
+    }
 }
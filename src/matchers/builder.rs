@@ -1,9 +1,16 @@
 use std::sync::Arc;
 
-use crate::core::matcher::{DynMatcher, Matcher};
+use serde::Deserialize;
+
+use crate::core::{
+    difficulty::AddressSpace,
+    matcher::{DynMatcher, Matcher},
+};
 
 use super::{
-    CompositeLogic, CompositeMatcher, ContainsMatcher, PrefixMatcher, RegexMatcher, SuffixMatcher,
+    expand_leet, Anchor, CaseMode, CharClass, CompositeLogic, CompositeMatcher, ContainsMatcher,
+    MaskMatcher, MatcherError, MirrorMatcher, PalindromeMatcher, PrefixMatcher, RegexMatcher,
+    RepeatMatcher, RunMatcher, SequenceMatcher, StretchMatcher, SuffixMatcher,
 };
 
 /// High-level configuration for building a matcher.
@@ -11,12 +18,63 @@ use super::{
 /// This is the shape we expect from CLI/config layers.
 /// All fields are optional; if nothing is set, the resulting matcher
 /// will match any address.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
 pub struct MatcherConfig {
     pub prefix: Option<String>,
     pub suffix: Option<String>,
     pub contains: Vec<String>,
     pub regex: Option<String>,
+    pub mask: Option<String>,
+    /// Case handling for prefix, suffix, contains and regex patterns.
+    pub case: CaseMode,
+    /// Expand prefix, suffix and contains patterns into their leetspeak spellings.
+    pub leet: bool,
+    /// Number of identical leading characters.
+    pub repeat_prefix: Option<usize>,
+    /// Number of identical trailing characters.
+    pub repeat_suffix: Option<usize>,
+    /// Number of characters the suffix mirrors from the prefix.
+    pub mirror: Option<usize>,
+    /// Minimum palindrome length anywhere.
+    pub palindrome: Option<usize>,
+    /// Minimum run of one repeated character anywhere.
+    pub run: Option<usize>,
+    /// Minimum stretch of consecutive digits.
+    pub digits: Option<usize>,
+    /// Minimum stretch of consecutive letters.
+    pub letters: Option<usize>,
+    /// Minimum ascending sequence length (e.g. `0123456`).
+    pub ascending: Option<usize>,
+}
+
+impl MatcherConfig {
+    /// Whether any constraint is set; without one every address matches.
+    pub fn has_constraints(&self) -> bool {
+        self.prefix.is_some()
+            || self.suffix.is_some()
+            || !self.contains.is_empty()
+            || self.regex.is_some()
+            || self.mask.is_some()
+            || !self.lengths().is_empty()
+    }
+
+    /// The aesthetic pattern lengths that are set, with their option names.
+    pub fn lengths(&self) -> Vec<(&'static str, usize)> {
+        [
+            ("repeat-prefix", self.repeat_prefix),
+            ("repeat-suffix", self.repeat_suffix),
+            ("mirror", self.mirror),
+            ("palindrome", self.palindrome),
+            ("run", self.run),
+            ("digits", self.digits),
+            ("letters", self.letters),
+            ("ascending", self.ascending),
+        ]
+        .into_iter()
+        .filter_map(|(name, len)| len.map(|len| (name, len)))
+        .collect()
+    }
 }
 
 /// Build a matcher from the provided configuration.
@@ -26,26 +84,96 @@ pub struct MatcherConfig {
 /// - If exactly one matcher is created, it is returned directly.
 /// - If multiple matchers are created, they are combined with `CompositeLogic::All`.
 ///
-/// Only regex construction can fail, so the error type is `regex::Error`.
-pub fn build_matcher(config: MatcherConfig) -> Result<DynMatcher, regex::Error> {
+/// `space` describes the addresses being matched: masks are validated against
+/// it, `CaseMode::Checksum` is resolved for it and leetspeak variants are
+/// limited to its alphabet. Leetspeak variants of one pattern are combined
+/// with `CompositeLogic::Any`.
+pub fn build_matcher(
+    config: MatcherConfig,
+    space: &AddressSpace,
+) -> Result<DynMatcher, MatcherError> {
     let mut matchers: Vec<DynMatcher> = vec![];
+    let case = config.case.resolve(space);
+
+    let expand = |pattern: String| -> Result<Vec<String>, MatcherError> {
+        if config.leet {
+            expand_leet(&pattern, space)
+        } else {
+            Ok(vec![pattern])
+        }
+    };
 
-    if let Some(prefix) = config.prefix {
-        matchers.push(Arc::new(PrefixMatcher::new(prefix)) as DynMatcher);
+    if let Some(prefix) = config.prefix.clone() {
+        let variants = expand(prefix)?
+            .into_iter()
+            .map(|p| Arc::new(PrefixMatcher::with_case(p, case)) as DynMatcher)
+            .collect();
+        matchers.push(any_of(variants));
     }
 
-    if let Some(suffix) = config.suffix {
-        matchers.push(Arc::new(SuffixMatcher::new(suffix)) as DynMatcher);
+    if let Some(suffix) = config.suffix.clone() {
+        let variants = expand(suffix)?
+            .into_iter()
+            .map(|p| Arc::new(SuffixMatcher::with_case(p, case)) as DynMatcher)
+            .collect();
+        matchers.push(any_of(variants));
     }
 
     if !config.contains.is_empty() {
-        matchers.push(Arc::new(ContainsMatcher::new(config.contains)) as DynMatcher);
+        let mut patterns = Vec::new();
+        for pattern in config.contains.clone() {
+            patterns.extend(expand(pattern)?);
+        }
+        matchers.push(Arc::new(ContainsMatcher::with_case(patterns, case)) as DynMatcher);
     }
 
     if let Some(pattern) = config.regex {
+        // Addresses keep their display case, so insensitive matching has to
+        // be requested from the regex engine.
+        let pattern = if case.is_case_sensitive() {
+            pattern
+        } else {
+            format!("(?i){pattern}")
+        };
         matchers.push(Arc::new(RegexMatcher::new(&pattern)?) as DynMatcher);
     }
 
+    if let Some(mask) = config.mask {
+        matchers.push(Arc::new(MaskMatcher::new(&mask, space)?) as DynMatcher);
+    }
+
+    if let Some(count) = config.repeat_prefix {
+        matchers.push(Arc::new(RepeatMatcher::new(Anchor::Start, count)) as DynMatcher);
+    }
+
+    if let Some(count) = config.repeat_suffix {
+        matchers.push(Arc::new(RepeatMatcher::new(Anchor::End, count)) as DynMatcher);
+    }
+
+    if let Some(len) = config.mirror {
+        matchers.push(Arc::new(MirrorMatcher::new(len)) as DynMatcher);
+    }
+
+    if let Some(len) = config.palindrome {
+        matchers.push(Arc::new(PalindromeMatcher::new(len)) as DynMatcher);
+    }
+
+    if let Some(len) = config.run {
+        matchers.push(Arc::new(RunMatcher::new(len)) as DynMatcher);
+    }
+
+    if let Some(len) = config.digits {
+        matchers.push(Arc::new(StretchMatcher::new(CharClass::Digit, len)) as DynMatcher);
+    }
+
+    if let Some(len) = config.letters {
+        matchers.push(Arc::new(StretchMatcher::new(CharClass::Letter, len)) as DynMatcher);
+    }
+
+    if let Some(len) = config.ascending {
+        matchers.push(Arc::new(SequenceMatcher::new(len)) as DynMatcher);
+    }
+
     let result: DynMatcher = match matchers.len() {
         0 => Arc::new(AlwaysMatch) as DynMatcher,
         1 => matchers.into_iter().next().unwrap(),
@@ -55,6 +183,14 @@ pub fn build_matcher(config: MatcherConfig) -> Result<DynMatcher, regex::Error>
     Ok(result)
 }
 
+fn any_of(mut matchers: Vec<DynMatcher>) -> DynMatcher {
+    if matchers.len() == 1 {
+        matchers.remove(0)
+    } else {
+        Arc::new(CompositeMatcher::new(CompositeLogic::Any, matchers)) as DynMatcher
+    }
+}
+
 /// A matcher that always returns `true`.
 ///
 /// Used as a fallback when no constraints are configured.
@@ -69,16 +205,24 @@ impl Matcher for AlwaysMatch {
     fn description(&self) -> String {
         "always".into()
     }
+
+    fn probability(&self, _space: &AddressSpace) -> Option<f64> {
+        Some(1.0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn space() -> AddressSpace {
+        AddressSpace::ethereum(true)
+    }
+
     #[test]
     fn empty_config_yields_always_matcher() {
         let cfg = MatcherConfig::default();
-        let matcher = build_matcher(cfg).expect("build matcher");
+        let matcher = build_matcher(cfg, &space()).expect("build matcher");
         assert!(matcher.matches("anything"));
         assert_eq!(matcher.description(), "always");
     }
@@ -89,7 +233,7 @@ mod tests {
             prefix: Some("abc".into()),
             ..Default::default()
         };
-        let matcher = build_matcher(cfg).expect("build matcher");
+        let matcher = build_matcher(cfg, &space()).expect("build matcher");
         assert!(matcher.matches("abcdef"));
         assert!(!matcher.matches("xabcdef"));
         assert!(matcher.description().starts_with("prefix:"));
@@ -103,7 +247,7 @@ mod tests {
             ..Default::default()
         };
 
-        let matcher = build_matcher(cfg).expect("build matcher");
+        let matcher = build_matcher(cfg, &space()).expect("build matcher");
 
         assert!(matcher.matches("abc000xyz"));
         assert!(!matcher.matches("abc000000"));
@@ -122,7 +266,80 @@ mod tests {
             ..Default::default()
         };
 
-        let err = build_matcher(cfg).err().expect("expected regex error");
+        let err = build_matcher(cfg, &space())
+            .err()
+            .expect("expected regex error");
         assert!(err.to_string().contains("unclosed"));
     }
+
+    #[test]
+    fn mask_errors_are_propagated() {
+        let cfg = MatcherConfig {
+            mask: Some("0xdead".into()),
+            ..Default::default()
+        };
+
+        let err = build_matcher(cfg, &space())
+            .err()
+            .expect("expected mask error");
+        assert!(matches!(err, MatcherError::InvalidMask(_)));
+    }
+
+    #[test]
+    fn leet_prefix_matches_any_spelling() {
+        let cfg = MatcherConfig {
+            prefix: Some("coffee".into()),
+            leet: true,
+            ..Default::default()
+        };
+
+        let matcher = build_matcher(cfg, &space()).expect("build matcher");
+        assert!(matcher.matches("c0ffee00"));
+        assert!(matcher.matches("C0FF3E00"));
+        assert!(!matcher.matches("coffee00"));
+        assert!(matcher.description().starts_with("any("));
+    }
+
+    #[test]
+    fn checksum_case_is_enforced_on_ethereum_only() {
+        let cfg = MatcherConfig {
+            prefix: Some("DeAd".into()),
+            case: CaseMode::Checksum,
+            ..Default::default()
+        };
+
+        let eth = build_matcher(cfg.clone(), &space()).expect("build matcher");
+        assert!(eth.matches("DeAd00"));
+        assert!(!eth.matches("dead00"));
+
+        let cfx = build_matcher(cfg, &AddressSpace::conflux()).expect("build matcher");
+        assert!(cfx.matches("dead00"));
+    }
+
+    #[test]
+    fn regex_is_case_insensitive_by_default() {
+        let cfg = MatcherConfig {
+            regex: Some("^dead".into()),
+            ..Default::default()
+        };
+
+        let matcher = build_matcher(cfg, &space()).expect("build matcher");
+        assert!(matcher.matches("DeAd00"));
+    }
+
+    #[test]
+    fn aesthetic_patterns_compose_with_prefix() {
+        let cfg = MatcherConfig {
+            prefix: Some("00".into()),
+            repeat_suffix: Some(3),
+            run: Some(5),
+            ..Default::default()
+        };
+
+        let matcher = build_matcher(cfg, &space()).expect("build matcher");
+        assert!(matcher.matches("00a77777bfff"));
+        assert!(!matcher.matches("00a7777bcfff"));
+        let desc = matcher.description();
+        assert!(desc.contains("repeat-suffix:3") && desc.contains("run:5"));
+    }
 }
@@ -0,0 +1,108 @@
+use serde::Deserialize;
+
+use crate::core::difficulty::AddressSpace;
+
+/// How a matcher treats letter case.
+///
+/// Matchers receive the address body in its display case: EIP-55 checksummed
+/// hex for Ethereum, lowercase base32 for Conflux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseMode {
+    /// Ignore case entirely.
+    #[default]
+    Insensitive,
+    /// Compare byte-for-byte with the displayed address.
+    Exact,
+    /// Enforce case only where the encoding carries it (EIP-55 checksums);
+    /// behaves like `Insensitive` on lowercase-only encodings.
+    Checksum,
+}
+
+impl CaseMode {
+    /// Resolve `Checksum` into `Exact` or `Insensitive` for a concrete chain.
+    pub fn resolve(self, space: &AddressSpace) -> Self {
+        match self {
+            CaseMode::Checksum if space.checksum_case() => CaseMode::Exact,
+            CaseMode::Checksum => CaseMode::Insensitive,
+            mode => mode,
+        }
+    }
+
+    /// Whether letter case participates in the comparison.
+    pub fn is_case_sensitive(self) -> bool {
+        !matches!(self, CaseMode::Insensitive)
+    }
+
+    /// Bring a user pattern into the form the matcher compares against.
+    pub fn normalize_pattern(self, mut pattern: String) -> String {
+        if !self.is_case_sensitive() {
+            pattern.make_ascii_lowercase();
+        }
+        pattern
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            CaseMode::Insensitive => "",
+            CaseMode::Exact => "(exact)",
+            CaseMode::Checksum => "(checksum)",
+        }
+    }
+
+    pub(crate) fn eq(self, a: &[u8], b: &[u8]) -> bool {
+        if self.is_case_sensitive() {
+            a == b
+        } else {
+            a.eq_ignore_ascii_case(b)
+        }
+    }
+
+    pub(crate) fn starts_with(self, address: &str, pattern: &str) -> bool {
+        let (a, p) = (address.as_bytes(), pattern.as_bytes());
+        a.len() >= p.len() && self.eq(&a[..p.len()], p)
+    }
+
+    pub(crate) fn ends_with(self, address: &str, pattern: &str) -> bool {
+        let (a, p) = (address.as_bytes(), pattern.as_bytes());
+        a.len() >= p.len() && self.eq(&a[a.len() - p.len()..], p)
+    }
+
+    pub(crate) fn contains(self, address: &str, pattern: &str) -> bool {
+        if self.is_case_sensitive() {
+            return address.contains(pattern);
+        }
+        let p = pattern.as_bytes();
+        p.is_empty() || address.as_bytes().windows(p.len()).any(|w| self.eq(w, p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_resolves_per_chain() {
+        assert_eq!(
+            CaseMode::Checksum.resolve(&AddressSpace::ethereum(true)),
+            CaseMode::Exact
+        );
+        assert_eq!(
+            CaseMode::Checksum.resolve(&AddressSpace::conflux()),
+            CaseMode::Insensitive
+        );
+        assert_eq!(
+            CaseMode::Exact.resolve(&AddressSpace::conflux()),
+            CaseMode::Exact
+        );
+    }
+
+    #[test]
+    fn comparisons_follow_mode() {
+        assert!(CaseMode::Insensitive.starts_with("DeAd00", "dead"));
+        assert!(!CaseMode::Exact.starts_with("DeAd00", "dead"));
+        assert!(CaseMode::Exact.ends_with("00BeEf", "BeEf"));
+        assert!(CaseMode::Insensitive.contains("00CaFe00", "cafe"));
+        assert!(!CaseMode::Exact.contains("00CaFe00", "cafe"));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::core::matcher::{DynMatcher, Matcher};
+use crate::core::{
+    difficulty::AddressSpace,
+    matcher::{DynMatcher, Matcher},
+};
 
 /// Global combination logic for composite matchers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,6 +65,23 @@ impl Matcher for CompositeMatcher {
             CompositeLogic::Any => format!("any({})", inner_decs.join(",")),
         }
     }
+
+    /// Inner matchers are assumed independent; any unestimable inner matcher
+    /// makes the whole composite unestimable.
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        let probabilities = self
+            .matchers
+            .iter()
+            .map(|m| m.probability(space))
+            .collect::<Option<Vec<f64>>>()?;
+
+        match self.logic {
+            CompositeLogic::All => Some(probabilities.iter().product()),
+            CompositeLogic::Any => {
+                Some(1.0 - probabilities.iter().map(|p| 1.0 - p).product::<f64>())
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
-use crate::core::matcher::Matcher;
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+use super::CaseMode;
 
 /// Matches addresses that contain *any* of the configured substrings.
 ///
@@ -6,19 +8,21 @@ use crate::core::matcher::Matcher;
 #[derive(Debug, Clone)]
 pub struct ContainsMatcher {
     patterns: Vec<String>,
+    case: CaseMode,
 }
 
 impl ContainsMatcher {
     pub fn new(patterns: Vec<String>) -> Self {
+        Self::with_case(patterns, CaseMode::Insensitive)
+    }
+
+    pub fn with_case(patterns: Vec<String>, case: CaseMode) -> Self {
         let patterns = patterns
             .into_iter()
-            .map(|mut p| {
-                p.make_ascii_lowercase();
-                p
-            })
+            .map(|p| case.normalize_pattern(p))
             .collect();
 
-        Self { patterns }
+        Self { patterns, case }
     }
 
     pub fn patterns(&self) -> &Vec<String> {
@@ -32,16 +36,27 @@ impl ContainsMatcher {
 
 impl Matcher for ContainsMatcher {
     fn matches(&self, address: &str) -> bool {
-        self.patterns.iter().any(|p| address.contains(p))
+        self.patterns.iter().any(|p| self.case.contains(address, p))
     }
 
     fn description(&self) -> String {
         if self.patterns.is_empty() {
             "contains:<empty>".to_string()
         } else {
-            format!("contains:{}", self.patterns.join(","))
+            format!("contains:{}{}", self.patterns.join(","), self.case.label())
         }
     }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        // Patterns are OR-ed, so the address misses only if it misses all of them.
+        let exact = self.case.resolve(space).is_case_sensitive();
+        let miss: f64 = self
+            .patterns
+            .iter()
+            .map(|p| 1.0 - space.contains_probability(p, exact))
+            .product();
+        Some(1.0 - miss)
+    }
 }
 
 #[cfg(test)]
@@ -69,4 +84,14 @@ mod tests {
         assert!(!m.matches("anything"));
         assert!(m.is_empty());
     }
+
+    #[test]
+    fn insensitive_matches_mixed_case_addresses() {
+        let m = ContainsMatcher::new(vec!["cafe".into()]);
+        assert!(m.matches("00CaFe00"));
+
+        let exact = ContainsMatcher::with_case(vec!["CaFe".into()], CaseMode::Exact);
+        assert!(exact.matches("00CaFe00"));
+        assert!(!exact.matches("00cafe00"));
+    }
 }
@@ -0,0 +1,91 @@
+use crate::core::difficulty::AddressSpace;
+
+use super::MatcherError;
+
+/// Upper bound on generated variants, to keep combined matchers cheap.
+pub const MAX_LEET_VARIANTS: usize = 4096;
+
+/// Digit look-alikes for letters.
+fn substitutes(c: char) -> &'static [char] {
+    match c.to_ascii_lowercase() {
+        'a' => &['4'],
+        'b' => &['8'],
+        'e' => &['3'],
+        'g' => &['6', '9'],
+        'i' | 'l' => &['1'],
+        'o' => &['0'],
+        's' => &['5'],
+        't' => &['7'],
+        'z' => &['2'],
+        _ => &[],
+    }
+}
+
+/// Expand `word` into every leetspeak spelling that the chain can produce.
+///
+/// Each character is kept as-is or replaced by one of its digit look-alikes;
+/// spellings containing characters outside `space`'s charset are dropped. So
+/// on hex addresses "coffee" becomes `c0ffee`, `c0ff3e`, `c0ffe3`, `c0ff33`.
+pub fn expand_leet(word: &str, space: &AddressSpace) -> Result<Vec<String>, MatcherError> {
+    let charset = space.charset();
+    let mut variants = vec![String::with_capacity(word.len())];
+
+    for c in word.chars() {
+        let options: Vec<char> = std::iter::once(c)
+            .chain(substitutes(c).iter().copied())
+            .filter(|o| charset.contains(o.to_ascii_lowercase()))
+            .collect();
+
+        if options.is_empty() {
+            return Err(MatcherError::InvalidLeet(format!(
+                "'{c}' in \"{word}\" has no spelling in this chain's alphabet"
+            )));
+        }
+
+        if variants.len() * options.len() > MAX_LEET_VARIANTS {
+            return Err(MatcherError::InvalidLeet(format!(
+                "\"{word}\" expands to more than {MAX_LEET_VARIANTS} variants"
+            )));
+        }
+
+        variants = variants
+            .iter()
+            .flat_map(|v| {
+                options.iter().map(move |o| {
+                    let mut next = v.clone();
+                    next.push(*o);
+                    next
+                })
+            })
+            .collect();
+    }
+
+    Ok(variants)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coffee_expands_to_hex_spellings() {
+        let mut variants = expand_leet("coffee", &AddressSpace::ethereum(true)).unwrap();
+        variants.sort();
+        assert_eq!(variants, vec!["c0ff33", "c0ff3e", "c0ffe3", "c0ffee"]);
+    }
+
+    #[test]
+    fn keeps_letters_valid_in_alphabet() {
+        // Conflux base32 has no 'o' or 'i' but does have 's' and 't'.
+        let variants = expand_leet("boost", &AddressSpace::conflux()).unwrap();
+        assert!(variants.contains(&"b0057".to_string()));
+        assert!(variants.contains(&"b00st".to_string()));
+        assert!(variants.iter().all(|v| !v.contains('o')));
+    }
+
+    #[test]
+    fn rejects_unspellable_words() {
+        let err = expand_leet("xyz", &AddressSpace::ethereum(true)).unwrap_err();
+        assert!(err.to_string().contains("'x'"));
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt::Write as _;
+
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+use super::MatcherError;
+
+/// Set of ASCII characters accepted at one position, as a 128-bit mask.
+type CharSet = u128;
+
+/// Matches addresses against a full-length positional mask.
+///
+/// The mask describes the whole normalized body, one token per position:
+/// - a literal character must appear at that position,
+/// - `?` accepts any character,
+/// - `[...]` accepts a character class such as `[0-9]` or `[^abc]`,
+/// - `{n}` after a token repeats it `n` times, e.g. `?{32}`.
+///
+/// A leading `0x` or network prefix (`cfx:`) is ignored and matching is
+/// case-insensitive. The mask is compiled into per-position lookups, so only
+/// constrained positions are checked.
+#[derive(Debug, Clone)]
+pub struct MaskMatcher {
+    mask: String,
+    len: usize,
+    checks: Vec<(usize, CharSet)>,
+}
+
+impl MaskMatcher {
+    /// Compile `mask`, checking it against the shape of `space`.
+    ///
+    /// The mask must have exactly `space.body_len()` positions and every
+    /// position must be satisfiable by the chain's encoding.
+    pub fn new(mask: &str, space: &AddressSpace) -> Result<Self, MatcherError> {
+        let body = strip_address_prefix(mask.trim());
+        let slots = parse_mask(body, space.body_len())?;
+
+        if slots.len() != space.body_len() {
+            return Err(MatcherError::InvalidMask(format!(
+                "mask has {} positions, but addresses have {}",
+                slots.len(),
+                space.body_len()
+            )));
+        }
+
+        let mut checks = Vec::new();
+        for (pos, slot) in slots.iter().enumerate() {
+            let allowed = charset_of(space.allowed_at(pos));
+            match slot {
+                Some(set) if set & allowed == 0 => {
+                    return Err(MatcherError::InvalidMask(format!(
+                        "position {} can only be one of \"{}\"",
+                        pos + 1,
+                        space.allowed_at(pos)
+                    )));
+                }
+                // Positions the encoding always satisfies need no check.
+                Some(set) if set & allowed != allowed => checks.push((pos, *set)),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            mask: render_mask(&slots),
+            len: slots.len(),
+            checks,
+        })
+    }
+
+    pub fn mask(&self) -> &str {
+        &self.mask
+    }
+
+    /// Number of positions that are actually checked.
+    pub fn constrained_len(&self) -> usize {
+        self.checks.len()
+    }
+}
+
+impl Matcher for MaskMatcher {
+    fn matches(&self, address: &str) -> bool {
+        let bytes = address.as_bytes();
+        bytes.len() == self.len
+            && self.checks.iter().all(|&(pos, set)| {
+                let b = bytes[pos].to_ascii_lowercase();
+                b < 128 && set & (1 << b) != 0
+            })
+    }
+
+    fn description(&self) -> String {
+        format!("mask:{}", self.mask)
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        if self.len != space.body_len() {
+            return Some(0.0);
+        }
+
+        let p = self
+            .checks
+            .iter()
+            .map(|&(pos, set)| {
+                let allowed = charset_of(space.allowed_at(pos));
+                (set & allowed).count_ones() as f64 / allowed.count_ones() as f64
+            })
+            .product();
+        Some(p)
+    }
+}
+
+fn strip_address_prefix(mask: &str) -> &str {
+    if let Some(body) = mask.strip_prefix("0x").or_else(|| mask.strip_prefix("0X")) {
+        return body;
+    }
+    mask.split_once(':').map(|(_, b)| b).unwrap_or(mask)
+}
+
+fn charset_of(chars: &str) -> CharSet {
+    chars
+        .bytes()
+        .filter(|b| b.is_ascii())
+        .fold(0, |set, b| set | (1 << b.to_ascii_lowercase()))
+}
+
+/// Parse a mask body into one slot per position; `None` means "any".
+///
+/// Masks with more than `max_len` positions are rejected while parsing, so a
+/// huge repetition count never gets allocated.
+fn parse_mask(body: &str, max_len: usize) -> Result<Vec<Option<CharSet>>, MatcherError> {
+    let chars: Vec<char> = body.chars().collect();
+    let mut slots = Vec::with_capacity(chars.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let slot = match chars[i] {
+            '?' => {
+                i += 1;
+                None
+            }
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map(|off| i + off)
+                    .ok_or_else(|| invalid("unclosed character class"))?;
+                let set = parse_class(&chars[i + 1..end])?;
+                i = end + 1;
+                Some(set)
+            }
+            '{' => return Err(invalid("repetition without a preceding token")),
+            c if c.is_ascii_alphanumeric() => {
+                i += 1;
+                Some(charset_of(c.encode_utf8(&mut [0; 4])))
+            }
+            c => return Err(invalid(&format!("unexpected character '{c}'"))),
+        };
+
+        let mut repeat = 1;
+        if chars.get(i) == Some(&'{') {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '}')
+                .map(|off| i + off)
+                .ok_or_else(|| invalid("unclosed repetition"))?;
+            let count: String = chars[i + 1..end].iter().collect();
+            repeat = count
+                .parse()
+                .map_err(|_| invalid(&format!("invalid repetition count '{count}'")))?;
+            i = end + 1;
+        }
+
+        if repeat > max_len - slots.len() {
+            return Err(invalid(&format!(
+                "mask has more than {max_len} positions, the length of an address"
+            )));
+        }
+        slots.extend(std::iter::repeat_n(slot, repeat));
+    }
+
+    Ok(slots)
+}
+
+fn parse_class(class: &[char]) -> Result<CharSet, MatcherError> {
+    let (negated, class) = match class.first() {
+        Some('^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+
+    if class.is_empty() {
+        return Err(invalid("empty character class"));
+    }
+
+    let mut set: CharSet = 0;
+    let mut i = 0;
+    while i < class.len() {
+        let start = class[i].to_ascii_lowercase();
+        if !start.is_ascii_alphanumeric() {
+            return Err(invalid(&format!("unexpected character '{start}' in class")));
+        }
+
+        if class.get(i + 1) == Some(&'-') && i + 2 < class.len() {
+            let end = class[i + 2].to_ascii_lowercase();
+            if !end.is_ascii_alphanumeric() || end < start {
+                return Err(invalid(&format!("invalid range '{start}-{end}'")));
+            }
+            for c in start..=end {
+                set |= 1 << c as u32;
+            }
+            i += 3;
+        } else {
+            set |= 1 << start as u32;
+            i += 1;
+        }
+    }
+
+    if negated {
+        set = !set & charset_of("0123456789abcdefghijklmnopqrstuvwxyz");
+    }
+
+    Ok(set)
+}
+
+/// Render slots back into a canonical mask string for descriptions.
+fn render_mask(slots: &[Option<CharSet>]) -> String {
+    let mut out = String::with_capacity(slots.len());
+    for slot in slots {
+        match slot {
+            None => out.push('?'),
+            Some(set) if set.count_ones() == 1 => out.push(set.trailing_zeros() as u8 as char),
+            Some(set) => {
+                out.push('[');
+                for c in (0u8..128).filter(|c| set & (1 << c) != 0) {
+                    let _ = write!(out, "{}", c as char);
+                }
+                out.push(']');
+            }
+        }
+    }
+    out
+}
+
+fn invalid(reason: &str) -> MatcherError {
+    MatcherError::InvalidMask(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth() -> AddressSpace {
+        AddressSpace::ethereum(true)
+    }
+
+    #[test]
+    fn matches_fixed_positions() {
+        let m = MaskMatcher::new("0xdead????????????????????????????????beef", &eth()).unwrap();
+        assert_eq!(m.constrained_len(), 8);
+        assert!(m.matches("dead0000000000000000000000000000ffffbeef"));
+        assert!(m.matches("DEad0000000000000000000000000000ffffBeEF"));
+        assert!(!m.matches("dead0000000000000000000000000000ffffbee0"));
+        assert!(!m.matches("deadbeef"));
+    }
+
+    #[test]
+    fn supports_classes_and_repetition() {
+        let m = MaskMatcher::new("[0-9]{4}?{32}[^0-9]{4}", &eth()).unwrap();
+        assert!(m.matches("1234ffffffffffffffffffffffffffffffffabcd"));
+        assert!(!m.matches("123affffffffffffffffffffffffffffffffabcd"));
+        assert!(!m.matches("1234ffffffffffffffffffffffffffffffffabc1"));
+        assert!(m.description().starts_with("mask:[0123456789]"));
+    }
+
+    #[test]
+    fn rejects_wrong_length_and_impossible_positions() {
+        let err = MaskMatcher::new("dead", &eth()).unwrap_err();
+        assert!(err.to_string().contains("4 positions"));
+
+        assert!(MaskMatcher::new("g?{39}", &eth()).is_err());
+        assert!(MaskMatcher::new("[a-f?{39}", &eth()).is_err());
+
+        // Conflux bodies always start with 'a'.
+        let cfx = AddressSpace::conflux();
+        assert!(MaskMatcher::new("cfx:b?{41}", &cfx).is_err());
+        assert!(MaskMatcher::new("cfx:aa?{40}", &cfx).is_ok());
+    }
+
+    #[test]
+    fn rejects_repetitions_longer_than_the_address() {
+        for mask in ["?{41}", "dead?{37}", "?{18446744073709551615}", "?{39}??"] {
+            let err = MaskMatcher::new(mask, &eth()).unwrap_err();
+            assert!(err.to_string().contains("more than 40 positions"), "{mask}");
+        }
+        assert!(MaskMatcher::new("?{0}?{40}", &eth()).is_ok());
+    }
+
+    #[test]
+    fn probability_counts_only_constrained_positions() {
+        let m = MaskMatcher::new("dead?{32}[0-7]???", &eth()).unwrap();
+        let p = m.probability(&eth()).unwrap();
+        assert!((p - 16f64.powi(-4) / 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+/// Matches addresses whose suffix mirrors the prefix, e.g. `abc…cba`.
+///
+/// `len` characters are compared from each end; comparison ignores case.
+#[derive(Debug, Clone)]
+pub struct MirrorMatcher {
+    len: usize,
+}
+
+impl MirrorMatcher {
+    pub fn new(len: usize) -> Self {
+        Self { len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Matcher for MirrorMatcher {
+    fn matches(&self, address: &str) -> bool {
+        let bytes = address.as_bytes();
+        let n = bytes.len();
+        n >= self.len && (0..self.len).all(|i| bytes[i].eq_ignore_ascii_case(&bytes[n - 1 - i]))
+    }
+
+    fn description(&self) -> String {
+        format!("mirror:{}", self.len)
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        let n = space.body_len();
+        if self.len > n {
+            return Some(0.0);
+        }
+
+        // Pairs past the middle repeat earlier ones.
+        let p = (0..self.len.min(n / 2))
+            .map(|i| space.same_char_probability(&[i, n - 1 - i]))
+            .product();
+        Some(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_mirrored_ends() {
+        let m = MirrorMatcher::new(3);
+        assert!(m.matches("abc0000cba"));
+        assert!(m.matches("ABc0000cbA"));
+        assert!(!m.matches("abc0000abc"));
+        assert_eq!(m.description(), "mirror:3");
+    }
+
+    #[test]
+    fn probability_is_one_match_per_pair() {
+        let m = MirrorMatcher::new(3);
+        let p = m.probability(&AddressSpace::ethereum(true)).unwrap();
+        assert!((p - 16f64.powi(-3)).abs() < 1e-15);
+    }
+}
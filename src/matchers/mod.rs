@@ -1,13 +1,46 @@
 mod builder;
+mod case;
 mod composite;
 mod contains;
+mod leet;
+mod mask;
+mod mirror;
+mod palindrome;
 mod prefix;
 mod regex_matcher;
+mod repeat;
+mod run;
+mod sequence;
+mod stretch;
 mod suffix;
 
 pub use builder::{build_matcher, MatcherConfig};
+pub use case::CaseMode;
 pub use composite::{CompositeLogic, CompositeMatcher};
 pub use contains::ContainsMatcher;
+pub use leet::{expand_leet, MAX_LEET_VARIANTS};
+pub use mask::MaskMatcher;
+pub use mirror::MirrorMatcher;
+pub use palindrome::PalindromeMatcher;
 pub use prefix::PrefixMatcher;
 pub use regex_matcher::RegexMatcher;
+pub use repeat::{Anchor, RepeatMatcher};
+pub use run::RunMatcher;
+pub use sequence::SequenceMatcher;
+pub use stretch::{CharClass, StretchMatcher};
 pub use suffix::SuffixMatcher;
+
+use thiserror::Error;
+
+/// Errors raised while building matchers from user-supplied patterns.
+#[derive(Error, Debug)]
+pub enum MatcherError {
+    #[error("invalid regex: {0}")]
+    Regex(#[from] regex::Error),
+
+    #[error("invalid mask: {0}")]
+    InvalidMask(String),
+
+    #[error("invalid leetspeak pattern: {0}")]
+    InvalidLeet(String),
+}
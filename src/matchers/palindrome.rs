@@ -0,0 +1,81 @@
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+/// Matches addresses containing a palindrome of at least `min_len`
+/// characters anywhere, e.g. `…12a3a21…`.
+///
+/// Comparison ignores case.
+#[derive(Debug, Clone)]
+pub struct PalindromeMatcher {
+    min_len: usize,
+}
+
+impl PalindromeMatcher {
+    pub fn new(min_len: usize) -> Self {
+        Self { min_len }
+    }
+
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+}
+
+fn is_palindrome(window: &[u8]) -> bool {
+    let n = window.len();
+    (0..n / 2).all(|i| window[i].eq_ignore_ascii_case(&window[n - 1 - i]))
+}
+
+impl Matcher for PalindromeMatcher {
+    fn matches(&self, address: &str) -> bool {
+        let bytes = address.as_bytes();
+        if self.min_len == 0 {
+            return true;
+        }
+
+        // Trimming one character from each end keeps a palindrome, so any
+        // palindrome of length >= min_len contains one of length min_len or
+        // min_len + 1.
+        [self.min_len, self.min_len + 1]
+            .iter()
+            .any(|&len| bytes.len() >= len && bytes.windows(len).any(is_palindrome))
+    }
+
+    fn description(&self) -> String {
+        format!("palindrome:{}", self.min_len)
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        let at_len = |len: usize| {
+            space.anywhere_probability(len, |start| {
+                (0..len / 2)
+                    .map(|i| space.same_char_probability(&[start + i, start + len - 1 - i]))
+                    .product()
+            })
+        };
+
+        let miss = (1.0 - at_len(self.min_len)) * (1.0 - at_len(self.min_len + 1));
+        Some(1.0 - miss)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_odd_and_even_palindromes() {
+        let m = PalindromeMatcher::new(5);
+        assert!(m.matches("0012a2100"));
+        assert!(m.matches("00abccba99"));
+        assert!(m.matches("xxAbCbAxx"));
+        assert!(!m.matches("0123456789"));
+        assert_eq!(m.description(), "palindrome:5");
+    }
+
+    #[test]
+    fn probability_is_between_zero_and_one() {
+        let p = PalindromeMatcher::new(7)
+            .probability(&AddressSpace::ethereum(true))
+            .unwrap();
+        assert!(p > 0.0 && p < 0.01);
+    }
+}
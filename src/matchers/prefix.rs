@@ -1,20 +1,27 @@
-use crate::core::matcher::Matcher;
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+use super::CaseMode;
 
 /// Matches addresses that start with a given prefix
 ///
-/// The address passed to `matches` is expected to be *normalized*:
-/// - For hex address: lowercased and without `0x` prefix
-/// - For other encodings: whatever with how the pattern was constructed.
+/// The address passed to `matches` is the body from
+/// `AddressConfig::matching_body`: no `0x` or network prefix, in its display
+/// case (EIP-55 for Ethereum). The `CaseMode` decides how case is compared;
+/// insensitive patterns are stored lowercased and compared ignoring case.
 #[derive(Debug, Clone)]
 pub struct PrefixMatcher {
     pattern: String,
+    case: CaseMode,
 }
 
 impl PrefixMatcher {
     pub fn new(pattern: impl Into<String>) -> Self {
-        let mut pattern = pattern.into();
-        pattern.make_ascii_lowercase();
-        Self { pattern }
+        Self::with_case(pattern, CaseMode::Insensitive)
+    }
+
+    pub fn with_case(pattern: impl Into<String>, case: CaseMode) -> Self {
+        let pattern = case.normalize_pattern(pattern.into());
+        Self { pattern, case }
     }
 
     pub fn pattern(&self) -> &str {
@@ -24,11 +31,15 @@ impl PrefixMatcher {
 
 impl Matcher for PrefixMatcher {
     fn matches(&self, address: &str) -> bool {
-        address.starts_with(&self.pattern)
+        self.case.starts_with(address, &self.pattern)
     }
 
     fn description(&self) -> String {
-        format!("prefix:{}", self.pattern)
+        format!("prefix:{}{}", self.pattern, self.case.label())
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        Some(space.prefix_probability(&self.pattern, self.case.resolve(space).is_case_sensitive()))
     }
 }
 
@@ -50,4 +61,13 @@ mod tests {
         let m = PrefixMatcher::new("AbC");
         assert_eq!(m.pattern(), "abc");
     }
+
+    #[test]
+    fn exact_case_keeps_pattern_case() {
+        let m = PrefixMatcher::with_case("DeAd", CaseMode::Exact);
+        assert_eq!(m.pattern(), "DeAd");
+        assert!(m.matches("DeAd00"));
+        assert!(!m.matches("dead00"));
+        assert!(m.description().ends_with("(exact)"));
+    }
 }
@@ -0,0 +1,92 @@
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+/// Which end of the address a pattern is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Start,
+    End,
+}
+
+/// Matches addresses whose first or last `count` characters are identical,
+/// whatever the character is (e.g. `000000…` or `…aaaaaa`).
+///
+/// Comparison ignores case.
+#[derive(Debug, Clone)]
+pub struct RepeatMatcher {
+    anchor: Anchor,
+    count: usize,
+}
+
+impl RepeatMatcher {
+    pub fn new(anchor: Anchor, count: usize) -> Self {
+        Self { anchor, count }
+    }
+
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Matcher for RepeatMatcher {
+    fn matches(&self, address: &str) -> bool {
+        let bytes = address.as_bytes();
+        if bytes.len() < self.count {
+            return false;
+        }
+
+        let window = match self.anchor {
+            Anchor::Start => &bytes[..self.count],
+            Anchor::End => &bytes[bytes.len() - self.count..],
+        };
+        window.iter().all(|b| b.eq_ignore_ascii_case(&window[0]))
+    }
+
+    fn description(&self) -> String {
+        match self.anchor {
+            Anchor::Start => format!("repeat-prefix:{}", self.count),
+            Anchor::End => format!("repeat-suffix:{}", self.count),
+        }
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        let len = space.body_len();
+        if self.count > len {
+            return Some(0.0);
+        }
+
+        let positions: Vec<usize> = match self.anchor {
+            Anchor::Start => (0..self.count).collect(),
+            Anchor::End => (len - self.count..len).collect(),
+        };
+        Some(space.same_char_probability(&positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_any_repeated_character() {
+        let m = RepeatMatcher::new(Anchor::Start, 4);
+        assert!(m.matches("0000ab"));
+        assert!(m.matches("aAaA12"));
+        assert!(!m.matches("000a00"));
+
+        let m = RepeatMatcher::new(Anchor::End, 3);
+        assert!(m.matches("12fff"));
+        assert!(!m.matches("fff12"));
+        assert_eq!(m.description(), "repeat-suffix:3");
+    }
+
+    #[test]
+    fn probability_sums_over_characters() {
+        let m = RepeatMatcher::new(Anchor::Start, 4);
+        let p = m.probability(&AddressSpace::ethereum(true)).unwrap();
+        assert!((p - 16f64.powi(-3)).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+/// Matches addresses containing a run of at least `min_len` identical
+/// characters anywhere, e.g. `…7777777…`.
+///
+/// Comparison ignores case.
+#[derive(Debug, Clone)]
+pub struct RunMatcher {
+    min_len: usize,
+}
+
+impl RunMatcher {
+    pub fn new(min_len: usize) -> Self {
+        Self { min_len }
+    }
+
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+}
+
+/// Length of the longest run of identical characters.
+pub(crate) fn longest_run(bytes: &[u8]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        if i > 0 && b.eq_ignore_ascii_case(&bytes[i - 1]) {
+            current += 1;
+        } else {
+            current = 1;
+        }
+        longest = longest.max(current);
+    }
+    longest
+}
+
+impl Matcher for RunMatcher {
+    fn matches(&self, address: &str) -> bool {
+        longest_run(address.as_bytes()) >= self.min_len
+    }
+
+    fn description(&self) -> String {
+        format!("run:{}", self.min_len)
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        Some(space.anywhere_probability(self.min_len, |start| {
+            let positions: Vec<usize> = (start..start + self.min_len).collect();
+            space.same_char_probability(&positions)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_runs_anywhere() {
+        let m = RunMatcher::new(4);
+        assert!(m.matches("12eeee34"));
+        assert!(m.matches("12EeEe34"));
+        assert!(!m.matches("12eee3e4"));
+        assert_eq!(longest_run(b"aabbbbc"), 4);
+        assert_eq!(m.description(), "run:4");
+    }
+}
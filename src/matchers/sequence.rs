@@ -0,0 +1,97 @@
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+/// Ordering used for ascending sequences: digits, then letters.
+const ORDER: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+fn rank(c: u8) -> Option<usize> {
+    ORDER.bytes().position(|o| o == c.to_ascii_lowercase())
+}
+
+/// Matches addresses containing an ascending sequence of at least `min_len`
+/// characters anywhere, e.g. `0123456` or `abcdef`.
+///
+/// Sequences follow `0-9` then `a-z` (so `789abc` counts) and ignore case.
+#[derive(Debug, Clone)]
+pub struct SequenceMatcher {
+    min_len: usize,
+}
+
+impl SequenceMatcher {
+    pub fn new(min_len: usize) -> Self {
+        Self { min_len }
+    }
+
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+}
+
+impl Matcher for SequenceMatcher {
+    fn matches(&self, address: &str) -> bool {
+        if self.min_len <= 1 {
+            return self.min_len == 0 || !address.is_empty();
+        }
+
+        let mut current = 1;
+        let mut prev = None;
+        for &b in address.as_bytes() {
+            let r = rank(b);
+            match (prev, r) {
+                (Some(p), Some(r)) if r == p + 1 => {
+                    current += 1;
+                    if current >= self.min_len {
+                        return true;
+                    }
+                }
+                _ => current = 1,
+            }
+            prev = r;
+        }
+        false
+    }
+
+    fn description(&self) -> String {
+        format!("ascending:{}", self.min_len)
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        let order = ORDER.as_bytes();
+        if self.min_len > order.len() {
+            return Some(0.0);
+        }
+
+        Some(space.anywhere_probability(self.min_len, |start| {
+            (0..=order.len() - self.min_len)
+                .map(|first| {
+                    (0..self.min_len)
+                        .map(|i| space.char_probability(start + i, order[first + i] as char, false))
+                        .product::<f64>()
+                })
+                .sum()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_ascending_sequences() {
+        let m = SequenceMatcher::new(5);
+        assert!(m.matches("ff01234ff"));
+        assert!(m.matches("ff789ABff"));
+        assert!(!m.matches("ff0123ff4"));
+        assert_eq!(m.description(), "ascending:5");
+    }
+
+    #[test]
+    fn hex_probability_counts_all_start_values() {
+        // 12 possible 5-long sequences in hex at each of 36 positions.
+        let p = SequenceMatcher::new(5)
+            .probability(&AddressSpace::ethereum(true))
+            .unwrap();
+        let per_start = 12.0 * 16f64.powi(-5);
+        assert!((p - (1.0 - (1.0 - per_start).powi(36))).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+/// Character class for `StretchMatcher`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Digit,
+    Letter,
+}
+
+impl CharClass {
+    fn contains(self, c: u8) -> bool {
+        match self {
+            CharClass::Digit => c.is_ascii_digit(),
+            CharClass::Letter => c.is_ascii_alphabetic(),
+        }
+    }
+}
+
+/// Matches addresses containing at least `min_len` consecutive digits or
+/// letters. With `min_len` equal to the body length the whole address must
+/// be digits (or letters) only.
+#[derive(Debug, Clone)]
+pub struct StretchMatcher {
+    class: CharClass,
+    min_len: usize,
+}
+
+impl StretchMatcher {
+    pub fn new(class: CharClass, min_len: usize) -> Self {
+        Self { class, min_len }
+    }
+
+    pub fn class(&self) -> CharClass {
+        self.class
+    }
+
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+}
+
+impl Matcher for StretchMatcher {
+    fn matches(&self, address: &str) -> bool {
+        let mut current = 0;
+        for &b in address.as_bytes() {
+            if self.class.contains(b) {
+                current += 1;
+                if current >= self.min_len {
+                    return true;
+                }
+            } else {
+                current = 0;
+            }
+        }
+        self.min_len == 0
+    }
+
+    fn description(&self) -> String {
+        match self.class {
+            CharClass::Digit => format!("digits:{}", self.min_len),
+            CharClass::Letter => format!("letters:{}", self.min_len),
+        }
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        let in_class = |pos: usize| {
+            let allowed = space.allowed_at(pos);
+            let hits = allowed.bytes().filter(|&b| self.class.contains(b)).count();
+            hits as f64 / allowed.len() as f64
+        };
+
+        Some(space.anywhere_probability(self.min_len, |start| {
+            (start..start + self.min_len).map(in_class).product()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_digit_and_letter_stretches() {
+        let digits = StretchMatcher::new(CharClass::Digit, 5);
+        assert!(digits.matches("ab12345cd"));
+        assert!(!digits.matches("ab1234c5d"));
+
+        let letters = StretchMatcher::new(CharClass::Letter, 4);
+        assert!(letters.matches("12aBcD34"));
+        assert!(!letters.matches("12abc3d4"));
+        assert_eq!(letters.description(), "letters:4");
+    }
+
+    #[test]
+    fn all_digit_body_probability() {
+        let m = StretchMatcher::new(CharClass::Digit, 40);
+        let p = m.probability(&AddressSpace::ethereum(true)).unwrap();
+        assert!((p - (10.0f64 / 16.0).powi(40)).abs() < 1e-15);
+    }
+}
@@ -1,18 +1,25 @@
-use crate::core::matcher::Matcher;
+use crate::core::{difficulty::AddressSpace, matcher::Matcher};
+
+use super::CaseMode;
 
 /// Matches addresses that end with a given suffix.
 ///
-/// Same normalization assumption as `PrefixMatcher`.
+/// Receives the same display-case address body as `PrefixMatcher` and
+/// compares case according to its `CaseMode`.
 #[derive(Debug, Clone)]
 pub struct SuffixMatcher {
     pattern: String,
+    case: CaseMode,
 }
 
 impl SuffixMatcher {
     pub fn new(pattern: impl Into<String>) -> Self {
-        let mut pattern = pattern.into();
-        pattern.make_ascii_lowercase();
-        Self { pattern }
+        Self::with_case(pattern, CaseMode::Insensitive)
+    }
+
+    pub fn with_case(pattern: impl Into<String>, case: CaseMode) -> Self {
+        let pattern = case.normalize_pattern(pattern.into());
+        Self { pattern, case }
     }
     pub fn pattern(&self) -> &str {
         &self.pattern
@@ -21,11 +28,15 @@ impl SuffixMatcher {
 
 impl Matcher for SuffixMatcher {
     fn matches(&self, address: &str) -> bool {
-        address.ends_with(&self.pattern)
+        self.case.ends_with(address, &self.pattern)
     }
 
     fn description(&self) -> String {
-        format!("suffix:{}", self.pattern)
+        format!("suffix:{}{}", self.pattern, self.case.label())
+    }
+
+    fn probability(&self, space: &AddressSpace) -> Option<f64> {
+        Some(space.suffix_probability(&self.pattern, self.case.resolve(space).is_case_sensitive()))
     }
 }
 
@@ -47,4 +58,13 @@ mod tests {
         let m = SuffixMatcher::new("XyZ");
         assert_eq!(m.pattern(), "xyz");
     }
+
+    #[test]
+    fn exact_case_keeps_pattern_case() {
+        let m = SuffixMatcher::with_case("BeEf", CaseMode::Exact);
+        assert_eq!(m.pattern(), "BeEf");
+        assert!(m.matches("00BeEf"));
+        assert!(!m.matches("00beef"));
+        assert!(m.description().ends_with("(exact)"));
+    }
 }
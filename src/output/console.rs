@@ -2,13 +2,14 @@ use std::io::{self, Write};
 
 use indicatif::ProgressBar;
 
-use crate::core::types::{FoundAddress, SecretInfo};
+use crate::core::{secret::Secret, types::FoundAddress};
 
-use super::ResultSink;
+use super::{qr, ResultSink};
 /// Simple console sink that prints found address to stdout
 pub struct ConsoleResultSink {
     writer: Option<Box<dyn Write + Send>>,
     progress_bar: Option<ProgressBar>,
+    qr: bool,
 }
 
 impl ConsoleResultSink {
@@ -16,6 +17,7 @@ impl ConsoleResultSink {
         Self {
             writer: Some(Box::new(io::stdout())),
             progress_bar: None,
+            qr: false,
         }
     }
 
@@ -23,9 +25,16 @@ impl ConsoleResultSink {
         Self {
             writer: None,
             progress_bar: Some(pb),
+            qr: false,
         }
     }
 
+    /// Also draw a QR code of each address, for scanning it off the screen.
+    pub fn with_qr(mut self, qr: bool) -> Self {
+        self.qr = qr;
+        self
+    }
+
     #[cfg(test)]
     pub(crate) fn with_writer<W>(writer: W) -> Self
     where
@@ -34,20 +43,35 @@ impl ConsoleResultSink {
         Self {
             writer: Some(Box::new(writer)),
             progress_bar: None,
+            qr: false,
         }
     }
 }
 
+impl Default for ConsoleResultSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ResultSink for ConsoleResultSink {
     fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
-        let secret = format_secret(&found.secret);
-        let line = format!("{} | {}", found.address, secret);
+        let secret = found.secret.formatted();
+        let mut line: Secret<String> = match &found.target {
+            Some(name) => format!("[{name}] {} | {}", found.address, secret.expose()),
+            None => format!("{} | {}", found.address, secret.expose()),
+        }
+        .into();
+        if self.qr {
+            line.expose_mut().push('\n');
+            line.expose_mut().push_str(&qr::terminal(&found.address)?);
+        }
 
         if let Some(pb) = &self.progress_bar {
-            pb.println(line);
+            pb.println(line.expose());
             Ok(())
         } else if let Some(writer) = &mut self.writer {
-            writeln!(writer, "{line}")
+            writeln!(writer, "{}", line.expose())
         } else {
             // Nothing configured; do nothing.
             Ok(())
@@ -55,31 +79,43 @@ impl ResultSink for ConsoleResultSink {
     }
 }
 
-fn format_secret(secret: &SecretInfo) -> String {
-    match secret {
-        SecretInfo::PrivateKey(s) => s.clone(),
-        SecretInfo::Mnemonic {
-            phrase,
-            derivation_path,
-        } => format!("mnemonic:{}|path:{}", phrase, derivation_path),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::types::SecretInfo;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn console_sink_writes_without_error() {
         let buffer: Vec<u8> = Vec::new();
         let mut sink = ConsoleResultSink::with_writer(buffer);
 
-        let found = FoundAddress {
-            address: "0xaddr".into(),
-            secret: SecretInfo::PrivateKey("deadbeef".into()),
-        };
+        let found = FoundAddress::new("0xaddr", SecretInfo::PrivateKey("deadbeef".into()));
 
         sink.handle(&found).unwrap();
     }
+
+    #[test]
+    fn console_sink_draws_address_qr_codes() {
+        #[derive(Clone, Default)]
+        struct Captured(Arc<Mutex<Vec<u8>>>);
+        impl Write for Captured {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Captured::default();
+        let mut sink = ConsoleResultSink::with_writer(buffer.clone()).with_qr(true);
+
+        let found = FoundAddress::new("0xaddr", SecretInfo::PrivateKey("deadbeef".into()));
+        sink.handle(&found).unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.starts_with("0xaddr | deadbeef\n"));
+        assert!(output.lines().count() > 10);
+    }
 }
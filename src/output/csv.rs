@@ -1,60 +1,350 @@
 use std::{
-    fs::OpenOptions,
-    io,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
-use csv::Writer;
+use chrono::{DateTime, SecondsFormat};
+use csv::{ReaderBuilder, Writer};
 
-use crate::core::types::{FoundAddress, SecretInfo};
+use crate::core::{
+    secret::Secret,
+    types::{FoundAddress, Result, SecretInfo, VanityError},
+};
+
+use super::{
+    permissions::{owner_only, restrict_to_owner},
+    ResultSink,
+};
+
+/// First line of files using the versioned schema, followed by the version.
+const VERSION_MARKER: &str = "# vanity-miner results v";
+
+/// The versioned schema written for `--csv-columns`.
+pub const CSV_SCHEMA_VERSION: u32 = 2;
+
+/// A column of the result CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// Name of the job target the record was found for.
+    Target,
+    Chain,
+    Network,
+    Address,
+    PublicKey,
+    /// Private key, `mnemonic:<phrase>|path:<path>` or `keystore:<path>`.
+    Secret,
+    PrivateKey,
+    Mnemonic,
+    DerivationPath,
+    /// Path of the keystore file holding the key.
+    Keystore,
+    Pattern,
+    Attempt,
+    Worker,
+    FoundAt,
+}
+
+impl CsvColumn {
+    pub const ALL: [CsvColumn; 14] = [
+        CsvColumn::Target,
+        CsvColumn::Chain,
+        CsvColumn::Network,
+        CsvColumn::Address,
+        CsvColumn::PublicKey,
+        CsvColumn::Secret,
+        CsvColumn::PrivateKey,
+        CsvColumn::Mnemonic,
+        CsvColumn::DerivationPath,
+        CsvColumn::Keystore,
+        CsvColumn::Pattern,
+        CsvColumn::Attempt,
+        CsvColumn::Worker,
+        CsvColumn::FoundAt,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Target => "target",
+            CsvColumn::Chain => "chain",
+            CsvColumn::Network => "network",
+            CsvColumn::Address => "address",
+            CsvColumn::PublicKey => "public_key",
+            CsvColumn::Secret => "secret",
+            CsvColumn::PrivateKey => "private_key",
+            CsvColumn::Mnemonic => "mnemonic",
+            CsvColumn::DerivationPath => "derivation_path",
+            CsvColumn::Keystore => "keystore",
+            CsvColumn::Pattern => "pattern",
+            CsvColumn::Attempt => "attempt",
+            CsvColumn::Worker => "worker",
+            CsvColumn::FoundAt => "found_at",
+        }
+    }
+
+    fn value(self, found: &FoundAddress) -> Secret<String> {
+        let secret = &found.secret;
+        let value = match self {
+            CsvColumn::Target => found.target.clone().unwrap_or_default(),
+            CsvColumn::Chain => found.chain.clone(),
+            CsvColumn::Network => found.network.map(|n| n.to_string()).unwrap_or_default(),
+            CsvColumn::Address => found.address.clone(),
+            CsvColumn::PublicKey => found.public_key.clone(),
+            CsvColumn::Secret => return secret.formatted(),
+            CsvColumn::PrivateKey => match secret {
+                SecretInfo::PrivateKey(key) => return key.clone(),
+                _ => String::new(),
+            },
+            CsvColumn::Mnemonic => match secret {
+                SecretInfo::Mnemonic { phrase, .. } => return phrase.clone(),
+                _ => String::new(),
+            },
+            CsvColumn::DerivationPath => match secret {
+                SecretInfo::Mnemonic {
+                    derivation_path, ..
+                } => derivation_path.clone(),
+                _ => String::new(),
+            },
+            CsvColumn::Keystore => match secret {
+                SecretInfo::Keystore(path) => path.clone(),
+                _ => String::new(),
+            },
+            CsvColumn::Pattern => found.pattern.clone(),
+            CsvColumn::Attempt => found.attempt.to_string(),
+            CsvColumn::Worker => found.worker.to_string(),
+            CsvColumn::FoundAt => found.found_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        };
+        value.into()
+    }
+}
+
+impl FromStr for CsvColumn {
+    type Err = VanityError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        CsvColumn::ALL
+            .into_iter()
+            .find(|c| c.name() == s.trim())
+            .ok_or_else(|| {
+                let names: Vec<&str> = CsvColumn::ALL.iter().map(|c| c.name()).collect();
+                VanityError::OutputFormat(format!(
+                    "unknown CSV column \"{s}\", expected one of {}",
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+impl fmt::Display for CsvColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The secret every result of a run carries, which decides the columns that
+/// can store it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretKind {
+    /// Private key, range and key recovery runs.
+    PrivateKey,
+    /// Mnemonic and mnemonic recovery runs.
+    Mnemonic,
+    /// Runs with `--keystore`, whose results point at keystore files.
+    Keystore,
+}
+
+/// Which columns a result CSV has, and whether it starts with a version line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvSchema {
+    /// `None` for the original unversioned layout.
+    pub version: Option<u32>,
+    pub columns: Vec<CsvColumn>,
+}
+
+impl CsvSchema {
+    /// The current versioned schema with the given columns.
+    pub fn new(columns: Vec<CsvColumn>) -> Self {
+        Self {
+            version: Some(CSV_SCHEMA_VERSION),
+            columns,
+        }
+    }
+
+    /// The original `[target,]address,secret` (or `,keystore`) layout.
+    pub fn legacy(target_column: bool, keystore: bool) -> Self {
+        let mut columns = Vec::with_capacity(3);
+        if target_column {
+            columns.push(CsvColumn::Target);
+        }
+        columns.push(CsvColumn::Address);
+        columns.push(if keystore {
+            CsvColumn::Keystore
+        } else {
+            CsvColumn::Secret
+        });
+        Self {
+            version: None,
+            columns,
+        }
+    }
+
+    /// Check that the columns can store secrets of `kind`, so no result is
+    /// written without what controls its address.
+    pub fn check_holds(&self, kind: SecretKind) -> Result<()> {
+        let needed: &[CsvColumn] = match kind {
+            SecretKind::PrivateKey => &[CsvColumn::PrivateKey],
+            SecretKind::Mnemonic => &[CsvColumn::Mnemonic, CsvColumn::DerivationPath],
+            SecretKind::Keystore => &[CsvColumn::Keystore],
+        };
+        if self.columns.contains(&CsvColumn::Secret)
+            || needed.iter().all(|c| self.columns.contains(c))
+        {
+            return Ok(());
+        }
+
+        let needed: Vec<&str> = needed.iter().map(|c| c.name()).collect();
+        Err(VanityError::OutputFormat(format!(
+            "CSV columns {} cannot store the secret of this run; add secret or {}",
+            self.header(),
+            needed.join(" and ")
+        )))
+    }
+
+    fn version_line(&self) -> Option<String> {
+        self.version.map(|v| format!("{VERSION_MARKER}{v}"))
+    }
 
-use super::ResultSink;
+    fn header(&self) -> String {
+        let names: Vec<&str> = self.columns.iter().map(|c| c.name()).collect();
+        names.join(",")
+    }
 
-/// CSV sink that appends found addresses to a CSV file
-pub struct CsvResultSink {
-    path: PathBuf,
-    writer: Writer<std::fs::File>,
+    /// Parse the schema from the first lines of a result file.
+    fn detect(mut lines: impl Iterator<Item = io::Result<String>>) -> io::Result<Option<Self>> {
+        let Some(first) = lines.next().transpose()? else {
+            return Ok(None);
+        };
+        let (version, header) = match first.strip_prefix(VERSION_MARKER) {
+            Some(version) => {
+                let version = version
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid_data(format!("bad version line \"{first}\"")))?;
+                let header = lines.next().transpose()?.unwrap_or_default();
+                (Some(version), header)
+            }
+            None => (None, first),
+        };
+        let columns = header
+            .trim_end_matches('\r')
+            .split(',')
+            .map(|name| name.parse().map_err(invalid_data))
+            .collect::<io::Result<_>>()?;
+        Ok(Some(Self { version, columns }))
+    }
+}
+
+impl fmt::Display for CsvSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version {
+            Some(v) => write!(f, "v{v} ({})", self.header()),
+            None => write!(f, "unversioned ({})", self.header()),
+        }
+    }
+}
+
+/// CSV sink that appends found addresses to a CSV file, or to any writer.
+pub struct CsvResultSink<W: Write = File> {
+    path: Option<PathBuf>,
+    writer: Writer<W>,
+    columns: Vec<CsvColumn>,
 }
 
 impl CsvResultSink {
     pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::with_schema(path, CsvSchema::legacy(false, false))
+    }
+
+    /// Like [`new`](Self::new), with a leading `target` column naming the
+    /// job target each record was found for.
+    pub fn with_target_column<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::with_schema(path, CsvSchema::legacy(true, false))
+    }
+
+    /// An index of keystore files: the address and keystore path, no secret.
+    pub fn keystore_index<P: AsRef<Path>>(path: P, target_column: bool) -> io::Result<Self> {
+        Self::with_schema(path, CsvSchema::legacy(target_column, true))
+    }
+
+    /// Append to `path` using `schema`. An existing file must have been
+    /// written with the same schema, so records are never mixed.
+    pub fn with_schema<P: AsRef<Path>>(path: P, schema: CsvSchema) -> io::Result<Self> {
         let path_buf = path.as_ref().to_path_buf();
 
         let file_exists_and_non_empty =
             path_buf.exists() && path_buf.metadata().map(|m| m.len() > 0).unwrap_or(false);
+        if file_exists_and_non_empty {
+            let lines = BufReader::new(File::open(&path_buf)?).lines();
+            let existing = CsvSchema::detect(lines).map_err(|e| {
+                invalid_data(format!(
+                    "{} is not a result CSV ({e}); choose another --output",
+                    path_buf.display()
+                ))
+            })?;
+            if existing.as_ref() != Some(&schema) {
+                let existing = existing.map_or_else(String::new, |s| s.to_string());
+                return Err(invalid_data(format!(
+                    "{} has schema {existing}, but this run writes {schema}; \
+                     choose another --output or matching --csv-columns",
+                    path_buf.display()
+                )));
+            }
+        }
 
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .append(true)
-            .open(&path_buf)?;
+        // The file holds private keys: never let others read it.
+        let file = owner_only(OpenOptions::new().create(true).append(true)).open(&path_buf)?;
+        restrict_to_owner(&path_buf)?;
 
-        let mut writer = Writer::from_writer(file);
+        let mut sink = CsvResultSink::from_writer(file, schema, !file_exists_and_non_empty)?;
+        sink.path = Some(path_buf);
+        Ok(sink)
+    }
+}
 
-        if !file_exists_and_non_empty {
+impl<W: Write> CsvResultSink<W> {
+    /// Write records to `writer`, starting with the header if `header` is set.
+    pub fn from_writer(mut writer: W, schema: CsvSchema, header: bool) -> io::Result<Self> {
+        if let Some(line) = schema.version_line().filter(|_| header) {
+            // Written raw: the csv writer would quote the line.
+            writeln!(writer, "{line}")?;
+        }
+        let mut writer = Writer::from_writer(writer);
+        if header {
             writer
-                .write_record(&["address", "secret"])
+                .write_record(schema.columns.iter().map(|c| c.name()))
                 .map_err(csv_to_io_error)?;
             writer.flush()?;
         }
-
         Ok(Self {
-            path: path_buf,
+            path: None,
             writer,
+            columns: schema.columns,
         })
     }
 
-    pub fn path(&self) -> &Path {
-        &self.path
+    /// The file being written, if the sink writes to a file.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
     }
 }
 
-impl ResultSink for CsvResultSink {
+impl<W: Write + Send> ResultSink for CsvResultSink<W> {
     fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
-        let secret = format_secret(&found.secret);
-
+        let row: Vec<Secret<String>> = self.columns.iter().map(|c| c.value(found)).collect();
         self.writer
-            .write_record(&[found.address.as_str(), secret.as_str()])
+            .write_record(row.iter().map(|value| value.expose()))
             .map_err(csv_to_io_error)?;
         self.writer.flush()?;
 
@@ -62,18 +352,119 @@ impl ResultSink for CsvResultSink {
     }
 }
 
-fn format_secret(secret: &SecretInfo) -> String {
-    match secret {
-        SecretInfo::PrivateKey(s) => s.clone(),
+/// Read back the results of any result CSV, including the original
+/// two-column `address,secret` files.
+pub fn read_csv_results<R: Read>(reader: R) -> Result<Vec<FoundAddress>> {
+    let mut reader = BufReader::new(reader);
+    let mut head = Vec::new();
+    for _ in 0..2 {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        head.push(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+    let Some(schema) = CsvSchema::detect(head.iter().cloned().map(Ok))? else {
+        return Ok(Vec::new());
+    };
+
+    // Replay the lines already consumed, minus the version line the csv
+    // reader would choke on.
+    let skip = usize::from(schema.version.is_some());
+    let replay: Vec<u8> = head
+        .into_iter()
+        .skip(skip)
+        .flat_map(|line| format!("{line}\n").into_bytes())
+        .collect();
+    let mut records = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(replay.as_slice().chain(reader));
+
+    let mut results = Vec::new();
+    for (index, record) in records.records().enumerate() {
+        let record = record.map_err(|e| VanityError::OutputFormat(e.to_string()))?;
+        let line = index + 2 + skip;
+        let field = |column: CsvColumn| {
+            schema
+                .columns
+                .iter()
+                .position(|&c| c == column)
+                .and_then(|i| record.get(i))
+                .filter(|v| !v.is_empty())
+        };
+        let invalid = |what: &str, value: &str| {
+            VanityError::OutputFormat(format!("line {line}: invalid {what} \"{value}\""))
+        };
+
+        let secret = if let Some(path) = field(CsvColumn::Keystore) {
+            SecretInfo::Keystore(path.to_string())
+        } else if let Some(phrase) = field(CsvColumn::Mnemonic) {
+            SecretInfo::Mnemonic {
+                phrase: phrase.into(),
+                derivation_path: field(CsvColumn::DerivationPath)
+                    .unwrap_or_default()
+                    .to_string(),
+            }
+        } else if let Some(key) = field(CsvColumn::PrivateKey) {
+            SecretInfo::PrivateKey(key.into())
+        } else if let Some(secret) = field(CsvColumn::Secret) {
+            parse_secret(secret)
+        } else {
+            return Err(VanityError::OutputFormat(format!(
+                "line {line}: no secret column"
+            )));
+        };
+        let Some(address) = field(CsvColumn::Address) else {
+            return Err(VanityError::OutputFormat(format!(
+                "line {line}: no address"
+            )));
+        };
+
+        let mut found = FoundAddress::new(address, secret);
+        found.target = field(CsvColumn::Target).map(str::to_string);
+        found.chain = field(CsvColumn::Chain).unwrap_or_default().to_string();
+        found.public_key = field(CsvColumn::PublicKey).unwrap_or_default().to_string();
+        found.pattern = field(CsvColumn::Pattern).unwrap_or_default().to_string();
+        if let Some(network) = field(CsvColumn::Network) {
+            found.network = Some(network.parse().map_err(|_| invalid("network", network))?);
+        }
+        if let Some(attempt) = field(CsvColumn::Attempt) {
+            found.attempt = attempt.parse().map_err(|_| invalid("attempt", attempt))?;
+        }
+        if let Some(worker) = field(CsvColumn::Worker) {
+            found.worker = worker.parse().map_err(|_| invalid("worker", worker))?;
+        }
+        if let Some(found_at) = field(CsvColumn::FoundAt) {
+            found.found_at = DateTime::parse_from_rfc3339(found_at)
+                .map_err(|_| invalid("timestamp", found_at))?
+                .to_utc();
+        }
+        results.push(found);
+    }
+    Ok(results)
+}
+
+/// Inverse of [`SecretInfo::formatted`].
+fn parse_secret(secret: &str) -> SecretInfo {
+    if let Some(rest) = secret.strip_prefix("mnemonic:") {
+        let (phrase, path) = rest.split_once("|path:").unwrap_or((rest, ""));
         SecretInfo::Mnemonic {
-            phrase,
-            derivation_path,
-        } => format!("mnemonic:{}|path:{}", phrase, derivation_path),
+            phrase: phrase.into(),
+            derivation_path: path.to_string(),
+        }
+    } else if let Some(path) = secret.strip_prefix("keystore:") {
+        SecretInfo::Keystore(path.to_string())
+    } else {
+        SecretInfo::PrivateKey(secret.into())
     }
 }
 
+fn invalid_data(e: impl fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
 fn csv_to_io_error(err: csv::Error) -> io::Error {
-    io::Error::new(io::ErrorKind::Other, err.to_string())
+    io::Error::other(err.to_string())
 }
 
 #[cfg(test)]
@@ -90,10 +481,7 @@ mod tests {
 
         let mut sink = CsvResultSink::new(&path).expect("create csv sink");
 
-        let found = FoundAddress {
-            address: "0xaddr".into(),
-            secret: SecretInfo::PrivateKey("deadbeef".into()),
-        };
+        let found = FoundAddress::new("0xaddr", SecretInfo::PrivateKey("deadbeef".into()));
 
         sink.handle(&found).unwrap();
         let content = std::fs::read_to_string(&path).expect("read csv file");
@@ -102,4 +490,160 @@ mod tests {
         assert!(content.contains("0xaddr"));
         assert!(content.contains("deadbeef"));
     }
+
+    #[test]
+    fn csv_sink_writes_target_column() {
+        let mut path = std::env::temp_dir();
+        path.push("vanity_miner_csv_target_test_output.csv");
+
+        let _ = std::fs::remove_file(&path);
+
+        let mut sink = CsvResultSink::with_target_column(&path).expect("create csv sink");
+
+        let found = FoundAddress {
+            target: Some("dead".into()),
+            ..FoundAddress::new("0xaddr", SecretInfo::PrivateKey("deadbeef".into()))
+        };
+
+        sink.handle(&found).unwrap();
+        let content = std::fs::read_to_string(&path).expect("read csv file");
+
+        assert!(content.starts_with("target,address,secret"));
+        assert!(content.contains("dead,0xaddr,deadbeef"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn csv_files_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join("vanity_miner_csv_mode_test_output.csv");
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        let _ = std::fs::remove_file(&path);
+        CsvResultSink::new(&path).expect("create csv sink");
+        assert_eq!(mode(&path), 0o600);
+
+        // Files left world-readable by older versions are tightened on reuse.
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        CsvResultSink::new(&path).expect("reopen csv sink");
+        assert_eq!(mode(&path), 0o600);
+    }
+
+    #[test]
+    fn versioned_schema_round_trips_and_refuses_other_schemas() {
+        let path = std::env::temp_dir().join("vanity_miner_csv_schema_test_output.csv");
+        let _ = std::fs::remove_file(&path);
+        let schema = CsvSchema::new(vec![
+            CsvColumn::Chain,
+            CsvColumn::Network,
+            CsvColumn::Address,
+            CsvColumn::Mnemonic,
+            CsvColumn::DerivationPath,
+            CsvColumn::FoundAt,
+        ]);
+
+        let mut found = FoundAddress::new(
+            "cfx:aaketjh",
+            SecretInfo::Mnemonic {
+                phrase: "abandon, ability".into(),
+                derivation_path: "m/44'/503'/0'/0/0".into(),
+            },
+        );
+        found.chain = "conflux".into();
+        found.network = Some(1029);
+        CsvResultSink::with_schema(&path, schema.clone())
+            .unwrap()
+            .handle(&found)
+            .unwrap();
+        // Appending with the same schema does not repeat the header.
+        CsvResultSink::with_schema(&path, schema)
+            .unwrap()
+            .handle(&found)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(
+            "# vanity-miner results v2\nchain,network,address,mnemonic,derivation_path,found_at\n"
+        ));
+
+        let read = read_csv_results(File::open(&path).unwrap()).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].chain, "conflux");
+        assert_eq!(read[0].network, Some(1029));
+        assert!(matches!(
+            &read[0].secret,
+            SecretInfo::Mnemonic { phrase, derivation_path }
+                if phrase.expose() == "abandon, ability" && derivation_path == "m/44'/503'/0'/0/0"
+        ));
+        assert_eq!(
+            read[0].found_at.timestamp_millis(),
+            found.found_at.timestamp_millis()
+        );
+
+        let err = CsvResultSink::new(&path).err().expect("schema mismatch");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("v2 (chain,network"));
+    }
+
+    #[test]
+    fn schema_must_hold_the_secret_of_the_run() {
+        let schema = |names: &[CsvColumn]| CsvSchema::new(names.to_vec());
+        let (address, secret) = (CsvColumn::Address, CsvColumn::Secret);
+
+        for kind in [
+            SecretKind::PrivateKey,
+            SecretKind::Mnemonic,
+            SecretKind::Keystore,
+        ] {
+            assert!(schema(&[address, secret]).check_holds(kind).is_ok());
+            assert!(schema(&[address, CsvColumn::Pattern])
+                .check_holds(kind)
+                .is_err());
+            assert!(CsvSchema::legacy(false, kind == SecretKind::Keystore)
+                .check_holds(kind)
+                .is_ok());
+        }
+
+        let key = schema(&[address, CsvColumn::PrivateKey]);
+        assert!(key.check_holds(SecretKind::PrivateKey).is_ok());
+        let err = key.check_holds(SecretKind::Mnemonic).unwrap_err();
+        assert!(err.to_string().contains("mnemonic and derivation_path"));
+        assert!(schema(&[address, CsvColumn::Mnemonic])
+            .check_holds(SecretKind::Mnemonic)
+            .is_err());
+        assert!(
+            schema(&[address, CsvColumn::Mnemonic, CsvColumn::DerivationPath])
+                .check_holds(SecretKind::Mnemonic)
+                .is_ok()
+        );
+        assert!(schema(&[address, CsvColumn::Keystore])
+            .check_holds(SecretKind::Keystore)
+            .is_ok());
+    }
+
+    #[test]
+    fn reads_original_two_column_files() {
+        let legacy = "address,secret\n\
+                      0xaaa,deadbeef\n\
+                      0xbbb,mnemonic:abandon about|path:m/44'/60'/0'/0/0\n";
+        let read = read_csv_results(legacy.as_bytes()).unwrap();
+        assert!(matches!(&read[0].secret, SecretInfo::PrivateKey(k) if k.expose() == "deadbeef"));
+        assert!(matches!(
+            &read[1].secret,
+            SecretInfo::Mnemonic { phrase, derivation_path }
+                if phrase.expose() == "abandon about" && derivation_path == "m/44'/60'/0'/0/0"
+        ));
+
+        let index = "target,address,keystore\nx,0xaaa,keys/UTC--x\n";
+        let read = read_csv_results(index.as_bytes()).unwrap();
+        assert_eq!(read[0].target.as_deref(), Some("x"));
+        assert!(matches!(&read[0].secret, SecretInfo::Keystore(p) if p == "keys/UTC--x"));
+
+        // An old file is still appended to by a run with the default layout.
+        let path = std::env::temp_dir().join("vanity_miner_csv_legacy_test_output.csv");
+        std::fs::write(&path, legacy).unwrap();
+        assert!(CsvResultSink::new(&path).is_ok());
+        assert!(CsvResultSink::with_target_column(&path).is_err());
+    }
 }
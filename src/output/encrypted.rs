@@ -0,0 +1,374 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    iter,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::{self, JoinHandle},
+};
+
+use age::{
+    scrypt, secrecy::SecretString, x25519, DecryptError, Decryptor, Encryptor, Identity,
+    IdentityFile, Recipient,
+};
+
+use crate::core::{
+    secret::Secret,
+    types::{FoundAddress, Result, VanityError},
+};
+
+use super::{permissions::owner_only, DynResultSink, ResultSink};
+
+/// Who can read an encrypted result file.
+#[derive(Clone)]
+pub enum EncryptionKey {
+    /// age X25519 public keys (`age1...`).
+    Recipients(Vec<x25519::Recipient>),
+    /// A passphrase, stretched with scrypt.
+    Passphrase(SecretString),
+}
+
+/// An in-memory buffer the plaintext result stream is written to.
+///
+/// The bytes are wiped when the buffer is dropped, and so is every smaller
+/// allocation it outgrows.
+#[derive(Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Secret<Vec<u8>>>>);
+
+impl SharedBuffer {
+    fn lock(&self) -> MutexGuard<'_, Secret<Vec<u8>>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut data = self.lock();
+        let (len, capacity) = (data.expose().len(), data.expose().capacity());
+        if len + buf.len() > capacity {
+            // Grow by hand: a plain `Vec` would free the old copy unwiped.
+            let capacity = (len + buf.len()).max(2 * capacity).max(1024);
+            let mut grown = Secret::new(Vec::with_capacity(capacity));
+            grown.expose_mut().extend_from_slice(data.expose());
+            *data = grown;
+        }
+        data.expose_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Encrypts the whole result stream of an inner sink into one age file.
+///
+/// age files cannot be appended to, so the file is rewritten (through a
+/// temporary file and a rename) by a writer thread whenever results were
+/// added. It is therefore always a complete age file. Results that arrive
+/// while a rewrite runs are picked up together by the next one, so slow
+/// passphrase encryption never holds up the other sinks. Dropping the sink
+/// waits for the last rewrite.
+pub struct EncryptedResultSink {
+    inner: DynResultSink,
+    /// Wakes the writer thread after each result.
+    wake: Option<Sender<()>>,
+    writer: Option<JoinHandle<()>>,
+    /// First failure of the writer thread, reported by the next `handle`.
+    error: Arc<Mutex<Option<io::Error>>>,
+}
+
+impl EncryptedResultSink {
+    /// Encrypt to `path` whatever the sink built by `inner` writes; the file
+    /// must not exist yet.
+    pub fn new<P, F>(path: P, key: EncryptionKey, inner: F) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(SharedBuffer) -> io::Result<DynResultSink>,
+    {
+        Self::with_work_factor(path.as_ref(), key, None, inner)
+    }
+
+    fn with_work_factor<F>(
+        path: &Path,
+        key: EncryptionKey,
+        work_factor: Option<u8>,
+        inner: F,
+    ) -> io::Result<Self>
+    where
+        F: FnOnce(SharedBuffer) -> io::Result<DynResultSink>,
+    {
+        let path = path.to_path_buf();
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} already exists and encrypted files cannot be appended to",
+                    path.display()
+                ),
+            ));
+        }
+
+        let plaintext = SharedBuffer::default();
+        let inner = inner(plaintext.clone())?;
+        let file = EncryptedFile {
+            path,
+            key,
+            work_factor,
+            plaintext,
+        };
+        // Write the header (if any) right away so the file exists from the start.
+        file.rewrite()?;
+
+        let (wake, woken) = mpsc::channel();
+        let error = Arc::new(Mutex::new(None));
+        let failed = Arc::clone(&error);
+        let writer = thread::Builder::new()
+            .name("encrypt".into())
+            .spawn(move || file.run(woken, &failed))?;
+        Ok(Self {
+            inner,
+            wake: Some(wake),
+            writer: Some(writer),
+            error,
+        })
+    }
+}
+
+impl ResultSink for EncryptedResultSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        if let Some(e) = self.error.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            return Err(e);
+        }
+        self.inner.handle(found)?;
+        self.wake
+            .as_ref()
+            .and_then(|wake| wake.send(()).ok())
+            .ok_or_else(|| io::Error::other("the encryption thread stopped"))
+    }
+}
+
+impl Drop for EncryptedResultSink {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer finish its last rewrite.
+        self.wake.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        if let Some(e) = self.error.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            eprintln!("Cannot write encrypted results: {e}");
+        }
+    }
+}
+
+/// The age file an [`EncryptedResultSink`] keeps up to date.
+struct EncryptedFile {
+    path: PathBuf,
+    key: EncryptionKey,
+    /// scrypt work factor for passphrases; `None` uses age's calibration.
+    work_factor: Option<u8>,
+    plaintext: SharedBuffer,
+}
+
+impl EncryptedFile {
+    /// Rewrite the file once per batch of wake-ups until the sink is dropped.
+    fn run(self, woken: Receiver<()>, error: &Mutex<Option<io::Error>>) {
+        while woken.recv().is_ok() {
+            while woken.try_recv().is_ok() {}
+            if let Err(e) = self.rewrite() {
+                error
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get_or_insert(e);
+            }
+        }
+    }
+
+    fn encryptor(&self) -> io::Result<Encryptor> {
+        let encryptor = match &self.key {
+            EncryptionKey::Recipients(recipients) => {
+                Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn Recipient))
+            }
+            EncryptionKey::Passphrase(passphrase) => {
+                let mut recipient = scrypt::Recipient::new(passphrase.clone());
+                if let Some(log_n) = self.work_factor {
+                    recipient.set_work_factor(log_n);
+                }
+                Encryptor::with_recipients(iter::once(&recipient as &dyn Recipient))
+            }
+        };
+        encryptor.map_err(io::Error::other)
+    }
+
+    fn rewrite(&self) -> io::Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        let file =
+            owner_only(OpenOptions::new().write(true).create(true).truncate(true)).open(&tmp)?;
+        // The key is wrapped (and a passphrase stretched) before the buffer
+        // is locked, so the sink keeps taking results meanwhile.
+        let mut writer = self.encryptor()?.wrap_output(file)?;
+        writer.write_all(self.plaintext.lock().expose())?;
+        writer.finish()?.sync_all()?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+/// How to unlock an encrypted result file.
+pub enum DecryptionKey {
+    /// age identity files (`AGE-SECRET-KEY-1...` lines).
+    Identities(Vec<PathBuf>),
+    Passphrase(SecretString),
+}
+
+/// Whether the age file at `path` was encrypted with a passphrase.
+pub fn is_passphrase_encrypted(path: &Path) -> Result<bool> {
+    let decryptor = Decryptor::new(File::open(path)?).map_err(failed)?;
+    Ok(decryptor.is_scrypt())
+}
+
+/// Decrypt the age file at `path`.
+pub fn decrypt_file(path: &Path, key: &DecryptionKey) -> Result<Secret<Vec<u8>>> {
+    let decryptor = Decryptor::new(File::open(path)?).map_err(failed)?;
+    let identities: Vec<Box<dyn Identity>> = match key {
+        DecryptionKey::Identities(files) => {
+            let mut identities = Vec::new();
+            for file in files {
+                let parsed = IdentityFile::from_file(file.display().to_string())
+                    .map_err(|e| VanityError::Decryption(format!("{}: {e}", file.display())))?;
+                identities.extend(parsed.into_identities().map_err(failed)?);
+            }
+            identities
+        }
+        DecryptionKey::Passphrase(passphrase) => {
+            vec![Box::new(scrypt::Identity::new(passphrase.clone()))]
+        }
+    };
+
+    let mut reader = decryptor
+        .decrypt(identities.iter().map(|i| i.as_ref() as &dyn Identity))
+        .map_err(|e| match e {
+            DecryptError::DecryptionFailed | DecryptError::NoMatchingKeys => {
+                failed("wrong passphrase or no matching identity")
+            }
+            e => failed(e),
+        })?;
+    let mut plaintext = Secret::new(Vec::new());
+    reader.read_to_end(plaintext.expose_mut())?;
+    Ok(plaintext)
+}
+
+fn failed(e: impl std::fmt::Display) -> VanityError {
+    VanityError::Decryption(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::types::SecretInfo,
+        output::{CsvResultSink, CsvSchema},
+    };
+
+    fn found(address: &str) -> FoundAddress {
+        FoundAddress::new(address, SecretInfo::PrivateKey("deadbeef".into()))
+    }
+
+    fn csv_sink(path: &Path, key: EncryptionKey) -> EncryptedResultSink {
+        let _ = fs::remove_file(path);
+        // A tiny scrypt work factor keeps passphrase tests fast.
+        EncryptedResultSink::with_work_factor(path, key, Some(2), |buffer| {
+            Ok(Box::new(CsvResultSink::from_writer(
+                buffer,
+                CsvSchema::legacy(false, false),
+                true,
+            )?))
+        })
+        .expect("encrypted sink")
+    }
+
+    #[test]
+    fn recipients_can_decrypt_every_record() {
+        let path = std::env::temp_dir().join("vanity_miner_encrypted_recipient.csv.age");
+        let identity = x25519::Identity::generate();
+        let mut sink = csv_sink(&path, EncryptionKey::Recipients(vec![identity.to_public()]));
+
+        sink.handle(&found("0xaaa")).unwrap();
+        sink.handle(&found("0xbbb")).unwrap();
+        // Dropping waits for the writer thread's last rewrite.
+        drop(sink);
+
+        let raw = fs::read(&path).unwrap();
+        assert!(!raw.windows(8).any(|w| w == b"deadbeef"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let identity_path = std::env::temp_dir().join("vanity_miner_encrypted_identity.txt");
+        {
+            use age::secrecy::ExposeSecret;
+            fs::write(&identity_path, identity.to_string().expose_secret()).unwrap();
+        }
+        assert!(!is_passphrase_encrypted(&path).unwrap());
+        let plaintext =
+            decrypt_file(&path, &DecryptionKey::Identities(vec![identity_path])).unwrap();
+        assert_eq!(
+            String::from_utf8(plaintext.expose().clone()).unwrap(),
+            "address,secret\n0xaaa,deadbeef\n0xbbb,deadbeef\n"
+        );
+
+        // Encrypted files are never appended to.
+        assert!(EncryptedResultSink::new(
+            &path,
+            EncryptionKey::Passphrase("x".to_string().into()),
+            |_| unreachable!()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn shared_buffer_keeps_its_bytes_when_growing() {
+        let mut buffer = SharedBuffer::default();
+        let mut expected = Vec::new();
+        for i in 0..500u32 {
+            let line = format!("0x{i:040x},{i:064x}\n");
+            buffer.write_all(line.as_bytes()).unwrap();
+            expected.extend_from_slice(line.as_bytes());
+        }
+        assert_eq!(buffer.lock().expose(), &expected);
+    }
+
+    #[test]
+    fn passphrase_files_need_the_passphrase() {
+        let path = std::env::temp_dir().join("vanity_miner_encrypted_passphrase.csv.age");
+        let mut sink = csv_sink(
+            &path,
+            EncryptionKey::Passphrase("correct horse".to_string().into()),
+        );
+        sink.handle(&found("0xccc")).unwrap();
+        drop(sink);
+
+        assert!(is_passphrase_encrypted(&path).unwrap());
+        let plaintext = decrypt_file(
+            &path,
+            &DecryptionKey::Passphrase("correct horse".to_string().into()),
+        )
+        .unwrap();
+        assert!(String::from_utf8(plaintext.expose().clone())
+            .unwrap()
+            .contains("0xccc,deadbeef"));
+        assert!(decrypt_file(
+            &path,
+            &DecryptionKey::Passphrase("wrong".to_string().into())
+        )
+        .is_err());
+    }
+}
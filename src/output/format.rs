@@ -0,0 +1,88 @@
+use std::{fmt, fs::File, path::Path, str::FromStr};
+
+use crate::core::types::{FoundAddress, Result, VanityError};
+
+use super::{csv::read_csv_results, jsonl::read_jsonl_results};
+
+/// Layout of the result file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `address,secret` records.
+    #[default]
+    Csv,
+    /// One JSON object per line with the full result metadata.
+    Jsonl,
+}
+
+impl OutputFormat {
+    /// Guess the format from the file extension (`.jsonl`/`.ndjson`, else CSV).
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext)
+                if ext.eq_ignore_ascii_case("jsonl") || ext.eq_ignore_ascii_case("ndjson") =>
+            {
+                OutputFormat::Jsonl
+            }
+            _ => OutputFormat::Csv,
+        }
+    }
+}
+
+/// Read back a CSV or JSONL result file, by default guessing the format
+/// from its extension.
+pub fn read_results(path: &Path, format: Option<OutputFormat>) -> Result<Vec<FoundAddress>> {
+    let file = File::open(path)?;
+    match format.unwrap_or_else(|| OutputFormat::for_path(path)) {
+        OutputFormat::Csv => read_csv_results(file),
+        OutputFormat::Jsonl => read_jsonl_results(file),
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = VanityError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(OutputFormat::Csv),
+            "jsonl" | "ndjson" => Ok(OutputFormat::Jsonl),
+            _ => Err(VanityError::OutputFormat(format!(
+                "unknown format \"{s}\", expected csv or jsonl"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Csv => f.write_str("csv"),
+            OutputFormat::Jsonl => f.write_str("jsonl"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_follows_extension_unless_named() {
+        assert_eq!(
+            OutputFormat::for_path(Path::new("found.jsonl")),
+            OutputFormat::Jsonl
+        );
+        assert_eq!(
+            OutputFormat::for_path(Path::new("found.NDJSON")),
+            OutputFormat::Jsonl
+        );
+        assert_eq!(
+            OutputFormat::for_path(Path::new("found.csv")),
+            OutputFormat::Csv
+        );
+        assert_eq!(
+            "jsonl".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Jsonl
+        );
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    secret::Secret,
+    types::{FoundAddress, Result, SecretInfo, VanityError},
+};
+
+use super::{
+    permissions::{owner_only, restrict_to_owner},
+    ResultSink,
+};
+
+/// Appends one JSON object per found address, for `jq` and friends.
+pub struct JsonlResultSink<W: Write = File> {
+    writer: W,
+}
+
+impl JsonlResultSink {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        // The file holds private keys: never let others read it.
+        let file = owner_only(OpenOptions::new().create(true).append(true)).open(path)?;
+        restrict_to_owner(path)?;
+        Ok(Self::from_writer(file))
+    }
+}
+
+impl<W: Write> JsonlResultSink<W> {
+    pub fn from_writer(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write + Send> ResultSink for JsonlResultSink<W> {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        let mut line = result_json(found, false)?;
+        line.expose_mut().push(b'\n');
+        // One write per record so concurrent readers never see half a line.
+        self.writer.write_all(line.expose())?;
+        self.writer.flush()
+    }
+}
+
+/// One result as a JSON object of the JSONL layout; `redact` leaves out the
+/// `secret` field.
+pub(crate) fn result_json(found: &FoundAddress, redact: bool) -> io::Result<Secret<Vec<u8>>> {
+    let mut record = Record::from(found);
+    if redact {
+        record.secret = None;
+    }
+    serde_json::to_vec(&record)
+        .map(Secret::new)
+        .map_err(io::Error::other)
+}
+
+/// Read back the results of a JSONL result file.
+pub fn read_jsonl_results<R: Read>(reader: R) -> Result<Vec<FoundAddress>> {
+    let mut results = Vec::new();
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line)
+            .map_err(|e| VanityError::OutputFormat(format!("line {}: {e}", index + 1)))?;
+        results.push(
+            record
+                .into_found()
+                .map_err(|e| VanityError::OutputFormat(format!("line {}: {e}", index + 1)))?,
+        );
+    }
+    Ok(results)
+}
+
+/// The JSON layout of one result.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    chain: String,
+    network: Option<u32>,
+    address: String,
+    public_key: String,
+    /// Left out of redacted records.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<RecordSecret>,
+    derivation_path: Option<String>,
+    target: Option<String>,
+    pattern: String,
+    attempt: u64,
+    worker: usize,
+    /// RFC 3339 in UTC, e.g. `2026-10-18T06:00:00.123Z`.
+    found_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RecordSecret {
+    PrivateKey { key: Secret<String> },
+    Mnemonic { phrase: Secret<String> },
+    Keystore { path: String },
+}
+
+impl From<&FoundAddress> for Record {
+    fn from(found: &FoundAddress) -> Self {
+        let (secret, derivation_path) = match &found.secret {
+            SecretInfo::PrivateKey(key) => (RecordSecret::PrivateKey { key: key.clone() }, None),
+            SecretInfo::Mnemonic {
+                phrase,
+                derivation_path,
+            } => (
+                RecordSecret::Mnemonic {
+                    phrase: phrase.clone(),
+                },
+                Some(derivation_path.clone()),
+            ),
+            SecretInfo::Keystore(path) => (RecordSecret::Keystore { path: path.clone() }, None),
+        };
+        Record {
+            chain: found.chain.clone(),
+            network: found.network,
+            address: found.address.clone(),
+            public_key: found.public_key.clone(),
+            secret: Some(secret),
+            derivation_path,
+            target: found.target.clone(),
+            pattern: found.pattern.clone(),
+            attempt: found.attempt,
+            worker: found.worker,
+            found_at: found.found_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        }
+    }
+}
+
+impl Record {
+    fn into_found(self) -> std::result::Result<FoundAddress, String> {
+        let secret = match self.secret {
+            Some(RecordSecret::PrivateKey { key }) => SecretInfo::PrivateKey(key),
+            Some(RecordSecret::Mnemonic { phrase }) => SecretInfo::Mnemonic {
+                phrase,
+                derivation_path: self.derivation_path.unwrap_or_default(),
+            },
+            Some(RecordSecret::Keystore { path }) => SecretInfo::Keystore(path),
+            None => return Err("the record has no secret".into()),
+        };
+        Ok(FoundAddress {
+            address: self.address,
+            secret,
+            target: self.target,
+            chain: self.chain,
+            network: self.network,
+            public_key: self.public_key,
+            pattern: self.pattern,
+            attempt: self.attempt,
+            worker: self.worker,
+            found_at: DateTime::parse_from_rfc3339(&self.found_at)
+                .map_err(|e| e.to_string())?
+                .to_utc(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn writes_one_object_per_line() {
+        let mut sink = JsonlResultSink::from_writer(Vec::new());
+
+        let mut found = FoundAddress::new(
+            "cfx:aaketjh",
+            SecretInfo::Mnemonic {
+                phrase: "abandon ability".into(),
+                derivation_path: "m/44'/503'/0'/0/0".into(),
+            },
+        );
+        found.target = Some("cfx".into());
+        found.chain = "conflux".into();
+        found.network = Some(1029);
+        found.public_key = "04ab".into();
+        found.pattern = "prefix: aa".into();
+        found.attempt = 42;
+        found.worker = 3;
+        found.found_at = Utc.with_ymd_and_hms(2026, 10, 18, 6, 0, 0).unwrap();
+        sink.handle(&found).unwrap();
+        sink.handle(&FoundAddress::new(
+            "0xaddr",
+            SecretInfo::PrivateKey("deadbeef".into()),
+        ))
+        .unwrap();
+
+        let output = String::from_utf8(sink.writer).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(
+            lines[0],
+            json!({
+                "chain": "conflux",
+                "network": 1029,
+                "address": "cfx:aaketjh",
+                "public_key": "04ab",
+                "secret": {"type": "mnemonic", "phrase": "abandon ability"},
+                "derivation_path": "m/44'/503'/0'/0/0",
+                "target": "cfx",
+                "pattern": "prefix: aa",
+                "attempt": 42,
+                "worker": 3,
+                "found_at": "2026-10-18T06:00:00.000Z",
+            })
+        );
+        assert_eq!(
+            lines[1]["secret"],
+            json!({"type": "private_key", "key": "deadbeef"})
+        );
+        assert!(lines[1]["derivation_path"].is_null());
+
+        let read = read_jsonl_results(output.as_bytes()).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].found_at, found.found_at);
+        assert_eq!(read[0].network, Some(1029));
+        assert!(matches!(
+            &read[0].secret,
+            SecretInfo::Mnemonic { derivation_path, .. } if derivation_path == "m/44'/503'/0'/0/0"
+        ));
+    }
+}
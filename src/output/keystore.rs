@@ -0,0 +1,547 @@
+use std::{
+    collections::HashMap,
+    fmt, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use aes::Aes128;
+use chrono::{DateTime, Utc};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+use crate::core::{
+    chain::Chain,
+    secret::Secret,
+    types::{FoundAddress, KeyPair, Result, SecretInfo, VanityError},
+};
+
+use super::{
+    permissions::{create_private_dir, write_private_file},
+    DynResultSink, ResultSink,
+};
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+const KEYSTORE_VERSION: u32 = 3;
+const CIPHER: &str = "aes-128-ctr";
+const DERIVED_KEY_LEN: usize = 32;
+
+/// Key derivation used to turn the password into the encryption key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Scrypt { log_n: u8, r: u32, p: u32 },
+    Pbkdf2 { rounds: u32 },
+}
+
+impl Kdf {
+    /// geth's standard scrypt parameters (N = 2^18, r = 8, p = 1).
+    pub const fn scrypt() -> Self {
+        Kdf::Scrypt {
+            log_n: 18,
+            r: 8,
+            p: 1,
+        }
+    }
+
+    /// PBKDF2-HMAC-SHA256 with geth's 262144 rounds.
+    pub const fn pbkdf2() -> Self {
+        Kdf::Pbkdf2 { rounds: 262_144 }
+    }
+
+    fn derive(&self, password: &str, salt: &[u8]) -> Result<Secret<[u8; DERIVED_KEY_LEN]>> {
+        let mut key = Secret::new([0u8; DERIVED_KEY_LEN]);
+        match *self {
+            Kdf::Scrypt { log_n, r, p } => {
+                let params = scrypt::Params::new(log_n, r, p, DERIVED_KEY_LEN)
+                    .map_err(|e| invalid(format!("bad scrypt parameters: {e}")))?;
+                scrypt::scrypt(password.as_bytes(), salt, &params, key.expose_mut())
+                    .map_err(|e| invalid(format!("scrypt failed: {e}")))?;
+            }
+            Kdf::Pbkdf2 { rounds } => {
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, key.expose_mut())
+            }
+        }
+        Ok(key)
+    }
+
+    fn params(&self, salt: &[u8]) -> KdfParams {
+        match *self {
+            Kdf::Scrypt { log_n, r, p } => KdfParams::Scrypt {
+                dklen: DERIVED_KEY_LEN,
+                n: 1 << log_n,
+                p,
+                r,
+                salt: hex::encode(salt),
+            },
+            Kdf::Pbkdf2 { rounds } => KdfParams::Pbkdf2 {
+                c: rounds,
+                dklen: DERIVED_KEY_LEN,
+                prf: "hmac-sha256".into(),
+                salt: hex::encode(salt),
+            },
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Kdf::Scrypt { .. } => "scrypt",
+            Kdf::Pbkdf2 { .. } => "pbkdf2",
+        }
+    }
+}
+
+impl fmt::Display for Kdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Kdf {
+    type Err = VanityError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "scrypt" => Ok(Kdf::scrypt()),
+            "pbkdf2" => Ok(Kdf::pbkdf2()),
+            _ => Err(invalid(format!(
+                "unknown KDF \"{s}\", expected scrypt or pbkdf2"
+            ))),
+        }
+    }
+}
+
+/// A Web3 Secret Storage (keystore V3) file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keystore {
+    /// Address in lowercase hex without `0x`.
+    pub address: String,
+    #[serde(alias = "Crypto")]
+    pub crypto: KeystoreCrypto,
+    pub id: String,
+    pub version: u32,
+    /// Account name; the Conflux flavour stores the base32 address here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Free-form JSON metadata, written as `{}` by Conflux tooling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub cipher: String,
+    pub ciphertext: String,
+    pub cipherparams: CipherParams,
+    pub kdf: String,
+    pub kdfparams: KdfParams,
+    pub mac: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub iv: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u64,
+        p: u32,
+        r: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        c: u32,
+        dklen: usize,
+        prf: String,
+        salt: String,
+    },
+}
+
+/// Which wallet software the keystore files should look native to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystoreFlavor {
+    Ethereum,
+    /// conflux-rust key files, which also carry `name` and `meta`.
+    Conflux,
+}
+
+impl KeystoreFlavor {
+    pub fn for_chain(chain: &dyn Chain) -> Self {
+        match chain.id() {
+            "conflux" => KeystoreFlavor::Conflux,
+            _ => KeystoreFlavor::Ethereum,
+        }
+    }
+}
+
+impl Keystore {
+    /// Encrypt `secret` for `address` (raw bytes) under `password`.
+    pub fn encrypt(
+        secret: &[u8; 32],
+        address: &[u8],
+        password: &str,
+        kdf: &Kdf,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self> {
+        let mut salt = [0u8; 32];
+        let mut iv = [0u8; 16];
+        let mut id = [0u8; 16];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut iv);
+        rng.fill_bytes(&mut id);
+
+        let key = kdf.derive(password, &salt)?;
+        let mut ciphertext = *secret;
+        Aes128Ctr::new(key.expose()[..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+        Ok(Self {
+            address: hex::encode(address),
+            crypto: KeystoreCrypto {
+                cipher: CIPHER.into(),
+                ciphertext: hex::encode(ciphertext),
+                cipherparams: CipherParams {
+                    iv: hex::encode(iv),
+                },
+                kdf: kdf.name().into(),
+                kdfparams: kdf.params(&salt),
+                mac: hex::encode(mac(key.expose(), &ciphertext)),
+            },
+            id: uuid_v4(id),
+            version: KEYSTORE_VERSION,
+            name: None,
+            meta: None,
+        })
+    }
+
+    /// Adjust the file for `flavor`; `display` is the address as shown to users.
+    pub fn with_flavor(mut self, flavor: KeystoreFlavor, display: &str) -> Self {
+        if flavor == KeystoreFlavor::Conflux {
+            self.name = Some(display.to_string());
+            self.meta = Some("{}".into());
+        }
+        self
+    }
+
+    /// Recover the private key, checking the MAC against `password`.
+    pub fn decrypt(&self, password: &str) -> Result<Secret<[u8; 32]>> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(invalid(format!("unsupported version {}", self.version)));
+        }
+        if self.crypto.cipher != CIPHER {
+            return Err(invalid(format!(
+                "unsupported cipher {}",
+                self.crypto.cipher
+            )));
+        }
+
+        let (kdf, salt) = match &self.crypto.kdfparams {
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                p,
+                r,
+                salt,
+            } if *dklen == DERIVED_KEY_LEN && n.is_power_of_two() => (
+                Kdf::Scrypt {
+                    log_n: n.trailing_zeros() as u8,
+                    r: *r,
+                    p: *p,
+                },
+                salt,
+            ),
+            KdfParams::Pbkdf2 {
+                c,
+                dklen,
+                prf,
+                salt,
+            } if *dklen == DERIVED_KEY_LEN && prf == "hmac-sha256" => {
+                (Kdf::Pbkdf2 { rounds: *c }, salt)
+            }
+            _ => return Err(invalid("unsupported KDF parameters".into())),
+        };
+
+        let salt = decode_hex("salt", salt)?;
+        let iv: [u8; 16] = decode_hex("iv", &self.crypto.cipherparams.iv)?
+            .try_into()
+            .map_err(|_| invalid("iv must be 16 bytes".into()))?;
+        let ciphertext = decode_hex("ciphertext", &self.crypto.ciphertext)?;
+        let expected = decode_hex("mac", &self.crypto.mac)?;
+
+        let key = kdf.derive(password, &salt)?;
+        if mac(key.expose(), &ciphertext)[..] != expected[..] {
+            return Err(invalid(
+                "wrong password or corrupted file (MAC mismatch)".into(),
+            ));
+        }
+
+        let mut secret = Secret::new(
+            <[u8; 32]>::try_from(ciphertext)
+                .map_err(|_| invalid("ciphertext must be 32 bytes".into()))?,
+        );
+        Aes128Ctr::new(key.expose()[..16].into(), &iv.into()).apply_keystream(secret.expose_mut());
+        Ok(secret)
+    }
+
+    /// geth's file name: `UTC--<ISO 8601 time>--<address>`.
+    pub fn file_name(&self, created: DateTime<Utc>) -> String {
+        format!(
+            "UTC--{}--{}",
+            created.format("%Y-%m-%dT%H-%M-%S%.9fZ"),
+            self.address
+        )
+    }
+}
+
+/// Writes each found key to its own keystore file and passes the file path,
+/// instead of the secret, on to the inner sink.
+pub struct KeystoreResultSink {
+    dir: PathBuf,
+    kdf: Kdf,
+    password: Secret<String>,
+    chains: HashMap<Option<String>, Arc<dyn Chain>>,
+    inner: DynResultSink,
+}
+
+impl KeystoreResultSink {
+    /// Create `dir` if needed (readable by the owner only on Unix).
+    pub fn new<P: AsRef<Path>>(
+        dir: P,
+        kdf: Kdf,
+        password: Secret<String>,
+        inner: DynResultSink,
+    ) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        create_private_dir(&dir)?;
+
+        Ok(Self {
+            dir,
+            kdf,
+            password,
+            chains: HashMap::new(),
+            inner,
+        })
+    }
+
+    /// Use `chain` to derive keys and addresses for results of `target`.
+    pub fn route(&mut self, target: Option<String>, chain: Arc<dyn Chain>) {
+        self.chains.insert(target, chain);
+    }
+
+    fn write_keystore(&self, found: &FoundAddress, chain: &dyn Chain) -> Result<PathBuf> {
+        if let SecretInfo::Keystore(path) = &found.secret {
+            return Ok(PathBuf::from(path));
+        }
+        let keypair = found.secret.keypair(chain)?;
+        let KeyPair::Secp256k1 { secret, .. } = &keypair else {
+            return Err(invalid(format!("{} keys cannot be stored", chain.name())));
+        };
+
+        let address = chain.compute_address(keypair.public_key())?;
+        let keystore = Keystore::encrypt(
+            secret.expose(),
+            address.raw(),
+            self.password.expose(),
+            &self.kdf,
+            &mut rand::rng(),
+        )?
+        .with_flavor(KeystoreFlavor::for_chain(chain), &found.address);
+
+        let path = self.dir.join(keystore.file_name(Utc::now()));
+        let json = serde_json::to_vec_pretty(&keystore)
+            .map_err(|e| invalid(format!("cannot serialize keystore: {e}")))?;
+        write_private_file(&path, &json)?;
+        Ok(path)
+    }
+}
+
+impl ResultSink for KeystoreResultSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        let chain = self.chains.get(&found.target).ok_or_else(|| {
+            io::Error::other(format!("no keystore chain for target {:?}", found.target))
+        })?;
+        let path = self
+            .write_keystore(found, chain.as_ref())
+            .map_err(io::Error::other)?;
+
+        self.inner.handle(&FoundAddress {
+            secret: SecretInfo::Keystore(path.display().to_string()),
+            ..found.clone()
+        })
+    }
+}
+
+fn mac(key: &[u8; DERIVED_KEY_LEN], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(&key[16..]);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+fn uuid_v4(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|e| invalid(format!("bad {field}: {e}")))
+}
+
+fn invalid(reason: String) -> VanityError {
+    VanityError::Keystore(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chains::{ConfluxChain, EthereumChain};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::fs;
+    use std::sync::Mutex;
+
+    /// Cheap scrypt parameters so tests stay fast.
+    const LIGHT: Kdf = Kdf::Scrypt {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    #[test]
+    fn decrypts_the_web3_secret_storage_test_vector() {
+        let json = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3,
+            "address": "008aeeda4d805471df9b2a5b0f38a0c3bcba786b"
+        }"#;
+        let keystore: Keystore = serde_json::from_str(json).unwrap();
+
+        let secret = keystore.decrypt("testpassword").unwrap();
+        assert_eq!(
+            hex::encode(secret.expose()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+    }
+
+    #[test]
+    fn scrypt_keystores_round_trip() {
+        let secret = [7u8; 32];
+        let mut rng = StdRng::seed_from_u64(1);
+        let keystore = Keystore::encrypt(&secret, &[0xab; 20], "pw", &LIGHT, &mut rng).unwrap();
+
+        let json = serde_json::to_string(&keystore).unwrap();
+        assert!(json.contains(r#""kdf":"scrypt""#));
+        assert!(!json.contains("name"));
+        let parsed: Keystore = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.decrypt("pw").unwrap().expose(), &secret);
+        assert!(parsed.decrypt("nope").is_err());
+
+        assert_eq!(parsed.id.len(), 36);
+        assert_eq!(&parsed.id[14..15], "4");
+        let created = DateTime::from_timestamp(1_700_000_000, 5).unwrap();
+        assert_eq!(
+            parsed.file_name(created),
+            format!("UTC--2023-11-14T22-13-20.000000005Z--{}", "ab".repeat(20))
+        );
+    }
+
+    struct Recording(Arc<Mutex<Vec<FoundAddress>>>);
+
+    impl ResultSink for Recording {
+        fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+            self.0.lock().unwrap().push(found.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sink_writes_keystores_and_forwards_only_the_path() {
+        let dir = std::env::temp_dir().join("vanity_miner_keystore_sink_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut sink = KeystoreResultSink::new(
+            &dir,
+            LIGHT,
+            "secret".into(),
+            Box::new(Recording(Arc::clone(&seen))),
+        )
+        .unwrap();
+        sink.route(None, Arc::new(EthereumChain::new()));
+        sink.route(Some("cfx".into()), Arc::new(ConfluxChain::new()));
+
+        let key = format!("{}01", "0".repeat(62));
+        sink.handle(&FoundAddress::new(
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+            SecretInfo::PrivateKey(key.as_str().into()),
+        ))
+        .unwrap();
+        sink.handle(&FoundAddress {
+            target: Some("cfx".into()),
+            ..FoundAddress::new("cfx:aaketjh", SecretInfo::PrivateKey(key.as_str().into()))
+        })
+        .unwrap();
+
+        let seen = seen.lock().unwrap();
+        let SecretInfo::Keystore(path) = &seen[0].secret else {
+            panic!("expected a keystore path");
+        };
+        assert!(path.ends_with("--7e5f4552091a69125d5dfcb7b8c2659029395bdf"));
+
+        let keystore: Keystore =
+            serde_json::from_slice(&fs::read(path).unwrap()).expect("keystore json");
+        assert_eq!(
+            hex::encode(keystore.decrypt("secret").unwrap().expose()),
+            key
+        );
+        assert!(keystore.name.is_none());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let SecretInfo::Keystore(path) = &seen[1].secret else {
+            panic!("expected a keystore path");
+        };
+        let keystore: Keystore = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(keystore.name.as_deref(), Some("cfx:aaketjh"));
+        assert_eq!(keystore.meta.as_deref(), Some("{}"));
+
+        assert!(sink
+            .handle(&FoundAddress {
+                target: Some("unrouted".into()),
+                ..FoundAddress::new("x", SecretInfo::PrivateKey(key.into()))
+            })
+            .is_err());
+    }
+}
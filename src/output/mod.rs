@@ -1,9 +1,36 @@
 mod console;
 mod csv;
+mod encrypted;
+mod format;
+mod jsonl;
+mod keystore;
+mod paper;
+pub(crate) mod permissions;
+mod qr;
 mod result;
+mod routed;
+mod shares;
+mod sqlite;
 mod stats_console;
+mod webhook;
 
 pub use console::ConsoleResultSink;
-pub use csv::CsvResultSink;
+pub use csv::{
+    read_csv_results, CsvColumn, CsvResultSink, CsvSchema, SecretKind, CSV_SCHEMA_VERSION,
+};
+pub use encrypted::{
+    decrypt_file, is_passphrase_encrypted, DecryptionKey, EncryptedResultSink, EncryptionKey,
+    SharedBuffer,
+};
+pub use format::{read_results, OutputFormat};
+pub use jsonl::{read_jsonl_results, JsonlResultSink};
+pub use keystore::{
+    CipherParams, Kdf, KdfParams, Keystore, KeystoreCrypto, KeystoreFlavor, KeystoreResultSink,
+};
+pub use paper::{PaperFormat, PaperWalletSink};
 pub use result::{CombinedResultSink, DynResultSink, ResultSink};
+pub use routed::RoutedResultSink;
+pub use shares::{combine_shares, read_share_file, share_paths, ShareRecord, ShareResultSink};
+pub use sqlite::{ResultFilter, ResultStore, SqliteResultSink, StoredResult, StoredRun};
 pub use stats_console::ConsoleStatsSink;
+pub use webhook::{Webhook, WebhookResultSink};
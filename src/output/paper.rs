@@ -0,0 +1,444 @@
+use std::{
+    fmt::{self, Write as _},
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::SecondsFormat;
+
+use crate::core::{
+    secret::Secret,
+    types::{FoundAddress, Result, SecretInfo, VanityError},
+};
+
+use super::{
+    permissions::{create_private_dir, write_private_file},
+    qr, ResultSink,
+};
+
+/// Columns of the numbered mnemonic word grid.
+const WORD_COLUMNS: usize = 4;
+/// Room for a whole page, so the buffers holding the secret never reallocate
+/// and leave unwiped copies behind.
+const PAGE_CAPACITY: usize = 64 * 1024;
+const WARNING: &str = "Anyone who sees the secret on this page controls the address. \
+                       Keep it offline and out of sight.";
+
+/// Page layout of a paper wallet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaperFormat {
+    /// An HTML page with inline SVG codes, ready to print from a browser.
+    #[default]
+    Html,
+    /// A standalone SVG image.
+    Svg,
+}
+
+impl PaperFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            PaperFormat::Html => "html",
+            PaperFormat::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for PaperFormat {
+    type Err = VanityError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "html" => Ok(PaperFormat::Html),
+            "svg" => Ok(PaperFormat::Svg),
+            _ => Err(VanityError::OutputFormat(format!(
+                "unknown paper wallet format \"{s}\", expected html or svg"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for PaperFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Writes each result as a printable page with QR codes of the address and
+/// the secret, one file per address. The pages load nothing from the
+/// network, so they can be opened and printed offline.
+pub struct PaperWalletSink {
+    dir: PathBuf,
+    format: PaperFormat,
+}
+
+impl PaperWalletSink {
+    /// Create `dir` if needed (readable by the owner only on Unix).
+    pub fn new<P: AsRef<Path>>(dir: P, format: PaperFormat) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        create_private_dir(&dir)?;
+        Ok(Self { dir, format })
+    }
+
+    /// The page of `found`, e.g. `0xAbC….html` (`:` in Conflux addresses
+    /// becomes `_`).
+    pub fn page_path(&self, found: &FoundAddress) -> PathBuf {
+        self.dir.join(format!(
+            "{}.{}",
+            found.address.replace(':', "_"),
+            self.format.extension()
+        ))
+    }
+}
+
+impl ResultSink for PaperWalletSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        let page = match self.format {
+            PaperFormat::Html => html_page(found)?,
+            PaperFormat::Svg => svg_page(found)?,
+        };
+        write_private_file(&self.page_path(found), page.expose().as_bytes())
+    }
+}
+
+/// What the page shows besides the address.
+enum PaperSecret<'a> {
+    PrivateKey(&'a str),
+    Mnemonic { words: Vec<&'a str>, path: &'a str },
+    Keystore(&'a str),
+}
+
+impl<'a> PaperSecret<'a> {
+    fn of(secret: &'a SecretInfo) -> Self {
+        match secret {
+            SecretInfo::PrivateKey(key) => PaperSecret::PrivateKey(key.expose()),
+            SecretInfo::Mnemonic {
+                phrase,
+                derivation_path,
+            } => PaperSecret::Mnemonic {
+                words: phrase.expose().split_whitespace().collect(),
+                path: derivation_path,
+            },
+            SecretInfo::Keystore(path) => PaperSecret::Keystore(path),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PaperSecret::PrivateKey(_) => "Private key",
+            PaperSecret::Mnemonic { .. } => "Recovery phrase",
+            PaperSecret::Keystore(_) => "Encrypted keystore",
+        }
+    }
+
+    /// The text behind the secret QR code; keystores get none.
+    fn qr_data(&self) -> Option<Secret<String>> {
+        match self {
+            PaperSecret::PrivateKey(key) => Some((*key).into()),
+            PaperSecret::Mnemonic { words, .. } => Some(words.join(" ").into()),
+            PaperSecret::Keystore(_) => None,
+        }
+    }
+}
+
+/// Chain, target and time of the result, under the title.
+fn subtitle(found: &FoundAddress) -> String {
+    let mut parts = Vec::new();
+    if !found.chain.is_empty() {
+        parts.push(found.chain.clone());
+    }
+    if let Some(target) = &found.target {
+        parts.push(target.clone());
+    }
+    parts.push(format!(
+        "found {}",
+        found.found_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+    ));
+    parts.join(" · ")
+}
+
+fn html_page(found: &FoundAddress) -> io::Result<Secret<String>> {
+    let secret = PaperSecret::of(&found.secret);
+    let mut page = Secret::new(String::with_capacity(PAGE_CAPACITY));
+    let out = page.expose_mut();
+    let address = escape(&found.address);
+
+    let _ = write!(
+        out,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Paper wallet {address}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #000; background: #fff; }}
+.codes {{ display: flex; gap: 3em; flex-wrap: wrap; }}
+figure {{ margin: 0; text-align: center; }}
+figcaption {{ font-weight: bold; margin-top: .5em; }}
+.mono {{ font-family: monospace; font-size: 1.1em; word-break: break-all; }}
+ol.words {{ display: grid; grid-template-columns: repeat({WORD_COLUMNS}, 1fr); gap: .4em 2em; padding-left: 2em; font-family: monospace; font-size: 1.1em; }}
+.warning {{ border: 2px solid #000; padding: .8em; margin-top: 2em; }}
+</style>
+</head>
+<body>
+<h1>Vanity address</h1>
+<p>{}</p>
+<div class="codes">
+<figure>{}<figcaption>Address</figcaption></figure>
+"#,
+        escape(&subtitle(found)),
+        qr::svg(&found.address, 0, 0, 220)?,
+    );
+    if let Some(data) = secret.qr_data() {
+        let _ = writeln!(
+            out,
+            "<figure>{}<figcaption>{}</figcaption></figure>",
+            Secret::new(qr::svg(data.expose(), 0, 0, 220)?).expose(),
+            secret.label()
+        );
+    }
+    let _ = write!(
+        out,
+        "</div>\n<h2>Address</h2>\n<p class=\"mono\">{address}</p>\n<h2>{}</h2>\n",
+        secret.label()
+    );
+    match &secret {
+        PaperSecret::PrivateKey(key) => {
+            out.push_str("<p class=\"mono\">");
+            escape_into(out, key);
+            out.push_str("</p>\n");
+        }
+        PaperSecret::Mnemonic { words, path } => {
+            out.push_str("<ol class=\"words\">\n");
+            for word in words {
+                out.push_str("<li>");
+                escape_into(out, word);
+                out.push_str("</li>\n");
+            }
+            let _ = writeln!(
+                out,
+                "</ol>\n<p>Derivation path: <span class=\"mono\">{}</span></p>",
+                escape(path)
+            );
+        }
+        PaperSecret::Keystore(path) => {
+            let _ = writeln!(
+                out,
+                "<p>The key is stored encrypted in <span class=\"mono\">{}</span>.</p>",
+                escape(path)
+            );
+        }
+    }
+    let _ = write!(
+        out,
+        "<p class=\"warning\">{}</p>\n</body>\n</html>\n",
+        escape(WARNING)
+    );
+    Ok(page)
+}
+
+fn svg_page(found: &FoundAddress) -> io::Result<Secret<String>> {
+    const WIDTH: u32 = 800;
+    const MARGIN: u32 = 40;
+    const QR_SIZE: u32 = 240;
+    const ROW: u32 = 26;
+
+    let secret = PaperSecret::of(&found.secret);
+    let mut page = Secret::new(String::with_capacity(PAGE_CAPACITY));
+    let out = page.expose_mut();
+
+    // Everything after the header, positioned from `y` down.
+    let mut body = Secret::new(String::with_capacity(PAGE_CAPACITY));
+    let text = body.expose_mut();
+    let qr_top = 110;
+    let _ = write!(
+        text,
+        "{}\n<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" class=\"label\">Address</text>\n",
+        qr::svg(&found.address, MARGIN, qr_top, QR_SIZE)?,
+        MARGIN + QR_SIZE / 2,
+        qr_top + QR_SIZE + 20
+    );
+    if let Some(data) = secret.qr_data() {
+        let x = WIDTH - MARGIN - QR_SIZE;
+        let _ = write!(
+            text,
+            "{}\n<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" class=\"label\">{}</text>\n",
+            Secret::new(qr::svg(data.expose(), x, qr_top, QR_SIZE)?).expose(),
+            x + QR_SIZE / 2,
+            qr_top + QR_SIZE + 20,
+            secret.label()
+        );
+    }
+
+    let mut y = qr_top + QR_SIZE + 70;
+    let _ = writeln!(
+        text,
+        "<text x=\"{MARGIN}\" y=\"{y}\" class=\"label\">Address</text>\n\
+         <text x=\"{MARGIN}\" y=\"{}\" class=\"mono\">{}</text>",
+        y + ROW,
+        escape(&found.address)
+    );
+    y += 2 * ROW + 20;
+    let _ = writeln!(
+        text,
+        "<text x=\"{MARGIN}\" y=\"{y}\" class=\"label\">{}</text>",
+        secret.label()
+    );
+    y += ROW;
+    match &secret {
+        PaperSecret::PrivateKey(key) => {
+            let _ = write!(text, "<text x=\"{MARGIN}\" y=\"{y}\" class=\"mono\">");
+            escape_into(text, key);
+            text.push_str("</text>\n");
+        }
+        PaperSecret::Mnemonic { words, path } => {
+            let column = (WIDTH - 2 * MARGIN) / WORD_COLUMNS as u32;
+            for (i, word) in words.iter().enumerate() {
+                let (row, col) = ((i / WORD_COLUMNS) as u32, (i % WORD_COLUMNS) as u32);
+                let _ = write!(
+                    text,
+                    "<text x=\"{}\" y=\"{}\" class=\"mono\">{:>2}. ",
+                    MARGIN + col * column,
+                    y + row * ROW,
+                    i + 1,
+                );
+                escape_into(text, word);
+                text.push_str("</text>\n");
+            }
+            y += words.len().div_ceil(WORD_COLUMNS) as u32 * ROW;
+            let _ = writeln!(
+                text,
+                "<text x=\"{MARGIN}\" y=\"{y}\">Derivation path: {}</text>",
+                escape(path)
+            );
+        }
+        PaperSecret::Keystore(path) => {
+            let _ = writeln!(
+                text,
+                "<text x=\"{MARGIN}\" y=\"{y}\">Stored encrypted in {}</text>",
+                escape(path)
+            );
+        }
+    }
+    y += 2 * ROW;
+    let _ = writeln!(
+        text,
+        "<text x=\"{MARGIN}\" y=\"{y}\" class=\"small\">{}</text>",
+        escape(WARNING)
+    );
+    let height = y + MARGIN;
+
+    let _ = write!(
+        out,
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="sans-serif">
+<style>.label {{ font-weight: bold; font-size: 16px; }} .mono {{ font-family: monospace; font-size: 14px; white-space: pre; }} .small {{ font-size: 12px; }}</style>
+<rect width="{WIDTH}" height="{height}" fill="#fff"/>
+<text x="{MARGIN}" y="50" font-size="26" font-weight="bold">Vanity address</text>
+<text x="{MARGIN}" y="78" font-size="14">{}</text>
+{}</svg>
+"##,
+        escape(&subtitle(found)),
+        body.expose()
+    );
+    Ok(page)
+}
+
+/// Escape text for HTML and SVG (XML) documents.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    escape_into(&mut escaped, text);
+    escaped
+}
+
+/// Append `text` escaped to `out`; secrets go straight into the page buffer
+/// this way, without an unwiped copy in between.
+fn escape_into(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mnemonic() -> FoundAddress {
+        FoundAddress {
+            chain: "ethereum".into(),
+            target: Some("<dead>".into()),
+            ..FoundAddress::new(
+                "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+                SecretInfo::Mnemonic {
+                    phrase: "abandon abandon abandon abandon abandon abandon abandon abandon \
+                             abandon abandon abandon about"
+                        .into(),
+                    derivation_path: "m/44'/60'/0'/0/0".into(),
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn pages_show_codes_and_numbered_words() {
+        let found = mnemonic();
+        let html = html_page(&found).unwrap();
+        let html = html.expose();
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert_eq!(html.matches("<li>").count(), 12);
+        assert!(html.contains("<li>about</li>"));
+        assert!(html.contains("&lt;dead&gt;"));
+        assert!(!html.contains("http://") && !html.contains("https://"));
+
+        let svg = svg_page(&found).unwrap();
+        let svg = svg.expose();
+        assert_eq!(svg.matches("<svg").count(), 3);
+        assert!(svg.contains("12. about"));
+
+        let key = FoundAddress::new("0xaddr", SecretInfo::PrivateKey("ab<cd".into()));
+        assert!(html_page(&key)
+            .unwrap()
+            .expose()
+            .contains("<p class=\"mono\">ab&lt;cd</p>"));
+        assert!(svg_page(&key)
+            .unwrap()
+            .expose()
+            .contains("class=\"mono\">ab&lt;cd</text>"));
+
+        let keystore = FoundAddress::new("0xaddr", SecretInfo::Keystore("keys/UTC--x".into()));
+        let html = html_page(&keystore).unwrap();
+        assert_eq!(html.expose().matches("<svg").count(), 1);
+        assert!(html.expose().contains("keys/UTC--x"));
+    }
+
+    #[test]
+    fn writes_one_private_page_per_address() {
+        let dir = std::env::temp_dir().join("vanity_miner_paper_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut sink = PaperWalletSink::new(&dir, PaperFormat::Svg).unwrap();
+        let found = FoundAddress::new(
+            "cfx:aak2rra2njvd77ezwjvx04kkds9fzagfe6ku8scz91",
+            SecretInfo::PrivateKey("01".repeat(32).into()),
+        );
+        sink.handle(&found).unwrap();
+
+        let path = dir.join("cfx_aak2rra2njvd77ezwjvx04kkds9fzagfe6ku8scz91.svg");
+        assert_eq!(sink.page_path(&found), path);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains(&"01".repeat(32)));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
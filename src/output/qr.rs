@@ -0,0 +1,59 @@
+use std::{fmt::Write, io};
+
+use qrcode::{render::unicode::Dense1x2, Color, EcLevel, QrCode};
+
+/// Modules of light border around the code that scanners need.
+const QUIET_ZONE: usize = 4;
+
+fn encode(data: &str) -> io::Result<QrCode> {
+    QrCode::with_error_correction_level(data, EcLevel::M)
+        .map_err(|e| io::Error::other(format!("cannot encode QR code: {e}")))
+}
+
+/// QR code of `data` as an `<svg>` element of `size` pixels at `(x, y)`;
+/// it can be inlined in HTML or nested in another SVG.
+pub(crate) fn svg(data: &str, x: u32, y: u32, size: u32) -> io::Result<String> {
+    let code = encode(data)?;
+    let width = code.width();
+    let view = width + 2 * QUIET_ZONE;
+
+    let mut path = String::new();
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (col, row) = (i % width + QUIET_ZONE, i / width + QUIET_ZONE);
+            write!(path, "M{col} {row}h1v1h-1z").expect("writing to a String");
+        }
+    }
+    Ok(format!(
+        r##"<svg x="{x}" y="{y}" width="{size}" height="{size}" viewBox="0 0 {view} {view}" shape-rendering="crispEdges"><rect width="{view}" height="{view}" fill="#fff"/><path fill="#000" d="{path}"/></svg>"##
+    ))
+}
+
+/// QR code of `data` drawn with half blocks, two rows per line. The light
+/// modules are the blocks, so the code reads on dark terminal backgrounds.
+pub(crate) fn terminal(data: &str) -> io::Result<String> {
+    Ok(encode(data)?
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_svg_and_terminal_codes() {
+        let address = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+        let svg = svg(address, 10, 20, 200).unwrap();
+        assert!(svg.starts_with(r#"<svg x="10" y="20" width="200" height="200""#));
+        assert!(svg.contains("viewBox=\"0 0 37 37\""));
+
+        let terminal = terminal(address).unwrap();
+        // Two module rows per line, plus the quiet zone.
+        assert_eq!(terminal.lines().count(), (29 + 8_usize).div_ceil(2));
+        assert!(terminal.contains('█'));
+    }
+}
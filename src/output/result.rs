@@ -64,10 +64,7 @@ mod tests {
         combined.push(Box::new(CountingSink::default()));
         combined.push(Box::new(CountingSink::default()));
 
-        let addr = FoundAddress {
-            address: "addr".into(),
-            secret: SecretInfo::PrivateKey("secret".into()),
-        };
+        let addr = FoundAddress::new("addr", SecretInfo::PrivateKey("secret".into()));
 
         combined.handle(&addr).unwrap();
     }
@@ -0,0 +1,91 @@
+use std::{collections::HashMap, io};
+
+use crate::core::types::FoundAddress;
+
+use super::{DynResultSink, ResultSink};
+
+/// A sink that forwards each record to the sink registered for its target.
+///
+/// Several targets may share one sink (e.g. the same output file); records
+/// for unrouted targets are dropped.
+#[derive(Default)]
+pub struct RoutedResultSink {
+    sinks: Vec<DynResultSink>,
+    routes: HashMap<Option<String>, usize>,
+}
+
+impl RoutedResultSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a sink and return its index for use with [`route`](Self::route).
+    pub fn push(&mut self, sink: DynResultSink) -> usize {
+        self.sinks.push(sink);
+        self.sinks.len() - 1
+    }
+
+    /// Send results for `target` to the sink at `index`.
+    pub fn route(&mut self, target: Option<String>, index: usize) {
+        assert!(index < self.sinks.len(), "route to unknown sink {index}");
+        self.routes.insert(target, index);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+}
+
+impl ResultSink for RoutedResultSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        match self.routes.get(&found.target) {
+            Some(&index) => self.sinks[index].handle(found),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::core::types::SecretInfo;
+
+    struct RecordingSink(Arc<Mutex<Vec<String>>>);
+
+    impl ResultSink for RecordingSink {
+        fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+            self.0.lock().unwrap().push(found.address.clone());
+            Ok(())
+        }
+    }
+
+    fn found(address: &str, target: &str) -> FoundAddress {
+        FoundAddress {
+            target: Some(target.into()),
+            ..FoundAddress::new(address, SecretInfo::PrivateKey("00".into()))
+        }
+    }
+
+    #[test]
+    fn routes_by_target_name() {
+        let a = Arc::new(Mutex::new(Vec::new()));
+        let b = Arc::new(Mutex::new(Vec::new()));
+
+        let mut sink = RoutedResultSink::new();
+        let ia = sink.push(Box::new(RecordingSink(Arc::clone(&a))));
+        let ib = sink.push(Box::new(RecordingSink(Arc::clone(&b))));
+        sink.route(Some("one".into()), ia);
+        sink.route(Some("two".into()), ia);
+        sink.route(Some("three".into()), ib);
+
+        sink.handle(&found("0x1", "one")).unwrap();
+        sink.handle(&found("0x2", "two")).unwrap();
+        sink.handle(&found("0x3", "three")).unwrap();
+        sink.handle(&found("0x4", "unknown")).unwrap();
+
+        assert_eq!(*a.lock().unwrap(), vec!["0x1", "0x2"]);
+        assert_eq!(*b.lock().unwrap(), vec!["0x3"]);
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use bip39::Mnemonic;
+use csv::{ReaderBuilder, Writer};
+use serde::Deserialize;
+
+use crate::core::{
+    secret::Secret,
+    shamir::{self, Share, SharePolicy},
+    slip39,
+    types::{FoundAddress, Result, SecretInfo, VanityError},
+};
+
+use super::{
+    permissions::{owner_only, restrict_to_owner},
+    ResultSink,
+};
+
+const HEADER: [&str; 5] = ["address", "chain", "network", "derivation_path", "share"];
+
+/// Prefix of plain Shamir shares of private keys: `shamir:<threshold>:<index>:<hex>`.
+const SHAMIR_PREFIX: &str = "shamir:";
+/// Prefix of SLIP-39 shares of mnemonic entropy: `slip39:<words>`.
+const SLIP39_PREFIX: &str = "slip39:";
+
+/// Splits each found secret into shares and appends share `i` to file `i`,
+/// so no single file holds the key.
+///
+/// Private keys are split with plain GF(256) Shamir sharing, mnemonics as
+/// SLIP-39 shares of their BIP-39 entropy. SLIP-39 wallets use a recovered
+/// master secret as the seed itself, so they would show other accounts; the
+/// mnemonic shares are only meant for `vanity-miner combine`.
+pub struct ShareResultSink {
+    policy: SharePolicy,
+    writers: Vec<Writer<File>>,
+}
+
+impl ShareResultSink {
+    /// Append to one CSV file per share; `paths` must hold `policy.count` paths.
+    pub fn new<P: AsRef<Path>>(paths: &[P], policy: SharePolicy) -> io::Result<Self> {
+        if paths.len() != usize::from(policy.count) {
+            return Err(io::Error::other(format!(
+                "{policy} sharing needs {} share files, got {}",
+                policy.count,
+                paths.len()
+            )));
+        }
+        let writers = paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                let empty = path.metadata().map(|m| m.len() == 0).unwrap_or(true);
+                let file = owner_only(OpenOptions::new().create(true).append(true)).open(path)?;
+                restrict_to_owner(path)?;
+                let mut writer = Writer::from_writer(file);
+                if empty {
+                    writer.write_record(HEADER).map_err(io::Error::other)?;
+                    writer.flush()?;
+                }
+                Ok(writer)
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { policy, writers })
+    }
+
+    fn shares(&self, secret: &SecretInfo) -> Result<Vec<Secret<String>>> {
+        let mut rng = rand::rng();
+        match secret {
+            SecretInfo::PrivateKey(key) => {
+                let key = Secret::new(
+                    hex::decode(key.expose().trim_start_matches("0x"))
+                        .map_err(|e| VanityError::Shares(format!("bad private key: {e}")))?,
+                );
+                Ok(shamir::split(key.expose(), self.policy, &mut rng)
+                    .into_iter()
+                    .map(|share| {
+                        format!(
+                            "{SHAMIR_PREFIX}{}:{}:{}",
+                            self.policy.threshold,
+                            share.index,
+                            hex::encode(share.value.expose())
+                        )
+                        .into()
+                    })
+                    .collect())
+            }
+            SecretInfo::Mnemonic { phrase, .. } => {
+                let mnemonic = Secret::new(
+                    Mnemonic::parse(phrase.expose())
+                        .map_err(|e| VanityError::Shares(format!("bad mnemonic: {e}")))?,
+                );
+                let entropy = Secret::new(mnemonic.expose().to_entropy());
+                Ok(slip39::split(entropy.expose(), self.policy, &mut rng)?
+                    .into_iter()
+                    .map(|words| format!("{SLIP39_PREFIX}{}", words.expose()).into())
+                    .collect())
+            }
+            SecretInfo::Keystore(_) => Err(VanityError::Shares(
+                "keystore results cannot be split into shares".into(),
+            )),
+        }
+    }
+}
+
+impl ResultSink for ShareResultSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        let shares = self.shares(&found.secret).map_err(io::Error::other)?;
+        let network = found.network.map(|n| n.to_string()).unwrap_or_default();
+        let derivation_path = match &found.secret {
+            SecretInfo::Mnemonic {
+                derivation_path, ..
+            } => derivation_path.as_str(),
+            _ => "",
+        };
+        for (writer, share) in self.writers.iter_mut().zip(&shares) {
+            writer
+                .write_record([
+                    found.address.as_str(),
+                    &found.chain,
+                    &network,
+                    derivation_path,
+                    share.expose(),
+                ])
+                .map_err(io::Error::other)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Share file `i` (1-based) of `count`: `keys.csv` becomes `keys.share1.csv`.
+pub fn share_paths(output: &Path, count: u8) -> Vec<PathBuf> {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = output
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..=count)
+        .map(|i| output.with_file_name(format!("{stem}.share{i}{extension}")))
+        .collect()
+}
+
+/// One row of a share file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShareRecord {
+    pub address: String,
+    #[serde(default)]
+    pub chain: String,
+    #[serde(default)]
+    pub network: Option<u32>,
+    #[serde(default)]
+    pub derivation_path: String,
+    pub share: Secret<String>,
+}
+
+/// Read the rows of a share file written by [`ShareResultSink`].
+pub fn read_share_file<R: Read>(reader: R) -> Result<Vec<ShareRecord>> {
+    ReaderBuilder::new()
+        .from_reader(reader)
+        .deserialize()
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| VanityError::Shares(format!("bad share file: {e}")))
+}
+
+/// Recover the secret of one address from its share records.
+pub fn combine_shares(records: &[&ShareRecord]) -> Result<SecretInfo> {
+    let Some(first) = records.first() else {
+        return Err(VanityError::Shares("no shares given".into()));
+    };
+    fn share<'a>(record: &&'a ShareRecord) -> &'a str {
+        record.share.expose()
+    }
+
+    if records.iter().all(|r| share(r).starts_with(SLIP39_PREFIX)) {
+        let mnemonics: Vec<&str> = records
+            .iter()
+            .map(|r| &share(r)[SLIP39_PREFIX.len()..])
+            .collect();
+        let entropy = slip39::combine(&mnemonics, "")?;
+        let mnemonic = Secret::new(
+            Mnemonic::from_entropy(entropy.expose())
+                .map_err(|e| VanityError::Shares(format!("recovered bad entropy: {e}")))?,
+        );
+        return Ok(SecretInfo::Mnemonic {
+            phrase: mnemonic.expose().to_string().into(),
+            derivation_path: first.derivation_path.clone(),
+        });
+    }
+
+    let mut threshold = None;
+    let mut shares = Vec::with_capacity(records.len());
+    for record in records {
+        let (t, index, value) = parse_shamir(share(record))?;
+        if threshold.is_some_and(|known| known != t) {
+            return Err(VanityError::Shares(format!(
+                "shares of {} have different thresholds",
+                first.address
+            )));
+        }
+        threshold = Some(t);
+        shares.push(Share { index, value });
+    }
+    if let Some(threshold) = threshold.filter(|&t| shares.len() < usize::from(t)) {
+        return Err(VanityError::Shares(format!(
+            "{} needs {threshold} shares, got {}",
+            first.address,
+            shares.len()
+        )));
+    }
+    let key = shamir::combine(&shares)?;
+    Ok(SecretInfo::PrivateKey(hex::encode(key.expose()).into()))
+}
+
+/// Split `shamir:<threshold>:<index>:<hex>` into its parts.
+fn parse_shamir(share: &str) -> Result<(u8, u8, Secret<Vec<u8>>)> {
+    let invalid = || VanityError::Shares("expected a shamir: or slip39: share".into());
+    let mut parts = share
+        .strip_prefix(SHAMIR_PREFIX)
+        .ok_or_else(invalid)?
+        .split(':');
+    let mut number = || {
+        parts
+            .next()
+            .and_then(|p| p.parse::<u8>().ok())
+            .ok_or_else(invalid)
+    };
+    let (threshold, index) = (number()?, number()?);
+    let value = parts
+        .next()
+        .and_then(|p| hex::decode(p).ok())
+        .ok_or_else(invalid)?;
+    Ok((threshold, index, value.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_and_read(found: &FoundAddress, name: &str) -> Vec<ShareRecord> {
+        let output = std::env::temp_dir().join(format!("vanity_miner_{name}.csv"));
+        let paths = share_paths(&output, 3);
+        for path in &paths {
+            let _ = std::fs::remove_file(path);
+        }
+        let mut sink = ShareResultSink::new(&paths, "2-of-3".parse().unwrap()).unwrap();
+        sink.handle(found).unwrap();
+        paths
+            .iter()
+            .flat_map(|path| read_share_file(File::open(path).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn names_share_files_after_the_output() {
+        assert_eq!(
+            share_paths(Path::new("out/keys.csv"), 2),
+            [
+                PathBuf::from("out/keys.share1.csv"),
+                PathBuf::from("out/keys.share2.csv")
+            ]
+        );
+    }
+
+    #[test]
+    fn any_two_of_three_shares_recover_keys_and_mnemonics() {
+        let key = FoundAddress::new("0xaddr", SecretInfo::PrivateKey("ab".repeat(32).into()));
+        let records = write_and_read(&key, "shares_key");
+        assert_eq!(records.len(), 3);
+        assert!(records
+            .iter()
+            .all(|r| !r.share.expose().contains(&"ab".repeat(32))));
+        let secret = combine_shares(&[&records[2], &records[0]]).unwrap();
+        assert_eq!(secret.formatted().expose(), &"ab".repeat(32));
+        assert!(combine_shares(&[&records[1]]).is_err());
+
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let mnemonic = FoundAddress::new(
+            "0xaddr",
+            SecretInfo::Mnemonic {
+                phrase: phrase.into(),
+                derivation_path: "m/44'/60'/0'/0/0".into(),
+            },
+        );
+        let records = write_and_read(&mnemonic, "shares_mnemonic");
+        assert!(records[0].share.expose().starts_with(SLIP39_PREFIX));
+        let SecretInfo::Mnemonic {
+            phrase: recovered,
+            derivation_path,
+        } = combine_shares(&[&records[1], &records[2]]).unwrap()
+        else {
+            panic!("expected a mnemonic");
+        };
+        assert_eq!(recovered.expose(), phrase);
+        assert_eq!(derivation_path, "m/44'/60'/0'/0/0");
+    }
+}
@@ -0,0 +1,444 @@
+use std::{fs::OpenOptions, io, path::Path, time::Duration};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension, Row};
+
+use crate::core::types::{FoundAddress, MiningResult, Result, SecretInfo, VanityError};
+
+use super::{
+    permissions::{owner_only, restrict_to_owner},
+    ResultSink,
+};
+
+/// Bumped whenever the tables below change.
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id            INTEGER PRIMARY KEY,
+    started_at    TEXT NOT NULL,
+    stopped_at    TEXT,
+    -- JSON description of what the run mined for.
+    config        TEXT NOT NULL,
+    found         INTEGER,
+    attempts      INTEGER,
+    duration_secs REAL,
+    hashrate      REAL,
+    stop_reason   TEXT,
+    -- JSON array of per-target results.
+    targets       TEXT
+);
+CREATE TABLE IF NOT EXISTS results (
+    id              INTEGER PRIMARY KEY,
+    run_id          INTEGER NOT NULL REFERENCES runs(id),
+    address         TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    chain           TEXT NOT NULL,
+    network         INTEGER,
+    public_key      TEXT NOT NULL,
+    -- private_key, mnemonic or keystore.
+    secret_type     TEXT NOT NULL,
+    -- Key hex, mnemonic phrase or keystore path.
+    secret          TEXT NOT NULL,
+    derivation_path TEXT,
+    target          TEXT,
+    pattern         TEXT NOT NULL,
+    attempt         INTEGER NOT NULL,
+    worker          INTEGER NOT NULL,
+    found_at        TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS results_chain ON results(chain);
+CREATE INDEX IF NOT EXISTS results_found_at ON results(found_at);
+CREATE INDEX IF NOT EXISTS results_run ON results(run_id);
+";
+
+/// Result columns in the order [`stored_result`] reads them.
+const RESULT_COLUMNS: &str = "run_id, address, chain, network, public_key, secret_type, secret, \
+                              derivation_path, target, pattern, attempt, worker, found_at";
+
+/// An SQLite database accumulating the results of many runs.
+///
+/// Every address is stored once: results already in the database are skipped.
+pub struct ResultStore {
+    conn: Connection,
+}
+
+/// Which stored results to return.
+#[derive(Debug, Clone, Default)]
+pub struct ResultFilter {
+    pub chain: Option<String>,
+    /// Substring of the pattern description, e.g. `prefix:dead`.
+    pub pattern: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub run: Option<i64>,
+}
+
+/// A result together with the run that found it.
+#[derive(Debug, Clone)]
+pub struct StoredResult {
+    pub run: i64,
+    pub found: FoundAddress,
+}
+
+/// A row of the runs table.
+#[derive(Debug, Clone)]
+pub struct StoredRun {
+    pub id: i64,
+    pub started_at: String,
+    pub stopped_at: Option<String>,
+    pub config: String,
+    pub found: Option<u64>,
+    pub attempts: Option<u64>,
+    pub stop_reason: Option<String>,
+}
+
+impl ResultStore {
+    /// Open or create the database at `path`, readable by its owner only.
+    pub fn open(path: &Path) -> Result<Self> {
+        // The database holds private keys: create it before SQLite does.
+        owner_only(OpenOptions::new().create(true).append(true)).open(path)?;
+        restrict_to_owner(path)?;
+
+        let conn = Connection::open(path).map_err(failed)?;
+        // Several runs may share one database.
+        conn.busy_timeout(Duration::from_secs(10)).map_err(failed)?;
+
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(failed)?;
+        if version > SCHEMA_VERSION {
+            return Err(VanityError::Database(format!(
+                "{} uses schema v{version}, newer than this version supports (v{SCHEMA_VERSION})",
+                path.display()
+            )));
+        }
+        conn.execute_batch(SCHEMA).map_err(failed)?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(failed)?;
+        Ok(Self { conn })
+    }
+
+    /// Record the start of a run described by `config` (JSON).
+    pub fn begin_run(&self, config: &str) -> Result<i64> {
+        self.conn
+            .execute(
+                "INSERT INTO runs (started_at, config) VALUES (?1, ?2)",
+                params![timestamp(&Utc::now()), config],
+            )
+            .map_err(failed)?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Record how run `run` ended.
+    pub fn finish_run(&self, run: i64, result: &MiningResult) -> Result<()> {
+        let targets: Vec<serde_json::Value> = result
+            .targets
+            .iter()
+            .map(|t| serde_json::json!({"name": t.name, "found": t.found, "limit": t.limit}))
+            .collect();
+        self.conn
+            .execute(
+                "UPDATE runs SET stopped_at = ?2, found = ?3, attempts = ?4, duration_secs = ?5,
+                 hashrate = ?6, stop_reason = ?7, targets = ?8 WHERE id = ?1",
+                params![
+                    run,
+                    timestamp(&Utc::now()),
+                    result.found as i64,
+                    result.attempts as i64,
+                    result.duration_secs,
+                    result.hashrate,
+                    result.stop_reason.to_string(),
+                    serde_json::Value::from(targets).to_string(),
+                ],
+            )
+            .map_err(failed)?;
+        Ok(())
+    }
+
+    /// Store a result of run `run`; returns `false` if its address is
+    /// already in the database.
+    pub fn insert(&self, run: i64, found: &FoundAddress) -> Result<bool> {
+        let (secret_type, secret, derivation_path) = match &found.secret {
+            SecretInfo::PrivateKey(key) => ("private_key", key.expose(), None),
+            SecretInfo::Mnemonic {
+                phrase,
+                derivation_path,
+            } => ("mnemonic", phrase.expose(), Some(derivation_path)),
+            SecretInfo::Keystore(path) => ("keystore", path, None),
+        };
+        let inserted = self
+            .conn
+            .execute(
+                &format!(
+                    "INSERT OR IGNORE INTO results ({RESULT_COLUMNS})
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
+                ),
+                params![
+                    run,
+                    found.address,
+                    found.chain,
+                    found.network,
+                    found.public_key,
+                    secret_type,
+                    secret,
+                    derivation_path,
+                    found.target,
+                    found.pattern,
+                    found.attempt as i64,
+                    found.worker as i64,
+                    timestamp(&found.found_at),
+                ],
+            )
+            .map_err(failed)?;
+        Ok(inserted == 1)
+    }
+
+    /// Store results read from `source` as a run of their own; returns how
+    /// many were new.
+    pub fn import(&self, source: &str, results: &[FoundAddress]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction().map_err(failed)?;
+        let run = self.begin_run(&serde_json::json!({ "import": source }).to_string())?;
+        let mut added = 0;
+        for found in results {
+            added += usize::from(self.insert(run, found)?);
+        }
+        self.conn
+            .execute(
+                "UPDATE runs SET stopped_at = ?2, found = ?3 WHERE id = ?1",
+                params![run, timestamp(&Utc::now()), added as i64],
+            )
+            .map_err(failed)?;
+        tx.commit().map_err(failed)?;
+        Ok(added)
+    }
+
+    /// Stored results matching `filter`, oldest first.
+    pub fn results(&self, filter: &ResultFilter) -> Result<Vec<StoredResult>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(chain) = &filter.chain {
+            values.push(chain.clone().into());
+            conditions.push(format!("chain = ?{}", values.len()));
+        }
+        if let Some(pattern) = &filter.pattern {
+            values.push(pattern.clone().into());
+            conditions.push(format!("instr(pattern, ?{}) > 0", values.len()));
+        }
+        if let Some(since) = &filter.since {
+            values.push(timestamp(since).into());
+            conditions.push(format!("found_at >= ?{}", values.len()));
+        }
+        if let Some(until) = &filter.until {
+            values.push(timestamp(until).into());
+            conditions.push(format!("found_at < ?{}", values.len()));
+        }
+        if let Some(run) = filter.run {
+            values.push(run.into());
+            conditions.push(format!("run_id = ?{}", values.len()));
+        }
+
+        let mut sql = format!("SELECT {RESULT_COLUMNS} FROM results");
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY found_at, id");
+
+        let mut statement = self.conn.prepare(&sql).map_err(failed)?;
+        let rows = statement
+            .query_map(params_from_iter(values), stored_result)
+            .map_err(failed)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(failed)
+    }
+
+    /// All runs, oldest first.
+    pub fn runs(&self) -> Result<Vec<StoredRun>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, started_at, stopped_at, config, found, attempts, stop_reason
+                 FROM runs ORDER BY id",
+            )
+            .map_err(failed)?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok(StoredRun {
+                    id: row.get(0)?,
+                    started_at: row.get(1)?,
+                    stopped_at: row.get(2)?,
+                    config: row.get(3)?,
+                    found: row.get::<_, Option<i64>>(4)?.map(|n| n as u64),
+                    attempts: row.get::<_, Option<i64>>(5)?.map(|n| n as u64),
+                    stop_reason: row.get(6)?,
+                })
+            })
+            .map_err(failed)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(failed)
+    }
+
+    /// Whether `address` is already stored.
+    pub fn contains(&self, address: &str) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT 1 FROM results WHERE address = ?1",
+                [address],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(failed)
+    }
+}
+
+/// Stores every result of one run in a [`ResultStore`].
+pub struct SqliteResultSink {
+    store: ResultStore,
+    run: i64,
+}
+
+impl SqliteResultSink {
+    pub fn new(store: ResultStore, run: i64) -> Self {
+        Self { store, run }
+    }
+}
+
+impl ResultSink for SqliteResultSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        // Addresses found by an earlier run are skipped, not errors.
+        self.store
+            .insert(self.run, found)
+            .map(|_| ())
+            .map_err(io::Error::other)
+    }
+}
+
+fn stored_result(row: &Row<'_>) -> rusqlite::Result<StoredResult> {
+    let secret: String = row.get(6)?;
+    let secret = match row.get::<_, String>(5)?.as_str() {
+        "mnemonic" => SecretInfo::Mnemonic {
+            phrase: secret.into(),
+            derivation_path: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+        },
+        "keystore" => SecretInfo::Keystore(secret),
+        _ => SecretInfo::PrivateKey(secret.into()),
+    };
+    let found_at: String = row.get(12)?;
+    Ok(StoredResult {
+        run: row.get(0)?,
+        found: FoundAddress {
+            address: row.get(1)?,
+            secret,
+            target: row.get(8)?,
+            chain: row.get(2)?,
+            network: row.get(3)?,
+            public_key: row.get(4)?,
+            pattern: row.get(9)?,
+            attempt: row.get::<_, i64>(10)? as u64,
+            worker: row.get::<_, i64>(11)? as usize,
+            found_at: DateTime::parse_from_rfc3339(&found_at)
+                .map(|t| t.to_utc())
+                .unwrap_or_default(),
+        },
+    })
+}
+
+/// Fixed-width UTC timestamps, so they also sort and compare as text.
+fn timestamp(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn failed(e: rusqlite::Error) -> VanityError {
+    VanityError::Database(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::StopReason;
+    use chrono::TimeZone;
+
+    fn found(address: &str, chain: &str, day: u32) -> FoundAddress {
+        let mut found = FoundAddress::new(address, SecretInfo::PrivateKey("deadbeef".into()));
+        found.chain = chain.into();
+        found.pattern = "prefix:dead".into();
+        found.found_at = Utc.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap();
+        found
+    }
+
+    #[test]
+    fn stores_runs_and_skips_duplicate_addresses() {
+        let path = std::env::temp_dir().join("vanity_miner_store_test.sqlite");
+        let _ = std::fs::remove_file(&path);
+
+        let store = ResultStore::open(&path).unwrap();
+        let first = store.begin_run(r#"{"mode":"private-key"}"#).unwrap();
+        let mut sink = SqliteResultSink::new(store, first);
+        sink.handle(&found("0xDEAD01", "ethereum", 1)).unwrap();
+        sink.handle(&found("cfx:aadead", "conflux", 2)).unwrap();
+
+        // A second run finds one of the same addresses again.
+        let store = ResultStore::open(&path).unwrap();
+        let second = store.begin_run("{}").unwrap();
+        assert!(!store
+            .insert(second, &found("0xdead01", "ethereum", 3))
+            .unwrap());
+        let mut mnemonic = found("0xdead02", "ethereum", 3);
+        mnemonic.secret = SecretInfo::Mnemonic {
+            phrase: "abandon about".into(),
+            derivation_path: "m/44'/60'/0'/0/0".into(),
+        };
+        assert!(store.insert(second, &mnemonic).unwrap());
+        store
+            .finish_run(
+                second,
+                &MiningResult {
+                    found: 2,
+                    attempts: 100,
+                    duration_secs: 1.5,
+                    hashrate: 66.7,
+                    targets: Vec::new(),
+                    stop_reason: StopReason::LimitReached,
+                },
+            )
+            .unwrap();
+
+        let all = store.results(&ResultFilter::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(store.contains("0xdead01").unwrap());
+
+        let ethereum = store
+            .results(&ResultFilter {
+                chain: Some("ethereum".into()),
+                since: Some(Utc.with_ymd_and_hms(2026, 10, 2, 0, 0, 0).unwrap()),
+                ..ResultFilter::default()
+            })
+            .unwrap();
+        assert_eq!(ethereum.len(), 1);
+        assert_eq!(ethereum[0].run, second);
+        assert!(matches!(
+            &ethereum[0].found.secret,
+            SecretInfo::Mnemonic { derivation_path, .. } if derivation_path == "m/44'/60'/0'/0/0"
+        ));
+
+        let imported = vec![
+            found("0xdead03", "ethereum", 4),
+            found("0xDEAD02", "ethereum", 4),
+        ];
+        assert_eq!(store.import("old.csv", &imported).unwrap(), 1);
+
+        let runs = store.runs().unwrap();
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[2].config, r#"{"import":"old.csv"}"#);
+        assert_eq!(runs[2].found, Some(1));
+        assert_eq!(runs[0].found, None);
+        assert_eq!(runs[1].found, Some(2));
+        assert_eq!(runs[1].stop_reason.as_deref(), Some("limit"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -2,12 +2,22 @@ use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
-use crate::stats::{StatsSink, StatsSnapshot};
+use crate::{
+    core::{difficulty::Difficulty, range::KeyRange},
+    stats::{StatsSink, StatsSnapshot},
+    utils::format_duration,
+};
 
 /// Console-based stats sink using a single spinner and global average speed.
+///
+/// When a `Difficulty` is attached, the message also shows the expected time
+/// to the next match, the 50%/90%/99% probability times and the current luck.
+/// When a `KeyRange` is attached, it also shows how much of it was scanned.
 #[derive(Debug)]
 pub struct ConsoleStatsSink {
     progress: ProgressBar,
+    difficulty: Option<Difficulty>,
+    range: Option<KeyRange>,
 }
 
 impl ConsoleStatsSink {
@@ -20,21 +30,79 @@ impl ConsoleStatsSink {
 
         progress.enable_steady_tick(Duration::from_millis(200));
 
-        Self { progress }
+        Self {
+            progress,
+            difficulty: None,
+            range: None,
+        }
+    }
+
+    pub fn with_difficulty(mut self, difficulty: Option<Difficulty>) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    pub fn with_range(mut self, range: Option<KeyRange>) -> Self {
+        self.range = range;
+        self
+    }
+
+    fn estimate_message(&self, current: &StatsSnapshot, rate: f64) -> Option<String> {
+        let difficulty = self.difficulty?;
+        let eta = |target: f64| {
+            difficulty
+                .time_for_probability(target, rate)
+                .map(format_duration)
+                .unwrap_or_else(|| "-".into())
+        };
+        let expected = difficulty
+            .expected_time(rate)
+            .map(format_duration)
+            .unwrap_or_else(|| "-".into());
+
+        Some(format!(
+            "ETA: {} (50%: {}, 90%: {}, 99%: {}) | Luck: {:.1}%",
+            expected,
+            eta(0.5),
+            eta(0.9),
+            eta(0.99),
+            difficulty.probability_after(current.attempts) * 100.0
+        ))
     }
 }
 
 impl StatsSink for ConsoleStatsSink {
-    fn update(&mut self, current: &StatsSnapshot, _previous: &StatsSnapshot) {
+    fn update(&mut self, current: &StatsSnapshot, previous: &StatsSnapshot) {
         self.progress.tick();
 
         // Global average speed from the beginning.
         let avg_speed = current.hashrate();
 
-        self.progress.set_message(format!(
+        let mut message = format!(
             "Avg: {:.0} addr/s | Total attempts: {} | Total found: {}",
             avg_speed, current.attempts, current.found
-        ));
+        );
+
+        // Estimates follow the live rate, falling back to the average while
+        // the interval speed is not available yet.
+        let live_speed = current.calculate_speed(previous) as f64;
+        let rate = if live_speed > 0.0 {
+            live_speed
+        } else {
+            avg_speed
+        };
+        if let Some(estimate) = self.estimate_message(current, rate) {
+            message.push_str(" | ");
+            message.push_str(&estimate);
+        }
+        if let Some(range) = &self.range {
+            message.push_str(&format!(
+                " | Range: {:.2}%",
+                range.progress(current.attempts) * 100.0
+            ));
+        }
+
+        self.progress.set_message(message);
     }
 
     fn on_stop(&mut self, final_snapshot: &StatsSnapshot) {
@@ -65,4 +133,22 @@ mod tests {
         sink.update(&current, &prev);
         sink.on_stop(&current);
     }
+
+    #[test]
+    fn estimate_message_reports_eta_and_luck() {
+        let stats = Arc::new(MiningStats::new());
+        let sink = ConsoleStatsSink::new(ProgressBar::hidden())
+            .with_difficulty(Difficulty::from_probability(1.0 / 1000.0));
+
+        stats.add_attempts(1000);
+        let snapshot = stats.get_snapshot();
+
+        let message = sink.estimate_message(&snapshot, 100.0).expect("estimate");
+        assert!(message.starts_with("ETA: 10s"));
+        assert!(message.contains("99%: 46s"));
+        assert!(message.contains("Luck: 63."));
+
+        let without = ConsoleStatsSink::new(ProgressBar::hidden());
+        assert!(without.estimate_message(&snapshot, 100.0).is_none());
+    }
 }
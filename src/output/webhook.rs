@@ -0,0 +1,408 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::core::{secret::Secret, types::FoundAddress};
+
+use super::{
+    jsonl::result_json,
+    permissions::{owner_only, restrict_to_owner},
+    ResultSink,
+};
+
+/// Where and how results are POSTed.
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    url: String,
+    redact: bool,
+    spool: Option<PathBuf>,
+    retries: u32,
+    backoff: Duration,
+    timeout: Duration,
+}
+
+impl Webhook {
+    /// POST to `url` with 5 retries, backing off from one second.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            redact: false,
+            spool: None,
+            retries: 5,
+            backoff: Duration::from_secs(1),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Send only the address and metadata, never the secret.
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact = redact;
+        self
+    }
+
+    /// Append results that could not be delivered to `path` (JSON Lines) and
+    /// send them once the endpoint answers again.
+    pub fn with_spool(mut self, path: impl Into<PathBuf>) -> Self {
+        self.spool = Some(path.into());
+        self
+    }
+
+    /// Retry a failed POST `retries` times, waiting `backoff`, then twice
+    /// as long each time.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the secret is left out of the payloads.
+    pub fn redacts(&self) -> bool {
+        self.redact
+    }
+}
+
+/// POSTs each result as a JSON object (the JSONL layout) to a webhook.
+///
+/// Delivery runs on a thread of its own, so a slow or unreachable endpoint
+/// never holds up the other sinks. Dropping the sink waits for the results
+/// handed to it: pending ones get a last attempt and are spooled if that
+/// fails too.
+pub struct WebhookResultSink {
+    redact: bool,
+    sender: Option<Sender<Secret<Vec<u8>>>>,
+    /// Never used to send: dropping it cuts the worker's backoff short.
+    stop: Option<Sender<()>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl WebhookResultSink {
+    /// Start the delivery thread; it first sends what an earlier run spooled.
+    pub fn new(webhook: Webhook) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let (stop, stopped) = mpsc::channel();
+        let redact = webhook.redact;
+        let worker = thread::Builder::new()
+            .name("webhook".into())
+            .spawn(move || Delivery::new(webhook, stopped).run(receiver))?;
+        Ok(Self {
+            redact,
+            sender: Some(sender),
+            stop: Some(stop),
+            worker: Some(worker),
+        })
+    }
+}
+
+impl ResultSink for WebhookResultSink {
+    fn handle(&mut self, found: &FoundAddress) -> io::Result<()> {
+        let body = result_json(found, self.redact)?;
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(body).ok())
+            .ok_or_else(|| io::Error::other("the webhook delivery thread stopped"))
+    }
+}
+
+impl Drop for WebhookResultSink {
+    fn drop(&mut self) {
+        // Closing the channels tells the worker to finish up.
+        self.sender.take();
+        self.stop.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Outcome of one POST.
+enum Sent {
+    Delivered,
+    /// Worth retrying: the endpoint is down, overloaded or timed out.
+    Failed(String),
+    /// The endpoint refused the request; retrying will not help.
+    Rejected(String),
+}
+
+/// The delivery thread.
+struct Delivery {
+    webhook: Webhook,
+    agent: ureq::Agent,
+    /// Disconnected once the sink is dropped.
+    stopped: Receiver<()>,
+    closing: bool,
+    /// Whether the spool may hold results.
+    spooled: bool,
+}
+
+impl Delivery {
+    fn new(webhook: Webhook, stopped: Receiver<()>) -> Self {
+        let agent = ureq::AgentBuilder::new().timeout(webhook.timeout).build();
+        let spooled = webhook.spool.as_deref().is_some_and(Path::exists);
+        Self {
+            webhook,
+            agent,
+            stopped,
+            closing: false,
+            spooled,
+        }
+    }
+
+    fn run(mut self, receiver: Receiver<Secret<Vec<u8>>>) {
+        self.replay_spool();
+        while let Ok(body) = receiver.recv() {
+            self.deliver(&body);
+        }
+    }
+
+    /// POST `body`, retrying with backoff, and spool it if every attempt
+    /// fails. Once the sink is closing, a failed attempt is the last one.
+    fn deliver(&mut self, body: &Secret<Vec<u8>>) {
+        let mut backoff = self.webhook.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.post(body.expose()) {
+                Sent::Delivered => {
+                    if self.spooled {
+                        // The endpoint is back: catch up on what it missed.
+                        self.replay_spool();
+                    }
+                    return;
+                }
+                Sent::Rejected(reason) => {
+                    eprintln!("Webhook rejected a result: {reason}");
+                    return;
+                }
+                Sent::Failed(reason) if attempt >= self.webhook.retries || self.closing => {
+                    self.spool(body, &reason);
+                    return;
+                }
+                Sent::Failed(_) => {
+                    if let Err(RecvTimeoutError::Disconnected) = self.stopped.recv_timeout(backoff)
+                    {
+                        self.closing = true;
+                    }
+                    backoff = backoff.saturating_mul(2);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn post(&self, body: &[u8]) -> Sent {
+        let request = self
+            .agent
+            .post(&self.webhook.url)
+            .set("Content-Type", "application/json");
+        match request.send_bytes(body) {
+            Ok(_) => Sent::Delivered,
+            Err(ureq::Error::Status(code @ (408 | 429), _)) => Sent::Failed(format!("HTTP {code}")),
+            Err(ureq::Error::Status(code, _)) if code < 500 => {
+                Sent::Rejected(format!("HTTP {code}"))
+            }
+            Err(ureq::Error::Status(code, _)) => Sent::Failed(format!("HTTP {code}")),
+            Err(e) => Sent::Failed(e.to_string()),
+        }
+    }
+
+    fn spool(&mut self, body: &Secret<Vec<u8>>, reason: &str) {
+        let Some(path) = &self.webhook.spool else {
+            eprintln!("Webhook delivery failed ({reason}); the result was not sent");
+            return;
+        };
+        let written = append_line(path, body.expose());
+        match written {
+            Ok(()) => {
+                self.spooled = true;
+                eprintln!(
+                    "Webhook delivery failed ({reason}); spooled to {}",
+                    path.display()
+                );
+            }
+            Err(e) => eprintln!(
+                "Webhook delivery failed ({reason}) and spooling to {} failed: {e}",
+                path.display()
+            ),
+        }
+    }
+
+    /// Send the spooled results once each, keeping those that still fail.
+    fn replay_spool(&mut self) {
+        let Some(path) = self.webhook.spool.clone() else {
+            return;
+        };
+        self.spooled = false;
+        let lines = match File::open(&path) {
+            Ok(file) => BufReader::new(file).lines().collect::<io::Result<Vec<_>>>(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+            Err(e) => Err(e),
+        };
+        let lines = match lines {
+            Ok(lines) => Secret::new(lines),
+            Err(e) => {
+                self.spooled = true;
+                eprintln!("Cannot read webhook spool {}: {e}", path.display());
+                return;
+            }
+        };
+
+        let mut kept = Secret::new(Vec::new());
+        for line in lines.expose().iter().filter(|l| !l.trim().is_empty()) {
+            match self.post(line.as_bytes()) {
+                Sent::Delivered => {}
+                Sent::Rejected(reason) => eprintln!("Webhook rejected a spooled result: {reason}"),
+                Sent::Failed(_) => {
+                    kept.expose_mut().extend_from_slice(line.as_bytes());
+                    kept.expose_mut().push(b'\n');
+                }
+            }
+        }
+
+        let rewritten = if kept.expose().is_empty() {
+            fs::remove_file(&path)
+        } else {
+            self.spooled = true;
+            replace_file(&path, kept.expose())
+        };
+        if let Err(e) = rewritten {
+            self.spooled = true;
+            eprintln!("Cannot update webhook spool {}: {e}", path.display());
+        }
+    }
+}
+
+/// Append `line` to the owner-only file at `path`.
+fn append_line(path: &Path, line: &[u8]) -> io::Result<()> {
+    let mut file = owner_only(OpenOptions::new().create(true).append(true)).open(path)?;
+    restrict_to_owner(path)?;
+    let mut record = Secret::new(Vec::with_capacity(line.len() + 1));
+    record.expose_mut().extend_from_slice(line);
+    record.expose_mut().push(b'\n');
+    file.write_all(record.expose())
+}
+
+/// Swap `contents` in for the file at `path` through a temporary file.
+fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    let mut file =
+        owner_only(OpenOptions::new().write(true).create(true).truncate(true)).open(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::SecretInfo;
+    use std::{net::TcpListener, time::Instant};
+    use tiny_http::{Response, Server};
+
+    /// A stand-in endpoint that answers with `statuses` in turn (then 200)
+    /// and hands back the bodies it received.
+    fn endpoint(statuses: Vec<u16>, requests: usize) -> (String, JoinHandle<Vec<String>>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", server.server_addr());
+        let handle = thread::spawn(move || {
+            let mut bodies = Vec::new();
+            let mut statuses = statuses.into_iter();
+            for _ in 0..requests {
+                let Ok(Some(mut request)) = server.recv_timeout(Duration::from_secs(10)) else {
+                    break;
+                };
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let status = statuses.next().unwrap_or(200);
+                if status == 200 {
+                    bodies.push(body);
+                }
+                request.respond(Response::empty(status)).unwrap();
+            }
+            bodies
+        });
+        (url, handle)
+    }
+
+    fn found(address: &str) -> FoundAddress {
+        FoundAddress::new(address, SecretInfo::PrivateKey("ab".repeat(32).into()))
+    }
+
+    fn fast(webhook: Webhook) -> Webhook {
+        webhook.with_retries(3, Duration::from_millis(5))
+    }
+
+    #[test]
+    fn posts_results_and_redacts_secrets() {
+        let (url, server) = endpoint(vec![], 2);
+        let mut sink = WebhookResultSink::new(fast(Webhook::new(&url))).unwrap();
+        sink.handle(&found("0xfull")).unwrap();
+        drop(sink);
+        let mut redacted =
+            WebhookResultSink::new(fast(Webhook::new(url)).with_redaction(true)).unwrap();
+        redacted.handle(&found("0xredacted")).unwrap();
+        drop(redacted);
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 2);
+        let full: serde_json::Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(full["address"], "0xfull");
+        assert_eq!(full["secret"]["key"], "ab".repeat(32));
+        let redacted: serde_json::Value = serde_json::from_str(&bodies[1]).unwrap();
+        assert_eq!(redacted["address"], "0xredacted");
+        assert!(redacted.get("secret").is_none());
+        assert!(!bodies[1].contains("abab"));
+    }
+
+    #[test]
+    fn retries_failed_posts() {
+        let (url, server) = endpoint(vec![503, 500], 3);
+        let mut sink = WebhookResultSink::new(fast(Webhook::new(url))).unwrap();
+        sink.handle(&found("0xretried")).unwrap();
+        // Kept open: dropping the sink would cut the retries short.
+        let bodies = server.join().unwrap();
+        drop(sink);
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].contains("0xretried"));
+    }
+
+    #[test]
+    fn spools_while_down_and_replays_later() {
+        let spool = std::env::temp_dir().join("vanity_miner_webhook_spool.jsonl");
+        let _ = fs::remove_file(&spool);
+        // A port nothing listens on.
+        let down = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/hook", listener.local_addr().unwrap())
+        };
+
+        let slow = Webhook::new(down)
+            .with_spool(&spool)
+            .with_retries(2, Duration::from_millis(200));
+        let mut sink = WebhookResultSink::new(slow).unwrap();
+        let started = Instant::now();
+        sink.handle(&found("0xspooled")).unwrap();
+        // Handing over a result never waits for the endpoint.
+        assert!(started.elapsed() < Duration::from_millis(100));
+        drop(sink);
+        assert!(fs::read_to_string(&spool).unwrap().contains("0xspooled"));
+
+        let (url, server) = endpoint(vec![], 2);
+        let mut sink = WebhookResultSink::new(fast(Webhook::new(url)).with_spool(&spool)).unwrap();
+        sink.handle(&found("0xfresh")).unwrap();
+        drop(sink);
+        let bodies = server.join().unwrap();
+        assert!(bodies[0].contains("0xspooled"));
+        assert!(bodies[1].contains("0xfresh"));
+        assert!(!spool.exists());
+    }
+}
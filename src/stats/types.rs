@@ -11,6 +11,8 @@ static RELAXED: Ordering = Ordering::Relaxed;
 #[derive(Debug)]
 pub struct MiningStats {
     start_time: Instant,
+    /// Time spent in earlier sessions of a resumed run, in milliseconds.
+    prior_elapsed_ms: AtomicU64,
     /// Total number of attempts made so far.
     pub attempt_count: AtomicU64,
     /// Total number of matching addresses found so far.
@@ -21,6 +23,7 @@ impl MiningStats {
     pub fn new() -> Self {
         Self {
             start_time: Instant::now(),
+            prior_elapsed_ms: AtomicU64::new(0),
             attempt_count: AtomicU64::new(0),
             found_count: AtomicU64::new(0),
         }
@@ -30,8 +33,18 @@ impl MiningStats {
         self.start_time
     }
 
+    /// Time since start, including earlier sessions of a resumed run.
     pub fn elapsed(&self) -> Duration {
-        self.start_time.elapsed()
+        Duration::from_millis(self.prior_elapsed_ms.load(RELAXED)) + self.start_time.elapsed()
+    }
+
+    /// Continue counting from an earlier session, e.g. after loading a
+    /// checkpoint. Replaces the current totals.
+    pub fn restore(&self, attempts: u64, found: u64, elapsed: Duration) {
+        self.attempt_count.store(attempts, RELAXED);
+        self.found_count.store(found, RELAXED);
+        self.prior_elapsed_ms
+            .store(elapsed.as_millis().try_into().unwrap_or(u64::MAX), RELAXED);
     }
 
     pub fn attempts(&self) -> u64 {
@@ -85,7 +98,8 @@ pub struct StatsSnapshot {
     pub found: u64,
     /// Wall-clock time when the snapshot was taken.
     pub timestamp: Instant,
-    /// Elapsed time since the corresponding `MiningStats` was created.
+    /// Elapsed time since the corresponding `MiningStats` was created,
+    /// including earlier sessions of a resumed run.
     pub elapsed: Duration,
 }
 
@@ -186,4 +200,16 @@ mod tests {
         assert_eq!(snapshot.attempts, 10);
         assert_eq!(snapshot.found, 5);
     }
+
+    #[test]
+    fn restore_continues_from_earlier_session() {
+        let stats = MiningStats::new();
+        stats.restore(1_000, 3, Duration::from_secs(60));
+        stats.add_attempts(10);
+
+        let snapshot = stats.get_snapshot();
+        assert_eq!(snapshot.attempts, 1_010);
+        assert_eq!(snapshot.found, 3);
+        assert!(snapshot.elapsed >= Duration::from_secs(60));
+    }
 }
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const YEAR: u64 = 365 * DAY;
+
+/// Formats a duration with its two most significant units, e.g. `3h 12m`.
+///
+/// Intended for human-facing estimates, so sub-second precision is dropped.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs == 0 && !duration.is_zero() {
+        return "<1s".into();
+    }
+
+    if secs >= 1_000 * YEAR {
+        return format!("{:.2e} years", secs as f64 / YEAR as f64);
+    }
+
+    let units = [
+        (YEAR, "y"),
+        (DAY, "d"),
+        (HOUR, "h"),
+        (MINUTE, "m"),
+        (1, "s"),
+    ];
+    let mut parts = Vec::with_capacity(2);
+    let mut rest = secs;
+
+    for (size, suffix) in units {
+        if rest >= size || (parts.is_empty() && size == 1) {
+            parts.push(format!("{}{}", rest / size, suffix));
+            rest %= size;
+        } else if !parts.is_empty() {
+            break;
+        }
+
+        if parts.len() == 2 {
+            break;
+        }
+    }
+
+    parts.join(" ")
+}
+
+/// Parses a human duration such as `90s`, `15m`, `2h`, `1d` or `1h30m`.
+///
+/// A bare number is taken as seconds.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("duration is empty".into());
+    }
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => MINUTE,
+            'h' => HOUR,
+            'd' => DAY,
+            'w' => 7 * DAY,
+            _ => return Err(format!("unknown duration unit '{c}' in \"{input}\"")),
+        };
+        let value: u64 = digits
+            .parse()
+            .map_err(|_| format!("missing number before '{c}' in \"{input}\""))?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(|| format!("duration \"{input}\" is too long"))?;
+        digits.clear();
+    }
+
+    if !digits.is_empty() {
+        return Err(format!("missing unit after {digits} in \"{input}\""));
+    }
+
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_two_most_significant_units() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0s");
+        assert_eq!(format_duration(Duration::from_millis(300)), "<1s");
+        assert_eq!(format_duration(Duration::from_secs(59)), "59s");
+        assert_eq!(format_duration(Duration::from_secs(3_725)), "1h 2m");
+        assert_eq!(format_duration(Duration::from_secs(2 * DAY + 5)), "2d");
+        assert_eq!(format_duration(Duration::from_secs(YEAR + DAY)), "1y 1d");
+        assert!(format_duration(Duration::from_secs(u64::MAX)).ends_with("years"));
+    }
+
+    #[test]
+    fn parses_human_durations() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(15 * MINUTE)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(2 * HOUR)));
+        assert_eq!(
+            parse_duration("1h30m"),
+            Ok(Duration::from_secs(HOUR + 30 * MINUTE))
+        );
+        assert_eq!(parse_duration("1D"), Ok(Duration::from_secs(DAY)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("1h30").is_err());
+    }
+}
@@ -0,0 +1,470 @@
+//! Keccak-256 over several inputs at once.
+//!
+//! Equal-length inputs are hashed side by side, one message per SIMD lane:
+//! four with AVX2, two with NEON. Anything else, and CPUs without those
+//! features, use the scalar permutation one input at a time. The backend is
+//! picked at runtime.
+
+/// Bytes absorbed per Keccak-f[1600] call for a 256-bit output.
+const RATE: usize = 136;
+
+/// Most inputs hashed in one pass by any backend.
+pub const MAX_LANES: usize = 4;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation of lane `x + 5y` in the rho step.
+const RHO: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Implementation used for multi-lane hashing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+impl Backend {
+    /// The fastest backend this CPU supports.
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        if std::arch::is_x86_feature_detected!("avx2") {
+            return Backend::Avx2;
+        }
+        #[cfg(target_arch = "aarch64")]
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return Backend::Neon;
+        }
+        Backend::Scalar
+    }
+
+    /// Whether this CPU has the instructions the backend needs.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => std::arch::is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+        }
+    }
+
+    /// Inputs hashed per pass.
+    pub fn lanes(self) -> usize {
+        match self {
+            Backend::Scalar => 1,
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => avx2::Lanes::WIDTH,
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon => neon::Lanes::WIDTH,
+        }
+    }
+}
+
+/// Keccak-256 of a single input.
+pub fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut out = [[0u8; 32]];
+    hash_lanes::<u64>(&[input], &mut out);
+    out[0]
+}
+
+/// Keccak-256 of each input into the matching slot of `out`.
+///
+/// Panics if `out` is shorter than `inputs`.
+pub fn keccak256_batch(inputs: &[&[u8]], out: &mut [[u8; 32]]) {
+    keccak256_batch_with(Backend::detect(), inputs, out);
+}
+
+/// `keccak256_batch` on an explicit backend. Backends this CPU does not
+/// support fall back to the scalar one.
+pub fn keccak256_batch_with(backend: Backend, inputs: &[&[u8]], out: &mut [[u8; 32]]) {
+    assert!(out.len() >= inputs.len(), "output slice too short");
+
+    let backend = if backend.is_supported() {
+        backend
+    } else {
+        Backend::Scalar
+    };
+
+    let lanes = backend.lanes();
+    let mut done = 0;
+    while done < inputs.len() {
+        let chunk = &inputs[done..(done + lanes).min(inputs.len())];
+        let same_len = chunk.iter().all(|i| i.len() == chunk[0].len());
+
+        if chunk.len() == lanes && same_len {
+            let out = &mut out[done..done + lanes];
+            match backend {
+                Backend::Scalar => hash_lanes::<u64>(chunk, out),
+                // SAFETY: `is_supported` checked above that the CPU has AVX2.
+                #[cfg(target_arch = "x86_64")]
+                Backend::Avx2 => unsafe { avx2::hash(chunk, out) },
+                // SAFETY: `is_supported` checked above that the CPU has NEON.
+                #[cfg(target_arch = "aarch64")]
+                Backend::Neon => unsafe { neon::hash(chunk, out) },
+            }
+            done += lanes;
+        } else {
+            // Short tail or mixed lengths: one at a time.
+            hash_lanes::<u64>(&inputs[done..done + 1], &mut out[done..done + 1]);
+            done += 1;
+        }
+    }
+}
+
+/// A vector of Keccak lanes, one 64-bit word per message.
+trait Lanes: Copy {
+    const WIDTH: usize;
+
+    fn splat(word: u64) -> Self;
+    /// Gather word `i` of every message.
+    fn load(words: &[u64]) -> Self;
+    fn store(self, words: &mut [u64]);
+    fn xor(self, other: Self) -> Self;
+    /// `!self & other`
+    fn andnot(self, other: Self) -> Self;
+    fn rotl(self, n: u32) -> Self;
+}
+
+impl Lanes for u64 {
+    const WIDTH: usize = 1;
+
+    #[inline(always)]
+    fn splat(word: u64) -> Self {
+        word
+    }
+
+    #[inline(always)]
+    fn load(words: &[u64]) -> Self {
+        words[0]
+    }
+
+    #[inline(always)]
+    fn store(self, words: &mut [u64]) {
+        words[0] = self;
+    }
+
+    #[inline(always)]
+    fn xor(self, other: Self) -> Self {
+        self ^ other
+    }
+
+    #[inline(always)]
+    fn andnot(self, other: Self) -> Self {
+        !self & other
+    }
+
+    #[inline(always)]
+    fn rotl(self, n: u32) -> Self {
+        self.rotate_left(n)
+    }
+}
+
+/// Keccak-f[1600] applied to every lane of `state`.
+#[inline(always)]
+fn keccak_f<L: Lanes>(a: &mut [L; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let mut c = [L::splat(0); 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            *c = a[x]
+                .xor(a[x + 5])
+                .xor(a[x + 10])
+                .xor(a[x + 15])
+                .xor(a[x + 20]);
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5].xor(c[(x + 1) % 5].rotl(1));
+            for y in 0..5 {
+                a[x + 5 * y] = a[x + 5 * y].xor(d);
+            }
+        }
+
+        // rho and pi
+        let mut b = [L::splat(0); 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = a[x + 5 * y].rotl(RHO[x + 5 * y]);
+            }
+        }
+
+        // chi
+        for y in 0..5 {
+            for x in 0..5 {
+                a[x + 5 * y] =
+                    b[x + 5 * y].xor(b[(x + 1) % 5 + 5 * y].andnot(b[(x + 2) % 5 + 5 * y]));
+            }
+        }
+
+        // iota
+        a[0] = a[0].xor(L::splat(rc));
+    }
+}
+
+/// Hash `L::WIDTH` equal-length inputs, one per lane.
+#[inline(always)]
+fn hash_lanes<L: Lanes>(inputs: &[&[u8]], out: &mut [[u8; 32]]) {
+    debug_assert_eq!(inputs.len(), L::WIDTH);
+    let len = inputs[0].len();
+    let mut state = [L::splat(0); 25];
+    let mut words = [0u64; MAX_LANES];
+
+    // Every input gets the same padding, so all lanes absorb the same
+    // number of blocks; the last one holds the 0x01 .. 0x80 padding.
+    let blocks = len / RATE + 1;
+    for block in 0..blocks {
+        let start = block * RATE;
+        let mut padded = [[0u8; RATE]; MAX_LANES];
+        for (lane, input) in inputs.iter().enumerate() {
+            let chunk = &input[start..(start + RATE).min(len)];
+            padded[lane][..chunk.len()].copy_from_slice(chunk);
+            if block + 1 == blocks {
+                padded[lane][chunk.len()] |= 0x01;
+                padded[lane][RATE - 1] |= 0x80;
+            }
+        }
+
+        for (i, lane_state) in state.iter_mut().take(RATE / 8).enumerate() {
+            for (lane, word) in words.iter_mut().take(L::WIDTH).enumerate() {
+                *word = u64::from_le_bytes(
+                    padded[lane][i * 8..i * 8 + 8]
+                        .try_into()
+                        .expect("8-byte word"),
+                );
+            }
+            *lane_state = lane_state.xor(L::load(&words));
+        }
+        keccak_f(&mut state);
+    }
+
+    for (i, lane_state) in state.iter().take(4).enumerate() {
+        lane_state.store(&mut words);
+        for (lane, word) in words.iter().take(L::WIDTH).enumerate() {
+            out[lane][i * 8..i * 8 + 8].copy_from_slice(&word.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    /// Four Keccak lanes in one 256-bit register.
+    #[derive(Clone, Copy)]
+    pub(super) struct Lanes(__m256i);
+
+    impl super::Lanes for Lanes {
+        const WIDTH: usize = 4;
+
+        #[inline(always)]
+        fn splat(word: u64) -> Self {
+            // SAFETY: only reached from `hash`, which requires AVX2.
+            unsafe { Lanes(_mm256_set1_epi64x(word as i64)) }
+        }
+
+        #[inline(always)]
+        fn load(words: &[u64]) -> Self {
+            assert!(words.len() >= 4);
+            // SAFETY: `words` holds at least four u64s; AVX2 as above.
+            unsafe { Lanes(_mm256_loadu_si256(words.as_ptr().cast())) }
+        }
+
+        #[inline(always)]
+        fn store(self, words: &mut [u64]) {
+            assert!(words.len() >= 4);
+            // SAFETY: `words` holds at least four u64s; AVX2 as above.
+            unsafe { _mm256_storeu_si256(words.as_mut_ptr().cast(), self.0) }
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            // SAFETY: AVX2 as above.
+            unsafe { Lanes(_mm256_xor_si256(self.0, other.0)) }
+        }
+
+        #[inline(always)]
+        fn andnot(self, other: Self) -> Self {
+            // SAFETY: AVX2 as above.
+            unsafe { Lanes(_mm256_andnot_si256(self.0, other.0)) }
+        }
+
+        #[inline(always)]
+        fn rotl(self, n: u32) -> Self {
+            if n == 0 {
+                return self;
+            }
+            // SAFETY: AVX2 as above.
+            unsafe {
+                let left = _mm256_sll_epi64(self.0, _mm_cvtsi32_si128(n as i32));
+                let right = _mm256_srl_epi64(self.0, _mm_cvtsi32_si128(64 - n as i32));
+                Lanes(_mm256_or_si256(left, right))
+            }
+        }
+    }
+
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn hash(inputs: &[&[u8]], out: &mut [[u8; 32]]) {
+        super::hash_lanes::<Lanes>(inputs, out)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use std::arch::aarch64::*;
+
+    /// Two Keccak lanes in one 128-bit register.
+    #[derive(Clone, Copy)]
+    pub(super) struct Lanes(uint64x2_t);
+
+    impl super::Lanes for Lanes {
+        const WIDTH: usize = 2;
+
+        #[inline(always)]
+        fn splat(word: u64) -> Self {
+            // SAFETY: only reached from `hash`, which requires NEON.
+            unsafe { Lanes(vdupq_n_u64(word)) }
+        }
+
+        #[inline(always)]
+        fn load(words: &[u64]) -> Self {
+            assert!(words.len() >= 2);
+            // SAFETY: `words` holds at least two u64s; NEON as above.
+            unsafe { Lanes(vld1q_u64(words.as_ptr())) }
+        }
+
+        #[inline(always)]
+        fn store(self, words: &mut [u64]) {
+            assert!(words.len() >= 2);
+            // SAFETY: `words` holds at least two u64s; NEON as above.
+            unsafe { vst1q_u64(words.as_mut_ptr(), self.0) }
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            // SAFETY: NEON as above.
+            unsafe { Lanes(veorq_u64(self.0, other.0)) }
+        }
+
+        #[inline(always)]
+        fn andnot(self, other: Self) -> Self {
+            // SAFETY: NEON as above. `vbicq(a, b)` is `a & !b`.
+            unsafe { Lanes(vbicq_u64(other.0, self.0)) }
+        }
+
+        #[inline(always)]
+        fn rotl(self, n: u32) -> Self {
+            if n == 0 {
+                return self;
+            }
+            // SAFETY: NEON as above. Negative shift counts shift right.
+            unsafe {
+                let left = vshlq_u64(self.0, vdupq_n_s64(i64::from(n)));
+                let right = vshlq_u64(self.0, vdupq_n_s64(i64::from(n) - 64));
+                Lanes(vorrq_u64(left, right))
+            }
+        }
+    }
+
+    /// # Safety
+    /// The CPU must support NEON.
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn hash(inputs: &[&[u8]], out: &mut [[u8; 32]]) {
+        super::hash_lanes::<Lanes>(inputs, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha3::{Digest, Keccak256};
+
+    fn reference(input: &[u8]) -> [u8; 32] {
+        Keccak256::digest(input).into()
+    }
+
+    fn message(len: usize, seed: u8) -> Vec<u8> {
+        (0..len)
+            .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+            .collect()
+    }
+
+    #[test]
+    fn scalar_matches_known_answers() {
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        for len in [1, 64, RATE - 1, RATE, RATE + 1, 3 * RATE + 7] {
+            let input = message(len, 7);
+            assert_eq!(keccak256(&input), reference(&input), "length {len}");
+        }
+    }
+
+    #[test]
+    fn every_backend_matches_single_hashes() {
+        assert!(Backend::detect().is_supported());
+
+        // Unsupported backends fall back to scalar, so every one is safe to call.
+        for backend in [
+            Backend::Scalar,
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2,
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon,
+        ] {
+            for len in [0, 64, RATE, RATE + 1, 300] {
+                let messages: Vec<Vec<u8>> = (0..9).map(|s| message(len, s)).collect();
+                let inputs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+                let mut out = vec![[0u8; 32]; inputs.len()];
+
+                keccak256_batch_with(backend, &inputs, &mut out);
+                for (input, hash) in inputs.iter().zip(&out) {
+                    assert_eq!(*hash, reference(input), "{backend:?}, length {len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mixed_lengths_fall_back_to_single_hashes() {
+        let messages: Vec<Vec<u8>> = (0..6).map(|i| message(60 + i, i as u8)).collect();
+        let inputs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+        let mut out = vec![[0u8; 32]; inputs.len()];
+
+        keccak256_batch(&inputs, &mut out);
+        for (input, hash) in inputs.iter().zip(&out) {
+            assert_eq!(*hash, reference(input));
+        }
+    }
+}
@@ -1 +1,4 @@
+mod duration;
+pub mod keccak;
 
+pub use duration::{format_duration, parse_duration};